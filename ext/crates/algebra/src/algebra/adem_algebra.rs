@@ -1590,7 +1590,9 @@ mod tests {
                         "{}",
                         ModuleFailedRelationError {
                             relation: relation_string,
-                            value: value_string
+                            value: value_string,
+                            degree: 0,
+                            element: "1".to_string(),
                         }
                     );
                 }
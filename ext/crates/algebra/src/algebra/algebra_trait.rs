@@ -211,6 +211,11 @@ pub trait Algebra: std::fmt::Display + Send + Sync + 'static {
 
 #[enum_dispatch]
 pub trait UnstableAlgebra: Algebra {
+    /// Whether the unstable operations are defined. If not, the other methods panic.
+    fn has_unstable_operations(&self) -> bool {
+        true
+    }
+
     fn dimension_unstable(&self, degree: i32, excess: i32) -> usize;
 
     fn multiply_basis_elements_unstable(
@@ -315,6 +320,10 @@ pub trait UnstableAlgebra: Algebra {
 /// [`UnstableAlgebra`] implements `MuAlgebra<true>`. This makes it possible to write code that is
 /// generic over stable and unstable algebras.
 pub trait MuAlgebra<const U: bool>: Algebra {
+    /// Whether the operations are defined. This is always the case when `U` is false, and is
+    /// [`UnstableAlgebra::has_unstable_operations`] otherwise.
+    fn has_unstable_operations(&self) -> bool;
+
     fn dimension_unstable(&self, degree: i32, excess: i32) -> usize;
 
     fn multiply_basis_elements_unstable(
@@ -371,6 +380,10 @@ pub trait MuAlgebra<const U: bool>: Algebra {
 }
 
 impl<A: Algebra> MuAlgebra<false> for A {
+    fn has_unstable_operations(&self) -> bool {
+        true
+    }
+
     fn dimension_unstable(&self, degree: i32, _excess: i32) -> usize {
         self.dimension(degree)
     }
@@ -429,6 +442,10 @@ impl<A: Algebra> MuAlgebra<false> for A {
 }
 
 impl<A: UnstableAlgebra> MuAlgebra<true> for A {
+    fn has_unstable_operations(&self) -> bool {
+        UnstableAlgebra::has_unstable_operations(self)
+    }
+
     fn dimension_unstable(&self, degree: i32, excess: i32) -> usize {
         UnstableAlgebra::dimension_unstable(self, degree, excess)
     }
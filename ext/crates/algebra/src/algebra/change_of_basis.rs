@@ -0,0 +1,890 @@
+//! The mod 2 Steenrod algebra in the $P^s_t$, Arnon and Wall bases.
+//!
+//! These bases are mostly of interest when comparing with the literature, so rather than
+//! implementing their multiplication directly, [`ChangeOfBasisAlgebra`] wraps a [`MilnorAlgebra`]
+//! and records the change of basis matrices in each degree. Products are computed by converting
+//! to the Milnor basis, multiplying there and converting back.
+//!
+//! The bases are
+//!  - [`AlternativeBasis::PSt`]: monomials $P^{s_1}_{t_1} \cdots P^{s_r}_{t_r}$ in distinct
+//!    $P^s_t = Sq(0, \ldots, 0, 2^s)$ (with $2^s$ in position $t$), where the factors are ordered by
+//!    increasing $t$, and then by increasing $s$.
+//!  - [`AlternativeBasis::Wall`]: monomials $Q^{m_1}_{k_1} \cdots Q^{m_r}_{k_r}$, where $Q^m_k =
+//!    Sq^{2^k} Sq^{2^{k + 1}} \cdots Sq^{2^m}$ and $(m_1, k_1) > \cdots > (m_r, k_r)$
+//!    lexicographically.
+//!  - [`AlternativeBasis::ArnonA`]: monomials $X^{m_1}_{k_1} \cdots X^{m_r}_{k_r}$, where $X^m_k =
+//!    Sq^{2^m} Sq^{2^{m - 1}} \cdots Sq^{2^k}$ and $(m_1, k_1) < \cdots < (m_r, k_r)$
+//!    lexicographically.
+//!  - [`AlternativeBasis::ArnonC`]: monomials $Sq^{s_1} \cdots Sq^{s_r}$ with $2 s_{i + 1} \geq s_i$
+//!    and $2^{r - i} \mid s_i$.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::anyhow;
+use fp::{
+    matrix::{AugmentedMatrix, Matrix},
+    prime::ValidPrime,
+    vector::{FpSlice, FpSliceMut, FpVector},
+};
+use itertools::Itertools;
+use once::OnceVec;
+use rustc_hash::FxHashMap as HashMap;
+
+use crate::algebra::{
+    Algebra, Bialgebra, GeneratedAlgebra, MilnorAlgebra,
+    milnor_algebra::{MilnorBasisElement, PPartEntry},
+};
+
+/// A basis of the mod 2 Steenrod algebra supported by [`ChangeOfBasisAlgebra`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AlternativeBasis {
+    PSt,
+    ArnonA,
+    ArnonC,
+    Wall,
+}
+
+impl fmt::Display for AlternativeBasis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::PSt => "pst",
+                Self::ArnonA => "arnona",
+                Self::ArnonC => "arnonc",
+                Self::Wall => "wall",
+            }
+        )
+    }
+}
+
+impl FromStr for AlternativeBasis {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "pst" => Ok(Self::PSt),
+            "arnona" => Ok(Self::ArnonA),
+            "arnonc" => Ok(Self::ArnonC),
+            "wall" => Ok(Self::Wall),
+            _ => Err(anyhow!("Invalid basis name: {s}")),
+        }
+    }
+}
+
+/// A factor of a basis element of a [`ChangeOfBasisAlgebra`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Factor {
+    /// The Milnor basis element $P^s_t = Sq(0, \ldots, 0, 2^s)$.
+    P {
+        s: u32,
+        t: u32,
+    },
+    /// Wall's $Q^m_k = Sq^{2^k} Sq^{2^{k + 1}} \cdots Sq^{2^m}$.
+    Q {
+        m: u32,
+        k: u32,
+    },
+    /// Arnon's $X^m_k = Sq^{2^m} Sq^{2^{m - 1}} \cdots Sq^{2^k}$.
+    X {
+        m: u32,
+        k: u32,
+    },
+    Sq(u32),
+}
+
+impl Factor {
+    pub fn degree(self) -> i32 {
+        match self {
+            Self::P { s, t } => (1 << s) * ((1 << t) - 1),
+            Self::Q { m, k } | Self::X { m, k } => (1 << k) * ((1 << (m - k + 1)) - 1),
+            Self::Sq(n) => n as i32,
+        }
+    }
+
+    /// The factor of the given basis with the same degree as $P^s_t$.
+    fn from_pst(basis: AlternativeBasis, s: u32, t: u32) -> Self {
+        match basis {
+            AlternativeBasis::PSt => Self::P { s, t },
+            AlternativeBasis::Wall => Self::Q { m: s + t - 1, k: s },
+            AlternativeBasis::ArnonA => Self::X { m: s + t - 1, k: s },
+            AlternativeBasis::ArnonC => Self::Sq((1 << s) * ((1 << t) - 1)),
+        }
+    }
+
+    /// The factor as a product of squares, or `None` for $P^s_t$.
+    fn squares(self) -> Option<Vec<u32>> {
+        match self {
+            Self::P { .. } => None,
+            Self::Q { m, k } => Some((k..=m).map(|i| 1 << i).collect()),
+            Self::X { m, k } => Some((k..=m).rev().map(|i| 1 << i).collect()),
+            Self::Sq(n) => Some(vec![n]),
+        }
+    }
+
+    /// The order of the factors in a basis element.
+    fn sort_key(self) -> (i64, i64) {
+        match self {
+            Self::P { s, t } => (t as i64, s as i64),
+            Self::Q { m, k } => (-(m as i64), -(k as i64)),
+            Self::X { m, k } => (m as i64, k as i64),
+            Self::Sq(n) => (n as i64, 0),
+        }
+    }
+}
+
+impl fmt::Display for Factor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::P { s, t } => write!(f, "P^{s}_{t}"),
+            Self::Q { m, k } => write!(f, "Q^{m}_{k}"),
+            Self::X { m, k } => write!(f, "X^{m}_{k}"),
+            Self::Sq(n) => write!(f, "Sq{n}"),
+        }
+    }
+}
+
+impl FromStr for Factor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow!("Invalid factor: {s}");
+        if let Some(n) = s.strip_prefix("Sq") {
+            let n = n.parse().map_err(|_| invalid())?;
+            return if n > 0 {
+                Ok(Self::Sq(n))
+            } else {
+                Err(invalid())
+            };
+        }
+        let (name, rest) = s.split_at_checked(2).ok_or_else(invalid)?;
+        let (a, b) = rest.split_once('_').ok_or_else(invalid)?;
+        let a: u32 = a.parse().map_err(|_| invalid())?;
+        let b: u32 = b.parse().map_err(|_| invalid())?;
+        match name {
+            "P^" if b > 0 => Ok(Self::P { s: a, t: b }),
+            "Q^" if a >= b => Ok(Self::Q { m: a, k: b }),
+            "X^" if a >= b => Ok(Self::X { m: a, k: b }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A basis element of a [`ChangeOfBasisAlgebra`], written as a product of factors.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChangeOfBasisElement {
+    pub degree: i32,
+    pub factors: Vec<Factor>,
+}
+
+impl fmt::Display for ChangeOfBasisElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.factors.is_empty() {
+            write!(f, "1")
+        } else {
+            write!(f, "{}", self.factors.iter().format(" "))
+        }
+    }
+}
+
+struct DegreeTable {
+    basis: Vec<ChangeOfBasisElement>,
+    index: HashMap<ChangeOfBasisElement, usize>,
+    /// The $i$th row is the $i$th basis element in the Milnor basis.
+    to_milnor: Matrix,
+    /// The $i$th row is the $i$th Milnor basis element in this basis.
+    from_milnor: Matrix,
+}
+
+/// The mod 2 Steenrod algebra in one of the bases of [`AlternativeBasis`]. See the
+/// [module-level documentation](self) for a description of the bases.
+pub struct ChangeOfBasisAlgebra {
+    basis: AlternativeBasis,
+    milnor: MilnorAlgebra,
+    tables: OnceVec<DegreeTable>,
+}
+
+impl fmt::Display for ChangeOfBasisAlgebra {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ChangeOfBasisAlgebra({}, p=2)", self.basis)
+    }
+}
+
+impl ChangeOfBasisAlgebra {
+    pub fn new(p: ValidPrime, basis: AlternativeBasis) -> Self {
+        assert_eq!(p, 2, "The {basis} basis is only defined at the prime 2");
+        Self {
+            basis,
+            milnor: MilnorAlgebra::new(p, false),
+            tables: OnceVec::new(),
+        }
+    }
+
+    pub fn basis(&self) -> AlternativeBasis {
+        self.basis
+    }
+
+    /// The Milnor algebra used to perform computations.
+    pub fn milnor(&self) -> &MilnorAlgebra {
+        &self.milnor
+    }
+
+    pub fn basis_element_from_index(&self, degree: i32, idx: usize) -> &ChangeOfBasisElement {
+        &self.tables[degree as usize].basis[idx]
+    }
+
+    pub fn try_basis_element_to_index(&self, elt: &ChangeOfBasisElement) -> Option<usize> {
+        self.tables
+            .get(elt.degree as usize)?
+            .index
+            .get(elt)
+            .copied()
+    }
+
+    pub fn basis_element_to_index(&self, elt: &ChangeOfBasisElement) -> usize {
+        self.try_basis_element_to_index(elt)
+            .unwrap_or_else(|| panic!("Didn't find element: {elt}"))
+    }
+
+    /// The basis element written in the Milnor basis.
+    pub fn basis_element_to_milnor(&self, degree: i32, idx: usize) -> FpSlice<'_> {
+        self.tables[degree as usize].to_milnor.row(idx)
+    }
+
+    /// Adds `coeff` times `input`, written in this basis, to `result`, written in the Milnor
+    /// basis.
+    pub fn to_milnor(&self, mut result: FpSliceMut, coeff: u32, degree: i32, input: FpSlice) {
+        let to_milnor = &self.tables[degree as usize].to_milnor;
+        for (i, c) in input.iter_nonzero() {
+            result.add(to_milnor.row(i), c * coeff);
+        }
+    }
+
+    /// Adds `coeff` times `input`, written in the Milnor basis, to `result`, written in this
+    /// basis.
+    pub fn from_milnor(&self, mut result: FpSliceMut, coeff: u32, degree: i32, input: FpSlice) {
+        let from_milnor = &self.tables[degree as usize].from_milnor;
+        for (i, c) in input.iter_nonzero() {
+            result.add(from_milnor.row(i), c * coeff);
+        }
+    }
+
+    /// Returns $Sq^x$ if it is a basis element. The argument `e` is only present for
+    /// compatibility with the other Steenrod algebras and must be zero.
+    pub fn try_beps_pn(&self, e: u32, x: u32) -> Option<(i32, usize)> {
+        if e != 0 {
+            return None;
+        }
+        let degree = x as i32;
+        self.compute_basis(degree);
+        let (_, milnor_idx) = self.milnor.beps_pn(0, x as PPartEntry);
+        let row = self.tables[degree as usize].from_milnor.row(milnor_idx);
+        let mut terms = row.iter_nonzero();
+        match (terms.next(), terms.next()) {
+            (Some((idx, 1)), None) => Some((degree, idx)),
+            _ => None,
+        }
+    }
+
+    /// Lists the factors of the basis elements in `degree`.
+    fn enumerate_basis(&self, degree: i32) -> Vec<Vec<Factor>> {
+        if self.basis == AlternativeBasis::ArnonC {
+            return arnon_c(degree as u32, 1, u32::MAX)
+                .into_iter()
+                .map(|squares| squares.into_iter().map(Factor::Sq).collect())
+                .collect();
+        }
+        let mut atoms = Vec::new();
+        for t in 1.. {
+            if (1 << t) - 1 > degree {
+                break;
+            }
+            for s in 0.. {
+                let atom = Factor::from_pst(self.basis, s, t);
+                if atom.degree() > degree {
+                    break;
+                }
+                atoms.push(atom);
+            }
+        }
+        atoms.sort_by_key(|atom| atom.sort_key());
+
+        let mut result = Vec::new();
+        distinct_products(&atoms, degree, &mut Vec::new(), &mut result);
+        result
+    }
+
+    /// Writes a product of factors in the Milnor basis.
+    fn factors_to_milnor(&self, factors: &[Factor]) -> FpVector {
+        let p = self.prime();
+        let mut degree = 0;
+        let mut result = FpVector::new(p, 1);
+        result.set_entry(0, 1);
+        for &factor in factors {
+            let ops = match factor.squares() {
+                Some(squares) => squares
+                    .into_iter()
+                    .map(|n| self.milnor.beps_pn(0, n as PPartEntry))
+                    .collect(),
+                None => {
+                    let Factor::P { s, t } = factor else {
+                        unreachable!()
+                    };
+                    let mut p_part = vec![0; t as usize];
+                    p_part[t as usize - 1] = 1 << s;
+                    let elt = MilnorBasisElement {
+                        degree: factor.degree(),
+                        q_part: 0,
+                        p_part,
+                    };
+                    vec![(elt.degree, self.milnor.basis_element_to_index(&elt))]
+                }
+            };
+            for (op_degree, op_idx) in ops {
+                let mut product = FpVector::new(p, self.milnor.dimension(degree + op_degree));
+                self.milnor.multiply_element_by_basis_element(
+                    product.as_slice_mut(),
+                    1,
+                    degree,
+                    result.as_slice(),
+                    op_degree,
+                    op_idx,
+                );
+                result = product;
+                degree += op_degree;
+            }
+        }
+        result
+    }
+
+    fn compute_table(&self, degree: i32) -> DegreeTable {
+        let p = self.prime();
+        let basis: Vec<_> = self
+            .enumerate_basis(degree)
+            .into_iter()
+            .map(|factors| ChangeOfBasisElement { degree, factors })
+            .collect();
+        let dim = basis.len();
+        assert_eq!(dim, self.milnor.dimension(degree));
+
+        let to_milnor = Matrix::from_rows(
+            p,
+            basis
+                .iter()
+                .map(|elt| self.factors_to_milnor(&elt.factors))
+                .collect(),
+            dim,
+        );
+
+        let mut matrix = AugmentedMatrix::<2>::new(p, dim, [dim, dim]);
+        for i in 0..dim {
+            matrix.row_segment_mut(i, 0, 0).assign(to_milnor.row(i));
+        }
+        matrix.segment(1, 1).add_identity();
+        matrix.row_reduce();
+        assert!(
+            matrix.pivots()[..dim].iter().all(|&pivot| pivot >= 0),
+            "The {} monomials do not form a basis in degree {degree}",
+            self.basis
+        );
+        let from_milnor = matrix.into_tail_segment(0, dim, 1);
+
+        let index = basis
+            .iter()
+            .enumerate()
+            .map(|(i, elt)| (elt.clone(), i))
+            .collect();
+
+        DegreeTable {
+            basis,
+            index,
+            to_milnor,
+            from_milnor,
+        }
+    }
+
+    /// Converts a linear combination of products of pairs of Milnor basis elements to this basis.
+    fn pairs_from_milnor(
+        &self,
+        pairs: Vec<(u32, (i32, usize), (i32, usize))>,
+    ) -> Vec<(u32, (i32, usize), (i32, usize))> {
+        let p = self.prime();
+        let mut result = BTreeMap::new();
+        for (c, (l_degree, l_idx), (r_degree, r_idx)) in pairs {
+            let left = self.tables[l_degree as usize].from_milnor.row(l_idx);
+            let right = self.tables[r_degree as usize].from_milnor.row(r_idx);
+            for (i, a) in left.iter_nonzero() {
+                for (j, b) in right.iter_nonzero() {
+                    let entry = result.entry(((l_degree, i), (r_degree, j))).or_insert(0);
+                    *entry = (*entry + c * a * b) % p;
+                }
+            }
+        }
+        result
+            .into_iter()
+            .filter(|&(_, c)| c != 0)
+            .map(|((l, r), c)| (c, l, r))
+            .collect()
+    }
+}
+
+/// Lists the products of distinct elements of `atoms` of total degree `degree`, in the order
+/// of `atoms`. The products are appended to `current`.
+fn distinct_products(
+    atoms: &[Factor],
+    degree: i32,
+    current: &mut Vec<Factor>,
+    result: &mut Vec<Vec<Factor>>,
+) {
+    if degree == 0 {
+        result.push(current.clone());
+        return;
+    }
+    for (i, &atom) in atoms.iter().enumerate() {
+        if atom.degree() <= degree {
+            current.push(atom);
+            distinct_products(&atoms[i + 1..], degree - atom.degree(), current, result);
+            current.pop();
+        }
+    }
+}
+
+/// Lists the exponents of Arnon's C basis in degree `n` with at most `max_len` factors whose first
+/// entry is at least `bound`.
+fn arnon_c(n: u32, bound: u32, max_len: u32) -> Vec<Vec<u32>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    if max_len == 0 {
+        return result;
+    }
+    // The first entry must be divisible by 2^(number of remaining factors)
+    for first in bound..n {
+        let rest_max_len = std::cmp::min(first.trailing_zeros(), max_len - 1);
+        for rest in arnon_c(n - first, std::cmp::max(first / 2, 1), rest_max_len) {
+            result.push(std::iter::once(first).chain(rest).collect());
+        }
+    }
+    if bound <= n {
+        result.push(vec![n]);
+    }
+    result
+}
+
+impl Algebra for ChangeOfBasisAlgebra {
+    fn prefix(&self) -> &str {
+        match self.basis {
+            AlternativeBasis::PSt => "pst",
+            AlternativeBasis::ArnonA => "arnona",
+            AlternativeBasis::ArnonC => "arnonc",
+            AlternativeBasis::Wall => "wall",
+        }
+    }
+
+    fn magic(&self) -> u32 {
+        (self.prime() << 16)
+            + match self.basis {
+                AlternativeBasis::PSt => 0x4000,
+                AlternativeBasis::ArnonA => 0x4001,
+                AlternativeBasis::ArnonC => 0x4002,
+                AlternativeBasis::Wall => 0x4003,
+            }
+    }
+
+    fn prime(&self) -> ValidPrime {
+        self.milnor.prime()
+    }
+
+    fn compute_basis(&self, degree: i32) {
+        if degree < 0 {
+            return;
+        }
+        self.milnor.compute_basis(degree);
+        self.tables
+            .extend(degree as usize, |d| self.compute_table(d as i32));
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < 0 {
+            return 0;
+        }
+        self.tables[degree as usize].basis.len()
+    }
+
+    fn multiply_basis_elements(
+        &self,
+        result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r_idx: usize,
+        s_degree: i32,
+        s_idx: usize,
+    ) {
+        let mut r = FpVector::new(self.prime(), self.dimension(r_degree));
+        r.set_entry(r_idx, 1);
+        self.multiply_element_by_basis_element(
+            result,
+            coeff,
+            r_degree,
+            r.as_slice(),
+            s_degree,
+            s_idx,
+        );
+    }
+
+    fn multiply_basis_element_by_element(
+        &self,
+        result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r_idx: usize,
+        s_degree: i32,
+        s: FpSlice,
+    ) {
+        let mut r = FpVector::new(self.prime(), self.dimension(r_degree));
+        r.set_entry(r_idx, 1);
+        self.multiply_element_by_element(result, coeff, r_degree, r.as_slice(), s_degree, s);
+    }
+
+    fn multiply_element_by_basis_element(
+        &self,
+        result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r: FpSlice,
+        s_degree: i32,
+        s_idx: usize,
+    ) {
+        let mut s = FpVector::new(self.prime(), self.dimension(s_degree));
+        s.set_entry(s_idx, 1);
+        self.multiply_element_by_element(result, coeff, r_degree, r, s_degree, s.as_slice());
+    }
+
+    fn multiply_element_by_element(
+        &self,
+        result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r: FpSlice,
+        s_degree: i32,
+        s: FpSlice,
+    ) {
+        let p = self.prime();
+        let mut r_milnor = FpVector::new(p, self.dimension(r_degree));
+        let mut s_milnor = FpVector::new(p, self.dimension(s_degree));
+        let mut product = FpVector::new(p, self.dimension(r_degree + s_degree));
+        self.to_milnor(r_milnor.as_slice_mut(), 1, r_degree, r);
+        self.to_milnor(s_milnor.as_slice_mut(), 1, s_degree, s);
+        self.milnor.multiply_element_by_element(
+            product.as_slice_mut(),
+            1,
+            r_degree,
+            r_milnor.as_slice(),
+            s_degree,
+            s_milnor.as_slice(),
+        );
+        self.from_milnor(result, coeff, r_degree + s_degree, product.as_slice());
+    }
+
+    fn default_filtration_one_products(&self) -> Vec<(String, i32, usize)> {
+        (0..4)
+            .filter_map(|i| {
+                let (degree, idx) = self.try_beps_pn(0, 1 << i)?;
+                Some((format!("h_{i}"), degree, idx))
+            })
+            .collect()
+    }
+
+    fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        self.basis_element_from_index(degree, idx).to_string()
+    }
+
+    fn basis_element_from_string(&self, elt: &str) -> Option<(i32, usize)> {
+        let elt = elt.trim();
+        if elt == "1" {
+            return Some((0, 0));
+        }
+        // Generators are written as Steenrod squares, as in the other algebras.
+        if let Some(x) = elt.strip_prefix("Sq").or_else(|| elt.strip_prefix('P'))
+            && let Ok(x) = x.parse()
+            && let Some(result) = self.try_beps_pn(0, x)
+        {
+            return Some(result);
+        }
+        let factors = elt
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<anyhow::Result<Vec<Factor>>>()
+            .ok()?;
+        let elt = ChangeOfBasisElement {
+            degree: factors.iter().map(|f| f.degree()).sum(),
+            factors,
+        };
+        self.compute_basis(elt.degree);
+        Some((elt.degree, self.try_basis_element_to_index(&elt)?))
+    }
+}
+
+impl GeneratedAlgebra for ChangeOfBasisAlgebra {
+    fn generators(&self, degree: i32) -> Vec<usize> {
+        if degree <= 0 || !(degree as u32).is_power_of_two() {
+            return vec![];
+        }
+        self.try_beps_pn(0, degree as u32)
+            .map(|(_, idx)| vec![idx])
+            .unwrap_or_default()
+    }
+
+    fn generator_to_string(&self, degree: i32, _idx: usize) -> String {
+        format!("Sq{degree}")
+    }
+
+    fn decompose_basis_element(
+        &self,
+        degree: i32,
+        idx: usize,
+    ) -> Vec<(u32, (i32, usize), (i32, usize))> {
+        let elt = self.basis_element_from_index(degree, idx);
+        if let [first, rest @ ..] = &elt.factors[..]
+            && !rest.is_empty()
+        {
+            // The first factor and the remaining factors are both basis elements
+            let first = ChangeOfBasisElement {
+                degree: first.degree(),
+                factors: vec![*first],
+            };
+            let rest = ChangeOfBasisElement {
+                degree: degree - first.degree,
+                factors: rest.to_vec(),
+            };
+            return vec![(
+                1,
+                (first.degree, self.basis_element_to_index(&first)),
+                (rest.degree, self.basis_element_to_index(&rest)),
+            )];
+        }
+
+        let milnor = self.tables[degree as usize].to_milnor.row(idx);
+        let pairs = milnor
+            .iter_nonzero()
+            .flat_map(|(i, c)| {
+                self.milnor
+                    .decompose_basis_element(degree, i)
+                    .into_iter()
+                    .map(move |(d, l, r)| (c * d, l, r))
+            })
+            .collect();
+        self.pairs_from_milnor(pairs)
+    }
+
+    fn generating_relations(&self, degree: i32) -> Vec<Vec<(u32, (i32, usize), (i32, usize))>> {
+        self.milnor
+            .generating_relations(degree)
+            .into_iter()
+            .map(|relation| self.pairs_from_milnor(relation))
+            .collect()
+    }
+}
+
+impl Bialgebra for ChangeOfBasisAlgebra {
+    fn coproduct(&self, op_deg: i32, op_idx: usize) -> Vec<(i32, usize, i32, usize)> {
+        let milnor = self.tables[op_deg as usize].to_milnor.row(op_idx);
+        let pairs = milnor
+            .iter_nonzero()
+            .flat_map(|(i, _)| self.milnor.coproduct(op_deg, i))
+            .map(|(l_degree, l_idx, r_degree, r_idx)| (1, (l_degree, l_idx), (r_degree, r_idx)))
+            .collect();
+        // We are at the prime 2, so all coefficients are 1.
+        self.pairs_from_milnor(pairs)
+            .into_iter()
+            .map(|(_, (l_degree, l_idx), (r_degree, r_idx))| (l_degree, l_idx, r_degree, r_idx))
+            .collect()
+    }
+
+    fn decompose(&self, op_deg: i32, op_idx: usize) -> Vec<(i32, usize)> {
+        vec![(op_deg, op_idx)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use fp::prime::TWO;
+
+    use super::*;
+
+    const BASES: [AlternativeBasis; 4] = [
+        AlternativeBasis::PSt,
+        AlternativeBasis::ArnonA,
+        AlternativeBasis::ArnonC,
+        AlternativeBasis::Wall,
+    ];
+
+    #[test]
+    fn test_basis() {
+        let check = |basis: AlternativeBasis, expected: Expect| {
+            let algebra = ChangeOfBasisAlgebra::new(TWO, basis);
+            algebra.compute_basis(6);
+            let result = (0..=6)
+                .map(|d| {
+                    (0..algebra.dimension(d))
+                        .map(|i| algebra.basis_element_to_string(d, i))
+                        .join(", ")
+                })
+                .join("\n");
+            expected.assert_eq(&result);
+        };
+
+        check(
+            AlternativeBasis::PSt,
+            expect![[r#"
+            1
+            P^0_1
+            P^1_1
+            P^0_1 P^1_1, P^0_2
+            P^0_1 P^0_2, P^2_1
+            P^0_1 P^2_1, P^1_1 P^0_2
+            P^0_1 P^1_1 P^0_2, P^1_1 P^2_1, P^1_2"#]],
+        );
+        check(
+            AlternativeBasis::ArnonA,
+            expect![[r#"
+            1
+            X^0_0
+            X^1_1
+            X^0_0 X^1_1, X^1_0
+            X^0_0 X^1_0, X^2_2
+            X^0_0 X^2_2, X^1_0 X^1_1
+            X^0_0 X^1_0 X^1_1, X^1_1 X^2_2, X^2_1"#]],
+        );
+        check(
+            AlternativeBasis::ArnonC,
+            expect![[r#"
+            1
+            Sq1
+            Sq2
+            Sq2 Sq1, Sq3
+            Sq2 Sq2, Sq4
+            Sq2 Sq3, Sq5
+            Sq2 Sq4, Sq4 Sq2, Sq6"#]],
+        );
+        check(
+            AlternativeBasis::Wall,
+            expect![[r#"
+            1
+            Q^0_0
+            Q^1_1
+            Q^1_1 Q^0_0, Q^1_0
+            Q^2_2, Q^1_0 Q^0_0
+            Q^2_2 Q^0_0, Q^1_1 Q^1_0
+            Q^2_2 Q^1_1, Q^2_1, Q^1_1 Q^1_0 Q^0_0"#]],
+        );
+    }
+
+    #[test]
+    fn test_change_of_basis() {
+        let max_degree = 32;
+        for basis in BASES {
+            let algebra = ChangeOfBasisAlgebra::new(TWO, basis);
+            algebra.compute_basis(max_degree);
+            for degree in 0..=max_degree {
+                let dim = algebra.dimension(degree);
+                for idx in 0..dim {
+                    let name = algebra.basis_element_to_string(degree, idx);
+                    assert_eq!(
+                        algebra.basis_element_from_string(&name),
+                        Some((degree, idx)),
+                        "{name}"
+                    );
+
+                    let mut milnor = FpVector::new(TWO, dim);
+                    let mut result = FpVector::new(TWO, dim);
+                    let mut expected = FpVector::new(TWO, dim);
+                    expected.set_entry(idx, 1);
+                    algebra.to_milnor(milnor.as_slice_mut(), 1, degree, expected.as_slice());
+                    algebra.from_milnor(result.as_slice_mut(), 1, degree, milnor.as_slice());
+                    assert_eq!(result, expected, "{name}");
+
+                    if degree == 0 || algebra.generators(degree).contains(&idx) {
+                        continue;
+                    }
+                    result.set_to_zero();
+                    for (c, (l_degree, l_idx), (r_degree, r_idx)) in
+                        algebra.decompose_basis_element(degree, idx)
+                    {
+                        algebra.multiply_basis_elements(
+                            result.as_slice_mut(),
+                            c,
+                            l_degree,
+                            l_idx,
+                            r_degree,
+                            r_idx,
+                        );
+                    }
+                    assert_eq!(result, expected, "{name}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generators() {
+        for basis in BASES {
+            let algebra = ChangeOfBasisAlgebra::new(TWO, basis);
+            algebra.compute_basis(16);
+            for i in 0..5 {
+                let (degree, idx) = algebra.try_beps_pn(0, 1 << i).unwrap();
+                assert_eq!(algebra.generators(degree), vec![idx]);
+                assert_eq!(
+                    algebra.basis_element_from_string(&format!("Sq{degree}")),
+                    Some((degree, idx))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply() {
+        let check = |basis: AlternativeBasis, left: &str, right: &str, expected: Expect| {
+            let algebra = ChangeOfBasisAlgebra::new(TWO, basis);
+            let (l_degree, l_idx) = algebra.basis_element_from_string(left).unwrap();
+            let (r_degree, r_idx) = algebra.basis_element_from_string(right).unwrap();
+            let degree = l_degree + r_degree;
+            algebra.compute_basis(degree);
+            let mut result = FpVector::new(TWO, algebra.dimension(degree));
+            algebra.multiply_basis_elements(
+                result.as_slice_mut(),
+                1,
+                l_degree,
+                l_idx,
+                r_degree,
+                r_idx,
+            );
+            expected.assert_eq(&algebra.element_to_string(degree, result.as_slice()));
+        };
+
+        check(
+            AlternativeBasis::PSt,
+            "P^1_1",
+            "P^1_1",
+            expect!["P^0_1 P^0_2"],
+        );
+        check(
+            AlternativeBasis::PSt,
+            "P^0_2",
+            "P^1_1",
+            expect!["P^1_1 P^0_2"],
+        );
+        check(AlternativeBasis::Wall, "Q^0_0", "Q^1_0", expect!["0"]);
+        check(
+            AlternativeBasis::Wall,
+            "Q^2_2",
+            "Q^1_0",
+            expect!["Q^2_2 Q^1_0"],
+        );
+        check(AlternativeBasis::ArnonA, "X^2_2", "X^1_1", expect!["X^2_1"]);
+        check(AlternativeBasis::ArnonC, "Sq4", "Sq2", expect!["Sq4 Sq2"]);
+    }
+}
@@ -117,7 +117,9 @@ impl MilnorBasisElement {
         }
     }
 
-    fn excess(&self, p: ValidPrime) -> u32 {
+    /// The excess of the element, i.e. the smallest degree of a class on which it can act
+    /// non-trivially.
+    pub fn excess(&self, p: ValidPrime) -> u32 {
         if p == 2 {
             self.p_part.iter().sum::<PPartEntry>()
         } else {
@@ -1993,7 +1995,9 @@ mod tests {
                         "{}",
                         ModuleFailedRelationError {
                             relation: relation_string,
-                            value: value_string
+                            value: value_string,
+                            degree: 0,
+                            element: "1".to_string(),
                         }
                     );
                 }
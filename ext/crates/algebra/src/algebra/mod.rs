@@ -10,6 +10,9 @@ pub use algebra_trait::{Algebra, GeneratedAlgebra, MuAlgebra, UnstableAlgebra};
 mod bialgebra_trait;
 pub use bialgebra_trait::Bialgebra;
 
+pub mod change_of_basis;
+pub use change_of_basis::ChangeOfBasisAlgebra;
+
 pub mod combinatorics;
 
 pub mod field;
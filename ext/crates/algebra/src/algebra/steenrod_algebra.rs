@@ -3,13 +3,16 @@ use std::io;
 use anyhow::anyhow;
 use fp::{
     prime::ValidPrime,
-    vector::{FpSlice, FpSliceMut},
+    vector::{FpSlice, FpSliceMut, FpVector},
 };
 use serde::Deserialize;
 use serde_json::Value;
 
 use crate::{
-    algebra::{AdemAlgebra, Algebra, Bialgebra, GeneratedAlgebra, MilnorAlgebra, UnstableAlgebra},
+    algebra::{
+        AdemAlgebra, Algebra, Bialgebra, ChangeOfBasisAlgebra, GeneratedAlgebra, MilnorAlgebra,
+        UnstableAlgebra, change_of_basis::AlternativeBasis,
+    },
     pair_algebra::PairAlgebra,
 };
 
@@ -17,18 +20,17 @@ use crate::{
 pub enum AlgebraType {
     Adem,
     Milnor,
+    /// One of the bases of [`ChangeOfBasisAlgebra`]. These are only defined at the prime 2.
+    Alternative(AlternativeBasis),
 }
 
 impl std::fmt::Display for AlgebraType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Adem => "adem",
-                Self::Milnor => "milnor",
-            }
-        )
+        match self {
+            Self::Adem => write!(f, "adem"),
+            Self::Milnor => write!(f, "milnor"),
+            Self::Alternative(basis) => basis.fmt(f),
+        }
     }
 }
 
@@ -39,7 +41,10 @@ impl std::convert::TryFrom<&str> for AlgebraType {
         match s {
             "adem" => Ok(Self::Adem),
             "milnor" => Ok(Self::Milnor),
-            _ => Err(anyhow!("Invalid algebra name: {}", s)),
+            _ => s
+                .parse()
+                .map(Self::Alternative)
+                .map_err(|_| anyhow!("Invalid algebra name: {}", s)),
         }
     }
 }
@@ -53,10 +58,11 @@ impl std::str::FromStr for AlgebraType {
 }
 
 #[allow(clippy::large_enum_variant)]
-#[enum_dispatch::enum_dispatch(Algebra, Bialgebra, GeneratedAlgebra, UnstableAlgebra)]
+#[enum_dispatch::enum_dispatch(Algebra, Bialgebra, GeneratedAlgebra)]
 pub enum SteenrodAlgebra {
     AdemAlgebra(AdemAlgebra),
     MilnorAlgebra(MilnorAlgebra),
+    ChangeOfBasisAlgebra(ChangeOfBasisAlgebra),
 }
 
 impl std::fmt::Display for SteenrodAlgebra {
@@ -64,6 +70,7 @@ impl std::fmt::Display for SteenrodAlgebra {
         match self {
             Self::AdemAlgebra(a) => a.fmt(f),
             Self::MilnorAlgebra(a) => a.fmt(f),
+            Self::ChangeOfBasisAlgebra(a) => a.fmt(f),
         }
     }
 }
@@ -77,6 +84,9 @@ impl<'a> TryInto<&'a AdemAlgebra> for &'a SteenrodAlgebra {
             SteenrodAlgebra::MilnorAlgebra(_) => {
                 Err(anyhow!("Expected AdemAlgebra, found MilnorAlgebra"))
             }
+            SteenrodAlgebra::ChangeOfBasisAlgebra(a) => {
+                Err(anyhow!("Expected AdemAlgebra, found {a}"))
+            }
         }
     }
 }
@@ -90,6 +100,9 @@ impl<'a> TryInto<&'a MilnorAlgebra> for &'a SteenrodAlgebra {
             SteenrodAlgebra::AdemAlgebra(_) => {
                 Err(anyhow!("Expected MilnorAlgebra, found AdemAlgebra"))
             }
+            SteenrodAlgebra::ChangeOfBasisAlgebra(a) => {
+                Err(anyhow!("Expected MilnorAlgebra, found {a}"))
+            }
         }
     }
 }
@@ -125,6 +138,20 @@ impl SteenrodAlgebra {
                 spec.profile.unwrap_or_default(),
                 unstable,
             )),
+            AlgebraType::Alternative(basis) => {
+                if spec.p != 2 {
+                    return Err(anyhow!("The {basis} basis is only defined at the prime 2"));
+                }
+                if unstable {
+                    return Err(anyhow!(
+                        "Unstable modules are not supported over the {basis} basis"
+                    ));
+                }
+                if spec.profile.is_some_and(|profile| !profile.is_trivial()) {
+                    return Err(anyhow!("Profiles are not supported over the {basis} basis"));
+                }
+                Self::ChangeOfBasisAlgebra(ChangeOfBasisAlgebra::new(spec.p, basis))
+            }
         })
     }
 }
@@ -136,72 +163,159 @@ macro_rules! dispatch_steenrod {
             match self {
                 SteenrodAlgebra::AdemAlgebra(a) => a.$method($($arg),*),
                 SteenrodAlgebra::MilnorAlgebra(a) => a.$method($($arg),*),
+                SteenrodAlgebra::ChangeOfBasisAlgebra(a) => a.$method($($arg),*),
             }
         }
         dispatch_steenrod!{$($tail)*}
     };
 }
 
-impl PairAlgebra for AdemAlgebra {
-    type Element = crate::pair_algebra::MilnorPairElement;
-
-    fn element_is_zero(_elt: &Self::Element) -> bool {
-        unimplemented!()
-    }
-
-    fn finalize_element(_elt: &mut Self::Element) {
-        unimplemented!()
+impl SteenrodAlgebra {
+    dispatch_steenrod! {
+        pub fn try_beps_pn(&self, e: u32, x: u32) -> Option<(i32, usize)>;
     }
 
-    fn p_tilde(&self) -> usize {
-        0
+    /// Computes the antipodes $\chi(P^i)$ (or $\chi(Sq^i)$ at the prime 2) for $0 \leq i \leq n$,
+    /// using that $\sum_{i = 0}^m P^i \chi(P^{m - i}) = 0$ for $m > 0$. Returns `None` if one of
+    /// the $P^i$ is not in the algebra.
+    pub fn power_antipodes(&self, n: u32) -> Option<Vec<FpVector>> {
+        let p = self.prime();
+        let mut result = Vec::with_capacity(n as usize + 1);
+
+        let mut one = FpVector::new(p, 1);
+        one.set_entry(0, 1);
+        result.push(one);
+
+        for m in 1..=n {
+            let (degree, _) = self.try_beps_pn(0, m)?;
+            self.compute_basis(degree);
+            let mut chi = FpVector::new(p, self.dimension(degree));
+            for i in 1..=m {
+                let (i_degree, i_idx) = self.try_beps_pn(0, i)?;
+                self.multiply_basis_element_by_element(
+                    chi.as_slice_mut(),
+                    p - 1,
+                    i_degree,
+                    i_idx,
+                    degree - i_degree,
+                    result[(m - i) as usize].as_slice(),
+                );
+            }
+            result.push(chi);
+        }
+        Some(result)
     }
+}
 
-    fn new_pair_element(&self, _degree: i32) -> Self::Element {
-        unimplemented!()
+/// Unstable modules are only defined over the Adem and Milnor bases, since the elements of an
+/// alternative basis do not span the quotients by elements of large excess.
+/// [`SteenrodAlgebra::from_json`] refuses to construct an alternative basis with `unstable` set,
+/// so [`ChangeOfBasisAlgebra`] does not implement [`UnstableAlgebra`], and unstable resolutions
+/// check [`UnstableAlgebra::has_unstable_operations`] for algebras constructed otherwise.
+impl UnstableAlgebra for SteenrodAlgebra {
+    fn has_unstable_operations(&self) -> bool {
+        !matches!(self, Self::ChangeOfBasisAlgebra(_))
     }
 
-    fn sigma_multiply_basis(
-        &self,
-        _result: &mut Self::Element,
-        _coeff: u32,
-        _r_degree: i32,
-        _r_idx: usize,
-        _s_degree: i32,
-        _s_idx: usize,
-    ) {
-        unimplemented!()
+    fn dimension_unstable(&self, degree: i32, excess: i32) -> usize {
+        match self {
+            Self::AdemAlgebra(a) => a.dimension_unstable(degree, excess),
+            Self::MilnorAlgebra(a) => a.dimension_unstable(degree, excess),
+            Self::ChangeOfBasisAlgebra(a) => panic!("{a} is not an unstable algebra"),
+        }
     }
 
-    fn a_multiply(
+    fn multiply_basis_elements_unstable(
         &self,
-        _result: FpSliceMut,
-        _coeff: u32,
-        _r_degree: i32,
-        _r: FpSlice,
-        _s_degree: i32,
-        _s: &Self::Element,
+        result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r_index: usize,
+        s_degree: i32,
+        s_index: usize,
+        excess: i32,
     ) {
-        unimplemented!()
-    }
-
-    fn element_to_bytes(
-        &self,
-        _elt: &Self::Element,
-        _buffer: &mut impl io::Write,
-    ) -> io::Result<()> {
-        unimplemented!()
+        match self {
+            Self::AdemAlgebra(a) => a.multiply_basis_elements_unstable(
+                result, coeff, r_degree, r_index, s_degree, s_index, excess,
+            ),
+            Self::MilnorAlgebra(a) => a.multiply_basis_elements_unstable(
+                result, coeff, r_degree, r_index, s_degree, s_index, excess,
+            ),
+            Self::ChangeOfBasisAlgebra(a) => panic!("{a} is not an unstable algebra"),
+        }
     }
+}
 
-    fn element_from_bytes(
-        &self,
-        _degree: i32,
-        _buffer: &mut impl io::Read,
-    ) -> io::Result<Self::Element> {
-        unimplemented!()
-    }
+/// Secondary operations are only implemented in the Milnor basis.
+macro_rules! unimplemented_pair_algebra {
+    ($($algebra:ty),*) => {
+        $(
+            impl PairAlgebra for $algebra {
+                type Element = crate::pair_algebra::MilnorPairElement;
+
+                fn element_is_zero(_elt: &Self::Element) -> bool {
+                    unimplemented!()
+                }
+
+                fn finalize_element(_elt: &mut Self::Element) {
+                    unimplemented!()
+                }
+
+                fn p_tilde(&self) -> usize {
+                    0
+                }
+
+                fn new_pair_element(&self, _degree: i32) -> Self::Element {
+                    unimplemented!()
+                }
+
+                fn sigma_multiply_basis(
+                    &self,
+                    _result: &mut Self::Element,
+                    _coeff: u32,
+                    _r_degree: i32,
+                    _r_idx: usize,
+                    _s_degree: i32,
+                    _s_idx: usize,
+                ) {
+                    unimplemented!()
+                }
+
+                fn a_multiply(
+                    &self,
+                    _result: FpSliceMut,
+                    _coeff: u32,
+                    _r_degree: i32,
+                    _r: FpSlice,
+                    _s_degree: i32,
+                    _s: &Self::Element,
+                ) {
+                    unimplemented!()
+                }
+
+                fn element_to_bytes(
+                    &self,
+                    _elt: &Self::Element,
+                    _buffer: &mut impl io::Write,
+                ) -> io::Result<()> {
+                    unimplemented!()
+                }
+
+                fn element_from_bytes(
+                    &self,
+                    _degree: i32,
+                    _buffer: &mut impl io::Read,
+                ) -> io::Result<Self::Element> {
+                    unimplemented!()
+                }
+            }
+        )*
+    };
 }
 
+unimplemented_pair_algebra!(AdemAlgebra, ChangeOfBasisAlgebra);
+
 impl PairAlgebra for SteenrodAlgebra {
     type Element = crate::pair_algebra::MilnorPairElement;
 
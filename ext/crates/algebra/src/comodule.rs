@@ -0,0 +1,425 @@
+//! Finite comodules over the dual Steenrod algebra.
+//!
+//! We write the dual Steenrod algebra as
+//! $$ A_* = \mathbb{F}_p[\xi_1, \xi_2, \ldots] \otimes E(\tau_0, \tau_1, \ldots), $$
+//! where $|\xi_i| = 2^i - 1$ at the prime 2 (and the $\tau_i$ are absent), while $|\xi_i| =
+//! 2(p^i - 1)$ and $|\tau_i| = 2p^i - 1$ at odd primes. The monomial $\tau_0^{\epsilon_0}
+//! \tau_1^{\epsilon_1} \cdots \xi_1^{r_1} \xi_2^{r_2} \cdots$ is dual to the element $Q(\epsilon)
+//! P(R)$ of the Milnor basis, and the coproduct of $A_*$ is the transpose of the product of the
+//! Milnor basis. At odd primes, this fixes the signs of the coproduct, which may differ from other
+//! conventions in the literature.
+//!
+//! A finite dimensional left $A_*$-comodule $M$ is the same as a finite dimensional left
+//! $A$-module, namely its dual $M^*$. We put $(M^*)_n = (M_n)^*$, so that the homology of a space
+//! corresponds to its cohomology. If $\psi(m) = \sum a_i \otimes m_i$, then $a f$ is the
+//! functional $m \mapsto \sum \langle a, a_i \rangle f(m_i)$. We store a comodule as its dual module
+//! over the Milnor algebra, and this is how [`Comodule`] converts to and from
+//! [`FDModule`].
+//!
+//! # JSON format
+//! A comodule is given by
+//! ```json
+//! {
+//!     "type": "comodule",
+//!     "p": 2,
+//!     "gens": {"x0": 0, "x1": 1, "x2": 2, "x3": 3},
+//!     "coactions": ["x1 = xi1 x0", "x2 = xi1^2 x0", "x3 = xi2 x0 + xi1^2 x1"]
+//! }
+//! ```
+//! Each entry of `coactions` is the reduced coaction $\psi(x) - 1 \otimes x$ of a basis element,
+//! and coactions that are not listed are zero. Each term is an optional coefficient, a monomial in
+//! `xi{i}` (with optional exponent) and `tau{i}`, and a basis element. The optional `profile` of
+//! the algebra is respected, and the coaction must then land in the corresponding quotient of
+//! $A_*$.
+
+use std::sync::Arc;
+
+use anyhow::{Context, anyhow, ensure};
+use fp::prime::ValidPrime;
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    algebra::{
+        Algebra, MilnorAlgebra, SteenrodAlgebra,
+        milnor_algebra::{MilnorBasisElement, PPartEntry},
+    },
+    module::{FDModule, Module},
+};
+
+/// A finite dimensional comodule over the dual Steenrod algebra, stored as its dual module. See
+/// the [module level documentation](self) for conventions.
+pub struct Comodule {
+    dual: FDModule<SteenrodAlgebra>,
+}
+
+impl std::fmt::Display for Comodule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.dual)
+    }
+}
+
+/// Writes the element of $A_*$ dual to a Milnor basis element as a monomial, e.g. `tau0 xi1^2`.
+pub fn monomial_to_string(elt: &MilnorBasisElement) -> String {
+    let taus = (0..u32::BITS)
+        .filter(|i| elt.q_part & (1 << i) != 0)
+        .map(|i| format!("tau{i}"));
+    let xis = elt
+        .p_part
+        .iter()
+        .enumerate()
+        .filter(|&(_, &r)| r > 0)
+        .map(|(i, &r)| {
+            if r == 1 {
+                format!("xi{}", i + 1)
+            } else {
+                format!("xi{}^{r}", i + 1)
+            }
+        });
+    let result = taus.chain(xis).join(" ");
+    if result.is_empty() {
+        "1".to_string()
+    } else {
+        result
+    }
+}
+
+/// Parses a monomial in the $\xi_i$ and $\tau_i$ given as a list of factors. This returns the dual
+/// Milnor basis element and the sign needed to put the $\tau_i$ in increasing order, or `None` if
+/// the monomial is zero.
+fn parse_monomial<'a>(
+    p: ValidPrime,
+    factors: impl Iterator<Item = &'a str>,
+) -> anyhow::Result<Option<(MilnorBasisElement, u32)>> {
+    let mut elt = MilnorBasisElement {
+        q_part: 0,
+        p_part: vec![],
+        degree: 0,
+    };
+    let mut sign = 1;
+    for factor in factors {
+        if let Some(i) = factor.strip_prefix("tau") {
+            ensure!(p != 2, "There is no {factor} at the prime 2");
+            let i: u32 = i.parse().map_err(|_| anyhow!("Invalid factor: {factor}"))?;
+            ensure!(i < u32::BITS, "Invalid factor: {factor}");
+            if elt.q_part & (1 << i) != 0 {
+                return Ok(None);
+            }
+            // Move tau_i past the tau_j with j > i
+            if (elt.q_part >> i).count_ones() % 2 == 1 {
+                sign = p - sign;
+            }
+            elt.q_part |= 1 << i;
+        } else if let Some(rest) = factor.strip_prefix("xi") {
+            let (i, r) = rest.split_once('^').unwrap_or((rest, "1"));
+            let i: usize = i.parse().map_err(|_| anyhow!("Invalid factor: {factor}"))?;
+            let r: PPartEntry = r.parse().map_err(|_| anyhow!("Invalid factor: {factor}"))?;
+            ensure!(i > 0, "Invalid factor: {factor}");
+            if elt.p_part.len() < i {
+                elt.p_part.resize(i, 0);
+            }
+            elt.p_part[i - 1] += r;
+        } else {
+            return Err(anyhow!("Invalid factor: {factor}"));
+        }
+    }
+    while elt.p_part.last() == Some(&0) {
+        elt.p_part.pop();
+    }
+    elt.compute_degree(p);
+    Ok(Some((elt, sign)))
+}
+
+impl Comodule {
+    fn milnor_algebra(algebra: &SteenrodAlgebra) -> anyhow::Result<&MilnorAlgebra> {
+        match algebra {
+            SteenrodAlgebra::MilnorAlgebra(a) => Ok(a),
+            SteenrodAlgebra::AdemAlgebra(_) | SteenrodAlgebra::ChangeOfBasisAlgebra(_) => {
+                Err(anyhow!("Comodules must be defined over the Milnor algebra"))
+            }
+        }
+    }
+
+    /// The comodule whose dual is `module`. The module must be defined over the Milnor algebra.
+    pub fn from_dual(module: FDModule<SteenrodAlgebra>) -> anyhow::Result<Self> {
+        Self::milnor_algebra(&module.algebra())?;
+        Ok(Self { dual: module })
+    }
+
+    /// The dual module of the comodule, which has the same basis.
+    pub fn dual(&self) -> &FDModule<SteenrodAlgebra> {
+        &self.dual
+    }
+
+    pub fn into_dual(self) -> FDModule<SteenrodAlgebra> {
+        self.dual
+    }
+
+    pub fn algebra(&self) -> Arc<SteenrodAlgebra> {
+        self.dual.algebra()
+    }
+
+    pub fn prime(&self) -> ValidPrime {
+        self.dual.prime()
+    }
+
+    pub fn min_degree(&self) -> i32 {
+        self.dual.min_degree()
+    }
+
+    pub fn max_degree(&self) -> Option<i32> {
+        self.dual.max_degree()
+    }
+
+    pub fn dimension(&self, degree: i32) -> usize {
+        self.dual.dimension(degree)
+    }
+
+    pub fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        self.dual.basis_element_to_string(degree, idx)
+    }
+
+    /// The reduced coaction of a basis element, as a list of `(coeff, op_deg, op_idx, degree,
+    /// idx)`, where `(op_deg, op_idx)` is the Milnor basis element dual to the monomial of $A_*$.
+    pub fn reduced_coaction(&self, degree: i32, idx: usize) -> Vec<(u32, i32, usize, i32, usize)> {
+        let algebra = self.algebra();
+        let mut result = Vec::new();
+        for input_deg in self.min_degree()..degree {
+            let op_deg = degree - input_deg;
+            for op_idx in 0..algebra.dimension(op_deg) {
+                for input_idx in 0..self.dimension(input_deg) {
+                    let c = self
+                        .dual
+                        .action(op_deg, op_idx, input_deg, input_idx)
+                        .entry(idx);
+                    if c != 0 {
+                        result.push((c, op_deg, op_idx, input_deg, input_idx));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Writes the reduced coaction of a basis element in the format used by the json.
+    pub fn reduced_coaction_to_string(&self, degree: i32, idx: usize) -> String {
+        let algebra = self.algebra();
+        let milnor = Self::milnor_algebra(&algebra).unwrap();
+        let terms = self.reduced_coaction(degree, idx);
+        if terms.is_empty() {
+            return "0".to_string();
+        }
+        terms
+            .into_iter()
+            .map(|(c, op_deg, op_idx, deg, idx)| {
+                let monomial = monomial_to_string(milnor.basis_element_from_index(op_deg, op_idx));
+                let x = self.basis_element_to_string(deg, idx);
+                if c == 1 {
+                    format!("{monomial} {x}")
+                } else {
+                    format!("{c} {monomial} {x}")
+                }
+            })
+            .join(" + ")
+    }
+
+    pub fn from_json(algebra: Arc<SteenrodAlgebra>, json: &Value) -> anyhow::Result<Self> {
+        let p = algebra.prime();
+        let milnor = Self::milnor_algebra(&algebra)?;
+        let (graded_dimension, gen_names, gen_to_idx) = crate::module_gens_from_json(&json["gens"]);
+        let name = json["name"].as_str().unwrap_or("").to_string();
+
+        let mut dual = FDModule::new(Arc::clone(&algebra), name, graded_dimension.clone());
+        for (i, dim) in graded_dimension.iter_enum() {
+            for j in 0..*dim {
+                dual.set_basis_element_name(i, j, gen_names[i][j].clone());
+            }
+        }
+
+        let coactions = Vec::<String>::deserialize(&json["coactions"])
+            .context("Comodule must have a list of coactions")?;
+        for entry in &coactions {
+            let (lhs, rhs) = entry
+                .split_once(" = ")
+                .ok_or_else(|| anyhow!("Invalid coaction: {entry}"))?;
+            let (degree, idx) = gen_to_idx(lhs.trim())?;
+            if rhs.trim() == "0" {
+                continue;
+            }
+            for term in rhs.split(" + ") {
+                let mut tokens = term.split_whitespace().collect::<Vec<_>>();
+                let x = tokens
+                    .pop()
+                    .ok_or_else(|| anyhow!("Invalid term in coaction: {entry}"))?;
+                let (input_deg, input_idx) = gen_to_idx(x)?;
+                let coeff = match tokens.first().map(|t| t.parse::<u32>()) {
+                    Some(Ok(c)) => {
+                        tokens.remove(0);
+                        c % p
+                    }
+                    _ => 1,
+                };
+                let Some((elt, sign)) = parse_monomial(p, tokens.into_iter())
+                    .with_context(|| format!("Invalid term {term} in coaction: {entry}"))?
+                else {
+                    continue;
+                };
+                ensure!(
+                    elt.degree > 0,
+                    "The coaction {entry} contains the term {term}, but the coaction should be \
+                     reduced"
+                );
+                ensure!(
+                    elt.degree + input_deg == degree,
+                    "The term {term} has degree {} but {lhs} has degree {degree}",
+                    elt.degree + input_deg
+                );
+                let op_idx = milnor.try_basis_element_to_index(&elt).ok_or_else(|| {
+                    anyhow!(
+                        "{} is not in the dual of the algebra",
+                        monomial_to_string(&elt)
+                    )
+                })?;
+                dual.action_mut(elt.degree, op_idx, input_deg, input_idx)
+                    .add_basis_element(idx, coeff * sign);
+            }
+        }
+        // The dual of coassociativity is associativity of the action.
+        dual.check_products()
+            .context("The coaction is not coassociative")?;
+        Ok(Self { dual })
+    }
+
+    pub fn to_json(&self, json: &mut Value) {
+        if !self.dual.name.is_empty() {
+            json["name"] = Value::String(self.dual.name.clone());
+        }
+        json["type"] = Value::from("comodule");
+        json["gens"] = json!({});
+        let mut coactions = Vec::new();
+        let max_degree = self.max_degree().unwrap_or(self.min_degree() - 1);
+        for degree in self.min_degree()..=max_degree {
+            for idx in 0..self.dimension(degree) {
+                let name = self.basis_element_to_string(degree, idx);
+                json["gens"][&name] = Value::from(degree);
+                if !self.reduced_coaction(degree, idx).is_empty() {
+                    coactions.push(Value::String(format!(
+                        "{name} = {}",
+                        self.reduced_coaction_to_string(degree, idx)
+                    )));
+                }
+            }
+        }
+        json["coactions"] = Value::Array(coactions);
+    }
+}
+
+impl From<Comodule> for FDModule<SteenrodAlgebra> {
+    fn from(comodule: Comodule) -> Self {
+        comodule.into_dual()
+    }
+}
+
+impl TryFrom<FDModule<SteenrodAlgebra>> for Comodule {
+    type Error = anyhow::Error;
+
+    fn try_from(module: FDModule<SteenrodAlgebra>) -> anyhow::Result<Self> {
+        Self::from_dual(module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    fn milnor(p: u32) -> Arc<SteenrodAlgebra> {
+        Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+            ValidPrime::new(p),
+            false,
+        )))
+    }
+
+    #[test]
+    fn joker() {
+        let algebra = milnor(2);
+        let module =
+            FDModule::from_json(Arc::clone(&algebra), &crate::tests::joker_json()).unwrap();
+        let comodule = Comodule::from_dual(module.clone()).unwrap();
+
+        let mut json = json!({});
+        comodule.to_json(&mut json);
+        expect![[r#"
+            [
+              "x1 = xi1 x0",
+              "x2 = xi1^2 x0",
+              "x3 = xi2 x0 + xi1^2 x1",
+              "x4 = xi1 xi2 x0 + xi1^3 x1 + xi2 x1 + xi1^2 x2 + xi1 x3"
+            ]"#]]
+        .assert_eq(&serde_json::to_string_pretty(&json["coactions"]).unwrap());
+
+        let comodule = Comodule::from_json(algebra, &json).unwrap();
+        module.test_equal(comodule.dual()).unwrap();
+    }
+
+    #[test]
+    fn empty() {
+        let algebra = milnor(2);
+        let json = json!({"type": "comodule", "p": 2, "gens": {}, "coactions": []});
+        let comodule = Comodule::from_json(Arc::clone(&algebra), &json).unwrap();
+        let mut output = json!({});
+        comodule.to_json(&mut output);
+        let roundtrip = Comodule::from_json(algebra, &output).unwrap();
+        comodule.dual().test_equal(roundtrip.dual()).unwrap();
+    }
+
+    #[test]
+    fn odd_prime() {
+        let algebra = milnor(3);
+        // A comodule with a non-trivial tau1 coaction
+        let json = json!({
+            "type": "comodule",
+            "p": 3,
+            "gens": {"x0": 0, "x1": 1, "x4": 4, "x5": 5},
+            "coactions": [
+                "x1 = tau0 x0",
+                "x4 = xi1 x0",
+                "x5 = tau0 x4 + 2 xi1 x1 + xi1 tau0 x0 + tau1 x0"
+            ]
+        });
+        let comodule = Comodule::from_json(Arc::clone(&algebra), &json).unwrap();
+        let mut output = json!({});
+        comodule.to_json(&mut output);
+        let roundtrip = Comodule::from_json(algebra, &output).unwrap();
+        comodule.dual().test_equal(roundtrip.dual()).unwrap();
+    }
+
+    #[test]
+    fn invalid() {
+        let algebra = milnor(2);
+        let err = |coactions: Value| {
+            let json = json!({
+                "type": "comodule",
+                "p": 2,
+                "gens": {"x0": 0, "x1": 1, "x3": 3},
+                "coactions": coactions,
+            });
+            match Comodule::from_json(Arc::clone(&algebra), &json) {
+                Ok(_) => panic!("Invalid comodule accepted"),
+                Err(e) => format!("{e:#}"),
+            }
+        };
+        expect![[r#"
+            The coaction is not coassociative: Relation failed on x0 in degree 0:
+                P(2) * P(1)  -  (P(3) + P(0, 1))  !=  0
+            Instead it is equal to x3
+        "#]]
+        .assert_eq(&err(json!(["x1 = xi1 x0", "x3 = xi1^2 x1"])));
+        expect!["The term xi1 x0 has degree 1 but x3 has degree 3"]
+            .assert_eq(&err(json!(["x3 = xi1 x0"])));
+        expect!["Invalid term tau0 x0 in coaction: x1 = tau0 x0: There is no tau0 at the prime 2"]
+            .assert_eq(&err(json!(["x1 = tau0 x0"])));
+    }
+}
@@ -2,13 +2,17 @@ use std::{fmt::Write as _, sync::Arc};
 
 use anyhow::{Context, anyhow};
 use bivec::BiVec;
-use fp::vector::{FpSliceMut, FpVector};
+use fp::{
+    matrix::{AugmentedMatrix, Subspace},
+    prime::Prime,
+    vector::{FpSliceMut, FpVector},
+};
 use serde::Deserialize;
 use serde_json::{json, value::Value};
 
 use crate::{
-    algebra::{Algebra, GeneratedAlgebra},
-    module::{Module, ModuleFailedRelationError, ZeroModule},
+    algebra::{Algebra, GeneratedAlgebra, SteenrodAlgebra},
+    module::{FPModule, Module, ModuleFailedRelationError, ZeroModule},
 };
 
 pub struct FiniteDimensionalModule<A: Algebra> {
@@ -395,15 +399,107 @@ impl<A: Algebra> FiniteDimensionalModule<A> {
         let output_degree = input_degree + operation_degree;
         &mut self.actions[input_degree][output_degree][operation_idx][input_idx]
     }
+
+    /// The direct sum of `self` and `other`. In each degree, the basis of `other` comes after that
+    /// of `self`. Names of basis elements of `other` that already occur in `self` are primed until
+    /// they are unique.
+    pub fn direct_sum(&self, other: &Self) -> Self {
+        let min_degree = std::cmp::min(self.min_degree(), other.min_degree());
+        let max_degree = std::cmp::max(self.graded_dimension.len(), other.graded_dimension.len());
+
+        let dimension = |m: &Self, t: i32| {
+            if t < m.min_degree() || t >= m.graded_dimension.len() {
+                0
+            } else {
+                m.graded_dimension[t]
+            }
+        };
+
+        let mut graded_dimension = BiVec::with_capacity(min_degree, max_degree);
+        for t in min_degree..max_degree {
+            graded_dimension.push(dimension(self, t) + dimension(other, t));
+        }
+        let mut result = Self::new(
+            Arc::clone(&self.algebra),
+            format!("{} + {}", self.name, other.name),
+            graded_dimension,
+        );
+
+        let mut names: std::collections::HashSet<String> =
+            self.gen_names.iter().flatten().cloned().collect();
+        for (t, gens) in self.gen_names.iter_enum() {
+            for (idx, name) in gens.iter().enumerate() {
+                result.set_basis_element_name(t, idx, name.clone());
+            }
+        }
+        for (t, gens) in other.gen_names.iter_enum() {
+            for (idx, name) in gens.iter().enumerate() {
+                let mut name = name.clone();
+                while names.contains(&name) {
+                    name.push('\'');
+                }
+                names.insert(name.clone());
+                result.set_basis_element_name(t, dimension(self, t) + idx, name);
+            }
+        }
+
+        for (summand, is_other) in [(self, false), (other, true)] {
+            let offset = |t| if is_other { dimension(self, t) } else { 0 };
+            for input_degree in summand.min_degree()..summand.graded_dimension.len() {
+                for output_degree in input_degree + 1..summand.graded_dimension.len() {
+                    let output_dim = summand.dimension(output_degree);
+                    if output_dim == 0 {
+                        continue;
+                    }
+                    let op_degree = output_degree - input_degree;
+                    let output_offset = offset(output_degree);
+                    for op_idx in 0..self.algebra.dimension(op_degree) {
+                        for input_idx in 0..summand.dimension(input_degree) {
+                            result
+                                .action_mut(
+                                    op_degree,
+                                    op_idx,
+                                    input_degree,
+                                    offset(input_degree) + input_idx,
+                                )
+                                .slice_mut(output_offset, output_offset + output_dim)
+                                .assign(
+                                    summand
+                                        .action(op_degree, op_idx, input_degree, input_idx)
+                                        .as_slice(),
+                                );
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
 }
 
 impl<M: Module> From<&M> for FiniteDimensionalModule<M::Algebra> {
     /// This should really by try_from but orphan rules prohibit this
     fn from(module: &M) -> Self {
-        let min_degree = module.min_degree();
         let max_degree = module
             .max_degree()
             .expect("Can only convert to fininte dimensional module if bounded");
+        Self::truncation(module, module.min_degree(), max_degree)
+    }
+}
+
+impl<A: Algebra> FiniteDimensionalModule<A> {
+    /// The part of `module` in degrees `min_degree` through `max_degree`. This is the quotient of
+    /// the submodule of elements of degree at least `min_degree` by the elements of degree greater
+    /// than `max_degree`.
+    ///
+    /// The basis elements keep their names, with spaces removed so that the result can be written
+    /// to json.
+    pub fn truncation<M: Module<Algebra = A>>(
+        module: &M,
+        min_degree: i32,
+        max_degree: i32,
+    ) -> Self {
+        let min_degree = std::cmp::max(min_degree, module.min_degree());
         module.compute_basis(max_degree);
 
         let mut graded_dimension = BiVec::with_capacity(min_degree, max_degree + 1);
@@ -413,7 +509,8 @@ impl<M: Module> From<&M> for FiniteDimensionalModule<M::Algebra> {
         let mut result = Self::new(module.algebra(), module.to_string(), graded_dimension);
         for t in min_degree..=max_degree {
             for idx in 0..result.dimension(t) {
-                result.set_basis_element_name(t, idx, module.basis_element_to_string(t, idx));
+                let name = module.basis_element_to_string(t, idx).replace(' ', "");
+                result.set_basis_element_name(t, idx, name);
             }
         }
 
@@ -444,6 +541,106 @@ impl<M: Module> From<&M> for FiniteDimensionalModule<M::Algebra> {
         }
         result
     }
+
+    /// A minimal presentation of the module that is valid through `max_degree`.
+    ///
+    /// The generators are basis elements of the module, chosen in each degree to span a complement
+    /// of the image of the positive degree part of the algebra. The relations in each degree
+    /// likewise span a complement of the relations implied by those in lower degrees. A finite
+    /// module usually needs relations in infinitely many degrees, e.g. the sphere needs $Sq^{2^i}$
+    /// for every $i$, so we stop at `max_degree`. The resulting module agrees with this one up to
+    /// `max_degree`.
+    pub fn minimal_presentation(&self, max_degree: i32) -> FPModule<A> {
+        let p = self.prime();
+        let algebra = self.algebra();
+        let min_degree = self.min_degree();
+        algebra.compute_basis(max_degree - min_degree);
+
+        let mut result = FPModule::new(Arc::clone(&algebra), self.name.clone(), min_degree);
+        let free = result.generators();
+        // The generators, as basis elements of the module
+        let mut generators: Vec<(i32, usize)> = Vec::new();
+        let mut relations: Vec<(i32, FpVector)> = Vec::new();
+
+        for degree in min_degree..=max_degree {
+            let dim = self.dimension(degree);
+
+            // The new generators span a complement of the decomposables
+            let mut decomposables = Subspace::new(p, dim);
+            for &(gen_deg, gen_idx) in &generators {
+                let op_deg = degree - gen_deg;
+                for op_idx in 0..algebra.dimension(op_deg) {
+                    let mut v = FpVector::new(p, dim);
+                    self.act_on_basis(v.as_slice_mut(), 1, op_deg, op_idx, gen_deg, gen_idx);
+                    decomposables.add_vector(v.as_slice());
+                }
+            }
+            let mut names = Vec::new();
+            for idx in 0..dim {
+                let mut v = FpVector::new(p, dim);
+                v.set_entry(idx, 1);
+                if !decomposables.contains(v.as_slice()) {
+                    decomposables.add_vector(v.as_slice());
+                    generators.push((degree, idx));
+                    names.push(self.basis_element_to_string(degree, idx));
+                }
+            }
+            result.add_generators(degree, names);
+            free.compute_basis(degree);
+
+            // The kernel of the map from the free module onto the module
+            let free_dim = free.dimension(degree);
+            let mut matrix = AugmentedMatrix::<2>::new(p, free_dim, [dim, free_dim]);
+            for i in 0..free_dim {
+                let opgen = free.index_to_op_gen(degree, i);
+                let gen_idx = generators
+                    .iter()
+                    .filter(|&&(d, _)| d == opgen.generator_degree)
+                    .nth(opgen.generator_index)
+                    .unwrap()
+                    .1;
+                self.act_on_basis(
+                    matrix.row_segment_mut(i, 0, 0),
+                    1,
+                    opgen.operation_degree,
+                    opgen.operation_index,
+                    opgen.generator_degree,
+                    gen_idx,
+                );
+                matrix.row_segment_mut(i, 1, 1).set_entry(i, 1);
+            }
+            matrix.row_reduce();
+            let kernel = matrix.compute_kernel();
+
+            // The new relations span a complement of the consequences of the old ones
+            let mut consequences = Subspace::new(p, free_dim);
+            for (rel_deg, rel) in &relations {
+                let op_deg = degree - rel_deg;
+                for op_idx in 0..algebra.dimension(op_deg) {
+                    let mut v = FpVector::new(p, free_dim);
+                    free.act(
+                        v.as_slice_mut(),
+                        1,
+                        op_deg,
+                        op_idx,
+                        *rel_deg,
+                        rel.as_slice(),
+                    );
+                    consequences.add_vector(v.as_slice());
+                }
+            }
+            let mut new_relations = Vec::new();
+            for v in kernel.basis() {
+                if !consequences.contains(v) {
+                    consequences.add_vector(v);
+                    new_relations.push(v.to_owned());
+                }
+            }
+            relations.extend(new_relations.iter().map(|v| (degree, v.clone())));
+            result.add_relations(degree, new_relations);
+        }
+        result
+    }
 }
 
 impl<A: GeneratedAlgebra> FiniteDimensionalModule<A> {
@@ -464,15 +661,152 @@ impl<A: GeneratedAlgebra> FiniteDimensionalModule<A> {
                 .parse_action(&gen_to_idx, &action, false)
                 .with_context(|| format!("Failed to parse action: {action}"))?;
         }
-        for input_degree in (result.min_degree()..=result.max_degree().unwrap()).rev() {
-            for output_degree in input_degree + 1..=result.max_degree().unwrap() {
-                result.extend_actions(input_degree, output_degree);
-                result.check_validity(input_degree, output_degree)?;
+
+        // Only the actions of the generators are needed, and we compute the others from them. If
+        // the actions of other elements are given, we check them against the computed ones.
+        let given = result.take_non_generator_actions();
+        result.complete_actions()?;
+        for (op_deg, op_idx, input_deg, input_idx, given) in given {
+            let implied = result.action(op_deg, op_idx, input_deg, input_idx);
+            if *implied != given {
+                let output_deg = input_deg + op_deg;
+                return Err(anyhow!(
+                    "{op} {x} = {given} is inconsistent with the actions of the generators, which \
+                     imply {op} {x} = {implied}",
+                    op = algebra.basis_element_to_string(op_deg, op_idx),
+                    x = result.gen_names[input_deg][input_idx],
+                    given = result.element_to_string(output_deg, given.as_slice()),
+                    implied = result.element_to_string(output_deg, implied.as_slice()),
+                ));
             }
         }
         Ok(result)
     }
 
+    /// Removes the non-zero actions of elements that are not generators of the algebra, and
+    /// returns them as `(op_deg, op_idx, input_deg, input_idx, value)`.
+    fn take_non_generator_actions(&mut self) -> Vec<(i32, usize, i32, usize, FpVector)> {
+        let algebra = self.algebra();
+        let mut result = Vec::new();
+        let Some(max_degree) = self.max_degree() else {
+            return result;
+        };
+        for input_deg in self.min_degree()..=max_degree {
+            for output_deg in input_deg + 1..=max_degree {
+                let op_deg = output_deg - input_deg;
+                let generators = algebra.generators(op_deg);
+                for op_idx in 0..algebra.dimension(op_deg) {
+                    if generators.contains(&op_idx) {
+                        continue;
+                    }
+                    for input_idx in 0..self.dimension(input_deg) {
+                        let row = self.action_mut(op_deg, op_idx, input_deg, input_idx);
+                        if !row.is_zero() {
+                            let value = row.clone();
+                            row.set_to_zero();
+                            result.push((op_deg, op_idx, input_deg, input_idx, value));
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Computes the action of all elements of the algebra from the action of its generators, and
+    /// checks that the relations of the algebra hold. If they do not, this returns the failing
+    /// relation of lowest degree.
+    pub fn complete_actions(&mut self) -> Result<(), ModuleFailedRelationError> {
+        let Some(max_degree) = self.max_degree() else {
+            return Ok(());
+        };
+        // The actions out of a degree are computed from the actions out of higher degrees
+        for input_degree in (self.min_degree()..=max_degree).rev() {
+            for output_degree in input_degree + 1..=max_degree {
+                self.extend_actions(input_degree, output_degree);
+            }
+        }
+        for input_degree in self.min_degree()..=max_degree {
+            for output_degree in input_degree + 1..=max_degree {
+                self.check_validity(input_degree, output_degree)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that $a(bm) = (ab)m$ for all basis elements $a$ and $b$ of the algebra and $m$ of
+    /// the module. This follows from the relations checked by
+    /// [`FiniteDimensionalModule::complete_actions`], but does not rely on the algebra providing a
+    /// complete set of relations.
+    ///
+    /// This takes time cubic in the dimension of the module, so it is not run when loading a
+    /// module.
+    pub fn check_products(&self) -> Result<(), ModuleFailedRelationError> {
+        let p = self.prime();
+        let algebra = self.algebra();
+        let Some(max_degree) = self.max_degree() else {
+            return Ok(());
+        };
+        for input_deg in self.min_degree()..=max_degree {
+            for mid_deg in input_deg + 1..=max_degree {
+                for output_deg in mid_deg + 1..=max_degree {
+                    let (b_deg, a_deg) = (mid_deg - input_deg, output_deg - mid_deg);
+                    if self.dimension(mid_deg) == 0 || self.dimension(output_deg) == 0 {
+                        continue;
+                    }
+                    for idx in 0..self.dimension(input_deg) {
+                        for b_idx in 0..algebra.dimension(b_deg) {
+                            let mut bm = FpVector::new(p, self.dimension(mid_deg));
+                            self.act_on_basis(bm.as_slice_mut(), 1, b_deg, b_idx, input_deg, idx);
+                            for a_idx in 0..algebra.dimension(a_deg) {
+                                let mut ab = FpVector::new(p, algebra.dimension(a_deg + b_deg));
+                                algebra.multiply_basis_elements(
+                                    ab.as_slice_mut(),
+                                    1,
+                                    a_deg,
+                                    a_idx,
+                                    b_deg,
+                                    b_idx,
+                                );
+                                let mut diff = FpVector::new(p, self.dimension(output_deg));
+                                self.act(
+                                    diff.as_slice_mut(),
+                                    1,
+                                    a_deg,
+                                    a_idx,
+                                    mid_deg,
+                                    bm.as_slice(),
+                                );
+                                self.act_by_element_on_basis(
+                                    diff.as_slice_mut(),
+                                    p - 1,
+                                    a_deg + b_deg,
+                                    ab.as_slice(),
+                                    input_deg,
+                                    idx,
+                                );
+                                if !diff.is_zero() {
+                                    return Err(ModuleFailedRelationError {
+                                        relation: format!(
+                                            "{} * {}  -  ({})",
+                                            algebra.basis_element_to_string(a_deg, a_idx),
+                                            algebra.basis_element_to_string(b_deg, b_idx),
+                                            algebra.element_to_string(a_deg + b_deg, ab.as_slice())
+                                        ),
+                                        value: self.element_to_string(output_deg, diff.as_slice()),
+                                        degree: input_deg,
+                                        element: self.basis_element_to_string(input_deg, idx),
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn to_json(&self, json: &mut Value) {
         if !self.name.is_empty() {
             json["name"] = Value::String(self.name.clone());
@@ -572,24 +906,29 @@ impl<A: GeneratedAlgebra> FiniteDimensionalModule<A> {
                 }
 
                 if !output_vec.is_zero() {
-                    let mut relation_string = String::new();
-                    for (coef, (deg_1, idx_1), (deg_2, idx_2)) in &relation {
-                        let _ = write!(
-                            relation_string,
-                            "{} * {} * {}  +  ",
-                            coef,
-                            algebra.basis_element_to_string(*deg_1, *idx_1),
-                            algebra.basis_element_to_string(*deg_2, *idx_2)
-                        );
-                    }
-                    for _ in 0..5 {
-                        relation_string.pop();
-                    }
+                    let relation_string = relation
+                        .iter()
+                        .map(|&(coef, (deg_1, idx_1), (deg_2, idx_2))| {
+                            let mut factors = Vec::new();
+                            if coef != 1 {
+                                factors.push(coef.to_string());
+                            }
+                            for (deg, idx) in [(deg_1, idx_1), (deg_2, idx_2)] {
+                                if deg > 0 {
+                                    factors.push(algebra.basis_element_to_string(deg, idx));
+                                }
+                            }
+                            factors.join(" * ")
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" + ");
 
                     let value_string = self.element_to_string(output_deg, output_vec.as_slice());
                     return Err(ModuleFailedRelationError {
                         relation: relation_string,
                         value: value_string,
+                        degree: input_deg,
+                        element: self.basis_element_to_string(input_deg, idx),
                     });
                 }
             }
@@ -679,6 +1018,92 @@ impl<A: GeneratedAlgebra> FiniteDimensionalModule<A> {
     }
 }
 
+impl FiniteDimensionalModule<SteenrodAlgebra> {
+    /// The Spanier–Whitehead dual of the module. The dual of a basis element `x` in degree $n$ is
+    /// named `x*` and is in degree $-n$, and an operation $a$ acts by $(af)(m) = (-1)^{|a||f|}
+    /// f(\chi(a) m)$, where $\chi$ is the antipode.
+    pub fn dual(&self) -> anyhow::Result<Self> {
+        let algebra = self.algebra();
+        let p = self.prime();
+        let min_degree = self.min_degree();
+        let max_degree = self.graded_dimension.len() - 1;
+        let q = if p == 2 { 1 } else { 2 * p.as_i32() - 2 };
+
+        let mut graded_dimension = BiVec::with_capacity(-max_degree, -min_degree + 1);
+        for t in -max_degree..=-min_degree {
+            graded_dimension.push(self.dimension(-t));
+        }
+        let mut result = Self::new(
+            Arc::clone(&algebra),
+            format!("D({})", self.name),
+            graded_dimension,
+        );
+        for (t, gens) in self.gen_names.iter_enum() {
+            for (idx, name) in gens.iter().enumerate() {
+                result.set_basis_element_name(-t, idx, format!("{name}*"));
+            }
+        }
+
+        let span = max_degree - min_degree;
+        let antipodes = algebra
+            .power_antipodes((span / q) as u32)
+            .ok_or_else(|| anyhow!("Failed to compute antipodes"))?;
+
+        for op_degree in 1..=span {
+            for op_idx in algebra.generators(op_degree) {
+                let chi = if p != 2 && op_degree == 1 {
+                    // The Bockstein is primitive, so chi(b) = -b
+                    let mut chi = FpVector::new(p, 1);
+                    chi.set_entry(0, p - 1);
+                    chi
+                } else if op_degree % q == 0
+                    && algebra.try_beps_pn(0, (op_degree / q) as u32) == Some((op_degree, op_idx))
+                {
+                    antipodes[(op_degree / q) as usize].clone()
+                } else {
+                    return Err(anyhow!(
+                        "Cannot dualize over an algebra with generator {}",
+                        algebra.generator_to_string(op_degree, op_idx)
+                    ));
+                };
+
+                for input_degree in -max_degree..=-min_degree - op_degree {
+                    let output_degree = input_degree + op_degree;
+                    let sign = if (op_degree * input_degree) % 2 == 0 {
+                        1
+                    } else {
+                        p - 1
+                    };
+                    // The action is the transpose of chi(a): M_{-output_degree} -> M_{-input_degree}
+                    let mut image = FpVector::new(p, self.dimension(-input_degree));
+                    for j in 0..self.dimension(-output_degree) {
+                        image.set_to_zero();
+                        for (idx, c) in chi.iter_nonzero() {
+                            self.act_on_basis(
+                                image.as_slice_mut(),
+                                sign * c % p,
+                                op_degree,
+                                idx,
+                                -output_degree,
+                                j,
+                            );
+                        }
+                        for (i, c) in image.iter_nonzero() {
+                            result
+                                .action_mut(op_degree, op_idx, input_degree, i)
+                                .add_basis_element(j, c);
+                        }
+                    }
+                }
+            }
+        }
+        result
+            .complete_actions()
+            .context("Dual module does not satisfy the relations of the algebra")?;
+        Ok(result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -826,4 +1251,104 @@ mod tests {
         assert!(module.check_validity(1, 1).is_ok());
         assert!(module.check_validity(2, 1).is_ok());
     }
+
+    #[test]
+    fn dual_and_direct_sum() {
+        let algebra = Arc::new(SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(
+            fp::prime::TWO,
+            false,
+        )));
+        let joker =
+            FiniteDimensionalModule::from_json(algebra, &crate::tests::joker_json()).unwrap();
+
+        let dual = joker.dual().unwrap();
+        assert_eq!(dual.min_degree(), -4);
+        assert_eq!(dual.max_degree(), Some(0));
+        // Sq^2 x4* = x2* since chi(Sq^2) = Sq^2
+        let (degree, idx) = dual.string_to_basis_element("x4*").unwrap();
+        assert_eq!(degree, -4);
+        assert!(!dual.action(2, 0, degree, idx).is_zero());
+        dual.dual().unwrap().test_equal(&joker).unwrap();
+
+        let sum = joker.direct_sum(&dual);
+        assert_eq!(sum.min_degree(), -4);
+        assert_eq!(sum.dimension(0), 2);
+        assert_eq!(sum.string_to_basis_element("x0"), Some((0, 0)));
+        assert_eq!(sum.string_to_basis_element("x0*"), Some((0, 1)));
+
+        let double = joker.direct_sum(&joker);
+        assert_eq!(double.string_to_basis_element("x2'"), Some((2, 1)));
+        assert!(!double.action(1, 0, 3, 1).is_zero());
+        assert_eq!(double.action(1, 0, 3, 0).entry(1), 0);
+    }
+
+    #[test]
+    fn minimal_presentation() {
+        use crate::algebra::MilnorAlgebra;
+
+        let joker_3 = serde_json::json!({
+            "gens": {"x0": 0, "x4": 4, "x8": 8},
+            "actions": ["P1 x0 = x4", "P1 x4 = 2 x8"]
+        });
+        for (p, json) in [(2, crate::tests::joker_json()), (3, joker_3)] {
+            let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+                fp::prime::ValidPrime::new(p),
+                false,
+            )));
+            let module = FiniteDimensionalModule::from_json(Arc::clone(&algebra), &json).unwrap();
+            let max_degree = module.max_degree().unwrap();
+
+            let presentation = module.minimal_presentation(20);
+            assert_eq!(presentation.generators().number_of_gens_in_degree(0), 1);
+            FiniteDimensionalModule::truncation(&presentation, 0, max_degree)
+                .test_equal(&module)
+                .unwrap();
+            // Nothing survives above the top degree
+            presentation.compute_basis(20);
+            assert!((max_degree + 1..=20).all(|t| presentation.dimension(t) == 0));
+
+            let mut json = serde_json::json!({});
+            presentation.to_json(&mut json);
+            let reloaded = FPModule::from_json(algebra, &json).unwrap();
+            FiniteDimensionalModule::truncation(&reloaded, 0, max_degree)
+                .test_equal(&module)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn validation() {
+        let algebra = Arc::new(AdemAlgebra::new(fp::prime::TWO, false));
+        let joker = |extra: &[&str]| {
+            let mut json = crate::tests::joker_json();
+            for &action in extra {
+                json["actions"].as_array_mut().unwrap().push(action.into());
+            }
+            FiniteDimensionalModule::from_json(Arc::clone(&algebra), &json)
+        };
+        let expected = joker(&[]).unwrap();
+        expected.check_products().unwrap();
+
+        // Actions of non-generators are checked rather than added to the computed ones
+        joker(&["Sq3 x1 = x4", "Sq2 Sq1 x0 = x3"])
+            .unwrap()
+            .test_equal(&expected)
+            .unwrap();
+        let Err(err) = joker(&["Sq3 x0 = x3"]) else {
+            panic!("Expected an inconsistent action");
+        };
+        let err = err.to_string();
+        assert!(err.contains("Sq3 x0 = x3 is inconsistent"), "{err}");
+
+        // Sq2 Sq2 x0 = Sq3 Sq1 x0 fails
+        let json = serde_json::json!({
+            "gens": {"x0": 0, "x1": 1, "x2": 2, "x3": 3, "x4": 4},
+            "actions": ["Sq1 x0 = x1", "Sq2 x0 = x2", "Sq2 x1 = x3", "Sq1 x3 = x4"]
+        });
+        let Err(err) = FiniteDimensionalModule::from_json(Arc::clone(&algebra), &json) else {
+            panic!("Expected a failed relation");
+        };
+        let err = err.downcast::<ModuleFailedRelationError>().unwrap();
+        assert_eq!((err.degree, &*err.element), (0, "x0"));
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use fp::vector::{FpSliceMut, FpVector};
 use itertools::Itertools;
 use once::OnceBiVec;
-use serde_json::Value;
+use serde_json::{Value, json};
 
 use crate::{
     algebra::Algebra,
@@ -129,6 +129,7 @@ impl<A: Algebra> FinitelyPresentedModule<A> {
                 for term in reln.as_str().unwrap().split(" + ") {
                     let (term, coef) = opt(digits).parse(term).unwrap();
                     let coef: u32 = coef.unwrap_or(1);
+                    let term = term.trim_start();
 
                     let (op, g) = term.rsplit_once(' ').unwrap_or(("1", term));
                     let (op_deg, op_idx) = algebra
@@ -170,6 +171,36 @@ impl<A: Algebra> FinitelyPresentedModule<A> {
     }
 }
 
+impl<A: Algebra> FinitelyPresentedModule<A> {
+    /// Writes the module in the format read by [`FinitelyPresentedModule::from_json`]. The
+    /// relations are only written in the basis of our algebra, so the result can only be read
+    /// with the same kind of algebra.
+    pub fn to_json(&self, json: &mut Value) {
+        if !self.name.is_empty() {
+            json["name"] = Value::String(self.name.clone());
+        }
+        json["type"] = Value::from("finitely presented module");
+        json["gens"] = json!({});
+        for (degree, names) in self.generators.gen_names().iter() {
+            for name in names {
+                json["gens"][name] = Value::from(degree);
+            }
+        }
+
+        let mut relations = Vec::new();
+        for (degree, names) in self.relations.gen_names().iter() {
+            for idx in 0..names.len() {
+                let relation = self.map.output(degree, idx);
+                relations.push(Value::from(
+                    self.generators
+                        .element_to_string(degree, relation.as_slice()),
+                ));
+            }
+        }
+        json[format!("{}_relations", self.algebra().prefix())] = Value::Array(relations);
+    }
+}
+
 impl<A: Algebra> Module for FinitelyPresentedModule<A> {
     type Algebra = A;
 
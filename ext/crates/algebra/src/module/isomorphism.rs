@@ -0,0 +1,591 @@
+//! Homomorphisms and isomorphisms between finite dimensional modules.
+//!
+//! A homomorphism $f\colon M \to N$ of finite dimensional modules is a family of linear maps
+//! $f_n\colon M_n \to N_n$ that commute with the action of the generators of the algebra. These
+//! form a vector space $\operatorname{Hom}(M, N)$, which we compute by solving the corresponding
+//! system of linear equations. The modules are isomorphic if and only if this space contains a map
+//! that is invertible in every degree.
+//!
+//! Finding such a map is not a linear problem. Before searching, we compare invariants of the two
+//! modules, namely the graded dimensions, the ranks of the actions of the basis elements of the
+//! algebra and the dimensions of the various Hom spaces, and report the first one that differs as
+//! an [`Obstruction`]. If the Hom space is small, we then check every element. Otherwise we try
+//! random elements, which finds an isomorphism with high probability if there is one, but cannot
+//! prove that there isn't.
+
+use std::fmt;
+
+use anyhow::ensure;
+use bivec::BiVec;
+use fp::{
+    matrix::{AugmentedMatrix, Matrix, Subspace},
+    prime::{Prime, ValidPrime},
+    vector::{FpSlice, FpVector},
+};
+use serde_json::{Value, json};
+
+use crate::{
+    algebra::{Algebra, GeneratedAlgebra},
+    module::{FDModule, Module},
+};
+
+/// The largest Hom space that we search exhaustively.
+const EXHAUSTIVE_LIMIT: u64 = 1 << 16;
+/// The number of random elements we try if the Hom space is too large to search exhaustively.
+const RANDOM_TRIALS: usize = 1 << 12;
+
+/// A map of graded vector spaces. The matrix in degree $n$ has the images of the basis elements of
+/// the source in degree $n$ as its rows.
+pub type GradedMap = BiVec<Matrix>;
+
+/// A reason two modules are not isomorphic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Obstruction {
+    /// The modules have different dimensions in `degree`.
+    Dimension {
+        degree: i32,
+        source: usize,
+        target: usize,
+    },
+    /// The action of `operation` out of `degree` has different ranks.
+    Rank {
+        operation: String,
+        degree: i32,
+        source: usize,
+        target: usize,
+    },
+    /// The dimensions of $\operatorname{End}(M)$, $\operatorname{End}(N)$,
+    /// $\operatorname{Hom}(M, N)$ and $\operatorname{Hom}(N, M)$, which are not all equal.
+    HomDimension([usize; 4]),
+    /// None of the homomorphisms is invertible. This is only reported if we checked all of them.
+    NoInvertibleMap { homomorphisms: usize },
+}
+
+impl fmt::Display for Obstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dimension {
+                degree,
+                source,
+                target,
+            } => write!(
+                f,
+                "The modules have dimensions {source} and {target} in degree {degree}"
+            ),
+            Self::Rank {
+                operation,
+                degree,
+                source,
+                target,
+            } => write!(
+                f,
+                "{operation} has rank {source} and {target} out of degree {degree}"
+            ),
+            Self::HomDimension([end_m, end_n, hom_mn, hom_nm]) => write!(
+                f,
+                "dim End(M) = {end_m}, dim End(N) = {end_n}, dim Hom(M, N) = {hom_mn} and dim \
+                 Hom(N, M) = {hom_nm} are not all equal"
+            ),
+            Self::NoInvertibleMap { homomorphisms } => write!(
+                f,
+                "None of the {homomorphisms}-dimensional space of homomorphisms is invertible"
+            ),
+        }
+    }
+}
+
+/// The result of [`find_isomorphism`].
+#[derive(Debug, Clone)]
+pub enum Isomorphism {
+    /// An isomorphism from the source to the target.
+    Found(GradedMap),
+    /// The modules are not isomorphic.
+    Obstructed(Obstruction),
+    /// The invariants agree but the search did not find an isomorphism.
+    Unknown,
+}
+
+/// The position of the entries of a graded map in a single vector. The entry $(i, j)$ of the
+/// matrix in degree $n$ is at `offsets[n] + i * target.dimension(n) + j`.
+struct Layout {
+    p: ValidPrime,
+    source_dims: BiVec<usize>,
+    target_dims: BiVec<usize>,
+    offsets: BiVec<usize>,
+    len: usize,
+}
+
+impl Layout {
+    fn new<A: Algebra>(source: &FDModule<A>, target: &FDModule<A>) -> Self {
+        let min_degree = std::cmp::min(source.min_degree(), target.min_degree());
+        // The zero module has no basis elements, so it does not contribute any degrees
+        let max_degree = [source, target]
+            .iter()
+            .filter_map(|m| m.max_degree())
+            .fold(min_degree - 1, std::cmp::max);
+        let mut source_dims = BiVec::new(min_degree);
+        let mut target_dims = BiVec::new(min_degree);
+        let mut offsets = BiVec::new(min_degree);
+        let mut len = 0;
+        for degree in min_degree..=max_degree {
+            source_dims.push(source.dimension(degree));
+            target_dims.push(target.dimension(degree));
+            offsets.push(len);
+            len += source.dimension(degree) * target.dimension(degree);
+        }
+        Self {
+            p: source.prime(),
+            source_dims,
+            target_dims,
+            offsets,
+            len,
+        }
+    }
+
+    fn index(&self, degree: i32, i: usize, j: usize) -> usize {
+        self.offsets[degree] + i * self.target_dims[degree] + j
+    }
+
+    fn to_graded_map(&self, v: FpSlice) -> GradedMap {
+        let mut result = BiVec::new(self.offsets.min_degree());
+        for (degree, &offset) in self.offsets.iter_enum() {
+            let (rows, columns) = (self.source_dims[degree], self.target_dims[degree]);
+            let mut matrix = Matrix::new(self.p, rows, columns);
+            for i in 0..rows {
+                let start = offset + i * columns;
+                matrix.row_mut(i).assign(v.restrict(start, start + columns));
+            }
+            result.push(matrix);
+        }
+        result
+    }
+
+    /// Whether the map is invertible, assuming the source and target have the same dimensions.
+    fn is_invertible(&self, v: FpSlice) -> bool {
+        self.to_graded_map(v)
+            .into_iter()
+            .all(|mut matrix| matrix.rows() == matrix.row_reduce())
+    }
+}
+
+/// The matrix of the action of the basis element `op_index` in degree `op_degree` on the basis
+/// elements of `module` in degree `degree`.
+fn action_matrix<A: Algebra>(
+    module: &FDModule<A>,
+    op_degree: i32,
+    op_index: usize,
+    degree: i32,
+) -> Matrix {
+    let p = module.prime();
+    let mut matrix = Matrix::new(
+        p,
+        module.dimension(degree),
+        module.dimension(degree + op_degree),
+    );
+    for i in 0..module.dimension(degree) {
+        module.act_on_basis(matrix.row_mut(i), 1, op_degree, op_index, degree, i);
+    }
+    matrix
+}
+
+/// The space of homomorphisms from `source` to `target`, as a subspace of vectors in the layout.
+fn hom_space<A: GeneratedAlgebra>(
+    source: &FDModule<A>,
+    target: &FDModule<A>,
+) -> (Layout, Subspace) {
+    let layout = Layout::new(source, target);
+    let p = layout.p;
+    let algebra = source.algebra();
+    let min_degree = layout.offsets.min_degree();
+    let max_degree = layout.offsets.max_degree();
+    algebra.compute_basis(std::cmp::max(max_degree - min_degree, 0));
+
+    // Each equation is a vector of coefficients of the unknown entries of the map. For a
+    // generator a and a basis element m of the source, we need f(am) = a f(m).
+    let mut equations: Vec<FpVector> = Vec::new();
+    for degree in min_degree..=max_degree {
+        for output_degree in degree + 1..=max_degree {
+            let op_degree = output_degree - degree;
+            let target_dim = layout.target_dims[output_degree];
+            if layout.source_dims[degree] == 0 || target_dim == 0 {
+                continue;
+            }
+            for op_index in algebra.generators(op_degree) {
+                let source_action = action_matrix(source, op_degree, op_index, degree);
+                let target_action = action_matrix(target, op_degree, op_index, degree);
+                for (i, am) in source_action.iter().enumerate() {
+                    for r in 0..target_dim {
+                        let mut equation = FpVector::new(p, layout.len);
+                        for (l, c) in am.iter_nonzero() {
+                            equation.add_basis_element(layout.index(output_degree, l, r), c);
+                        }
+                        for (j, an) in target_action.iter().enumerate() {
+                            let c = an.entry(r);
+                            if c != 0 {
+                                equation
+                                    .add_basis_element(layout.index(degree, i, j), p.as_u32() - c);
+                            }
+                        }
+                        if !equation.is_zero() {
+                            equations.push(equation);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // The kernel of the transpose of the matrix of equations
+    let mut matrix = AugmentedMatrix::<2>::new(p, layout.len, [equations.len(), layout.len]);
+    for (k, equation) in equations.iter().enumerate() {
+        for (u, c) in equation.iter_nonzero() {
+            matrix.row_segment_mut(u, 0, 0).set_entry(k, c);
+        }
+    }
+    for u in 0..layout.len {
+        matrix.row_segment_mut(u, 1, 1).set_entry(u, 1);
+    }
+    matrix.row_reduce();
+    let kernel = matrix.compute_kernel();
+    (layout, kernel)
+}
+
+/// A basis of the space of homomorphisms from `source` to `target`.
+pub fn homomorphisms<A: GeneratedAlgebra>(
+    source: &FDModule<A>,
+    target: &FDModule<A>,
+) -> Vec<GradedMap> {
+    let (layout, hom) = hom_space(source, target);
+    hom.basis().map(|v| layout.to_graded_map(v)).collect()
+}
+
+/// The first invariant that differs between `source` and `target`, ordered by degree.
+fn invariant_obstruction<A: GeneratedAlgebra>(
+    source: &FDModule<A>,
+    target: &FDModule<A>,
+) -> Option<Obstruction> {
+    let layout = Layout::new(source, target);
+    if let Some((degree, (&s, &t))) = layout
+        .source_dims
+        .iter_enum()
+        .zip(layout.target_dims.iter())
+        .map(|((degree, s), t)| (degree, (s, t)))
+        .find(|(_, (s, t))| s != t)
+    {
+        return Some(Obstruction::Dimension {
+            degree,
+            source: s,
+            target: t,
+        });
+    }
+
+    let algebra = source.algebra();
+    let min_degree = layout.offsets.min_degree();
+    let max_degree = layout.offsets.max_degree();
+    algebra.compute_basis(std::cmp::max(max_degree - min_degree, 0));
+    for degree in min_degree..=max_degree {
+        for output_degree in degree + 1..=max_degree {
+            let op_degree = output_degree - degree;
+            for op_index in 0..algebra.dimension(op_degree) {
+                let source_rank = action_matrix(source, op_degree, op_index, degree).row_reduce();
+                let target_rank = action_matrix(target, op_degree, op_index, degree).row_reduce();
+                if source_rank != target_rank {
+                    return Some(Obstruction::Rank {
+                        operation: algebra.basis_element_to_string(op_degree, op_index),
+                        degree,
+                        source: source_rank,
+                        target: target_rank,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether we can afford to check every element of a space of dimension `dim`.
+fn is_small(p: ValidPrime, dim: usize) -> bool {
+    u64::from(p.as_u32())
+        .checked_pow(dim as u32)
+        .is_some_and(|n| n <= EXHAUSTIVE_LIMIT)
+}
+
+/// Search for an isomorphism from `source` to `target`. If the modules are not isomorphic, this
+/// returns the first invariant that tells them apart.
+pub fn find_isomorphism<A: GeneratedAlgebra>(
+    source: &FDModule<A>,
+    target: &FDModule<A>,
+) -> anyhow::Result<Isomorphism> {
+    ensure!(
+        source.prime() == target.prime(),
+        "The modules are defined over different primes"
+    );
+    let (source_algebra, target_algebra) = (source.algebra(), target.algebra());
+    ensure!(
+        source_algebra.prefix() == target_algebra.prefix()
+            && source_algebra.magic() == target_algebra.magic(),
+        "The modules are defined over different algebras, {source_algebra} and {target_algebra}"
+    );
+    if let Some(obstruction) = invariant_obstruction(source, target) {
+        return Ok(Isomorphism::Obstructed(obstruction));
+    }
+
+    let (layout, hom) = hom_space(source, target);
+    let dims = [
+        hom_space(source, source).1.dimension(),
+        hom_space(target, target).1.dimension(),
+        hom.dimension(),
+        hom_space(target, source).1.dimension(),
+    ];
+    if dims.iter().any(|&d| d != dims[0]) {
+        return Ok(Isomorphism::Obstructed(Obstruction::HomDimension(dims)));
+    }
+
+    let p = layout.p;
+    if is_small(p, hom.dimension()) {
+        return Ok(
+            match hom
+                .iter_all_vectors()
+                .find(|v| layout.is_invertible(v.as_slice()))
+            {
+                Some(v) => Isomorphism::Found(layout.to_graded_map(v.as_slice())),
+                None => Isomorphism::Obstructed(Obstruction::NoInvertibleMap {
+                    homomorphisms: hom.dimension(),
+                }),
+            },
+        );
+    }
+
+    // A xorshift generator, so that the search is reproducible
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % u64::from(p.as_u32())) as u32
+    };
+    let mut v = FpVector::new(p, layout.len);
+    for _ in 0..RANDOM_TRIALS {
+        v.set_to_zero();
+        for basis_vector in hom.basis() {
+            v.as_slice_mut().add(basis_vector, next());
+        }
+        if layout.is_invertible(v.as_slice()) {
+            return Ok(Isomorphism::Found(layout.to_graded_map(v.as_slice())));
+        }
+    }
+    Ok(Isomorphism::Unknown)
+}
+
+/// All automorphisms of `module`. This fails if there are too many endomorphisms to check.
+pub fn automorphisms<A: GeneratedAlgebra>(module: &FDModule<A>) -> anyhow::Result<Vec<GradedMap>> {
+    let (layout, end) = hom_space(module, module);
+    ensure!(
+        is_small(layout.p, end.dimension()),
+        "The endomorphism ring has dimension {}, which is too large to enumerate",
+        end.dimension()
+    );
+    Ok(end
+        .iter_all_vectors()
+        .filter(|v| layout.is_invertible(v.as_slice()))
+        .map(|v| layout.to_graded_map(v.as_slice()))
+        .collect())
+}
+
+/// Whether `map` is a homomorphism from `source` to `target`.
+pub fn is_homomorphism<A: GeneratedAlgebra>(
+    source: &FDModule<A>,
+    target: &FDModule<A>,
+    map: &GradedMap,
+) -> bool {
+    let p = source.prime();
+    let algebra = source.algebra();
+    let apply = |degree: i32, v: FpSlice| {
+        let mut result = FpVector::new(p, target.dimension(degree));
+        if let Some(matrix) = map.get(degree) {
+            matrix.apply(result.as_slice_mut(), 1, v);
+        }
+        result
+    };
+    let (min_degree, max_degree) = (map.min_degree(), map.max_degree());
+    for degree in min_degree..=max_degree {
+        for output_degree in degree + 1..=max_degree {
+            let op_degree = output_degree - degree;
+            for op_index in algebra.generators(op_degree) {
+                for i in 0..source.dimension(degree) {
+                    let mut am = FpVector::new(p, source.dimension(output_degree));
+                    source.act_on_basis(am.as_slice_mut(), 1, op_degree, op_index, degree, i);
+                    let left = apply(output_degree, am.as_slice());
+
+                    let mut right = FpVector::new(p, target.dimension(output_degree));
+                    let fm = map[degree].row(i);
+                    target.act(right.as_slice_mut(), 1, op_degree, op_index, degree, fm);
+                    if left != right {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+/// The json of `module` with its basis elements renamed after their degrees. The basis elements
+/// in degree $n$ are renamed to `xn`, or `xn_i` if there are several (with `m` in place of a minus
+/// sign), and the actions of the generators are listed in order of degree.
+///
+/// This only removes the dependence on the names and the formatting of the original json. It still
+/// depends on the chosen basis, including the order of the basis elements in each degree, so
+/// isomorphic modules can have different outputs. Use [`find_isomorphism`] to compare modules up
+/// to isomorphism.
+pub fn canonical_json<A: GeneratedAlgebra>(module: &FDModule<A>) -> Value {
+    let mut module = module.clone();
+    module.name = String::new();
+    let min_degree = module.min_degree();
+    let max_degree = module.max_degree().unwrap_or(min_degree - 1);
+    for degree in min_degree..=max_degree {
+        let dim = module.dimension(degree);
+        let degree_str = if degree < 0 {
+            format!("m{}", -degree)
+        } else {
+            degree.to_string()
+        };
+        for i in 0..dim {
+            let name = if dim == 1 {
+                format!("x{degree_str}")
+            } else {
+                format!("x{degree_str}_{i}")
+            };
+            module.set_basis_element_name(degree, i, name);
+        }
+    }
+    let mut json = json!({ "p": module.prime().as_u32() });
+    module.to_json(&mut json);
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fp::prime::TWO;
+
+    use super::*;
+    use crate::{AdemAlgebra, MilnorAlgebra, algebra::SteenrodAlgebra};
+
+    fn module(json: Value) -> FDModule<SteenrodAlgebra> {
+        let p = ValidPrime::new(json["p"].as_u64().unwrap() as u32);
+        let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(p, false)));
+        FDModule::from_json(algebra, &json).unwrap()
+    }
+
+    #[test]
+    fn change_of_basis() {
+        // C2 + C2[1], and the same module with the basis {y + z, z} in degree 1
+        let source = module(json!({
+            "p": 2,
+            "gens": {"x": 0, "y": 1, "z": 1, "w": 2},
+            "actions": ["Sq1 x = y", "Sq1 z = w"]
+        }));
+        let target = module(json!({
+            "p": 2,
+            "gens": {"x": 0, "a": 1, "b": 1, "w": 2},
+            "actions": ["Sq1 x = a + b", "Sq1 a = w", "Sq1 b = w"]
+        }));
+        let Isomorphism::Found(map) = find_isomorphism(&source, &target).unwrap() else {
+            panic!("Expected an isomorphism");
+        };
+        assert!(is_homomorphism(&source, &target, &map));
+    }
+
+    #[test]
+    fn obstructions() {
+        let joker = module(crate::tests::joker_json());
+        let c2 = module(json!({
+            "p": 2,
+            "gens": {"x0": 0, "x1": 1},
+            "actions": ["Sq1 x0 = x1"]
+        }));
+        let split = module(json!({"p": 2, "gens": {"x0": 0, "x1": 1}, "actions": []}));
+        let Isomorphism::Obstructed(obstruction) = find_isomorphism(&c2, &split).unwrap() else {
+            panic!("Expected an obstruction");
+        };
+        assert_eq!(
+            obstruction,
+            Obstruction::Rank {
+                operation: "P(1)".to_string(),
+                degree: 0,
+                source: 1,
+                target: 0,
+            }
+        );
+
+        let sphere = module(json!({"p": 2, "gens": {"x0": 0}, "actions": []}));
+        assert!(matches!(
+            find_isomorphism(&joker, &sphere).unwrap(),
+            Isomorphism::Obstructed(Obstruction::Dimension { degree: 1, .. })
+        ));
+
+        let zero = module(json!({"p": 2, "gens": {}, "actions": []}));
+        assert!(matches!(
+            find_isomorphism(&zero, &zero).unwrap(),
+            Isomorphism::Found(_)
+        ));
+        assert!(matches!(
+            find_isomorphism(&zero, &sphere).unwrap(),
+            Isomorphism::Obstructed(Obstruction::Dimension { degree: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn different_algebras() {
+        let milnor = module(crate::tests::joker_json());
+        let adem_algebra = Arc::new(SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(TWO, false)));
+        let adem = FDModule::from_json(adem_algebra, &crate::tests::joker_json()).unwrap();
+        let err = find_isomorphism(&milnor, &adem).unwrap_err();
+        assert!(err.to_string().contains("different algebras"), "{err}");
+    }
+
+    #[test]
+    fn automorphism_groups() {
+        let joker = module(crate::tests::joker_json());
+        assert_eq!(homomorphisms(&joker, &joker).len(), 1);
+        assert_eq!(automorphisms(&joker).unwrap().len(), 1);
+
+        // The automorphisms of C2 + C2 form GL_2(F_2)
+        let two_c2 = module(json!({
+            "p": 2,
+            "gens": {"x0": 0, "x1": 1, "y0": 0, "y1": 1},
+            "actions": ["Sq1 x0 = x1", "Sq1 y0 = y1"]
+        }));
+        assert_eq!(homomorphisms(&two_c2, &two_c2).len(), 4);
+        let autos = automorphisms(&two_c2).unwrap();
+        assert_eq!(autos.len(), 6);
+        for map in &autos {
+            assert!(is_homomorphism(&two_c2, &two_c2, map));
+        }
+    }
+
+    #[test]
+    fn canonical_form() {
+        let source = module(crate::tests::joker_json());
+        let json = canonical_json(&source);
+        assert_eq!(
+            json,
+            json!({
+                "p": 2,
+                "type": "finite dimensional module",
+                "gens": {"x0": 0, "x1": 1, "x2": 2, "x3": 3, "x4": 4},
+                "actions": [
+                    "Sq1 x0 = x1",
+                    "Sq2 x0 = x2",
+                    "Sq2 x1 = x3",
+                    "Sq2 x2 = x4",
+                    "Sq1 x3 = x4",
+                ]
+            })
+        );
+        let reloaded = module(json);
+        assert_eq!(canonical_json(&reloaded), canonical_json(&source));
+    }
+}
@@ -0,0 +1,595 @@
+//! Modules that are generated from formulas for the Steenrod action, rather than written by hand.
+//!
+//! The spaces and spectra here have cohomology built out of polynomial generators $x$ whose total
+//! Steenrod power is $P(x) = x + x^p$ (or $Sq(x) = x + x^2$), so the Cartan formula determines
+//! the action on all of their cohomology. We compute the action of the generators of the Steenrod
+//! algebra this way, and let [`FDModule::complete_actions`] compute the remaining operations and
+//! check the relations. The modules are finite dimensional, so infinite complexes have to be
+//! truncated with a `max`.
+//!
+//! The modules can be constructed from JSON with the following `type`s:
+//!
+//!  - `complex projective space`: $\mathbb{CP}^{\mathrm{max}}_{\mathrm{min}}$, with basis $x^i$
+//!    in degree $2i$ for $\mathrm{min} \leq i \leq \mathrm{max}$. The bounds can be negative, in
+//!    which case this is a Thom spectrum over $\mathbb{CP}^\infty$.
+//!  - `quaternionic projective space`: $\mathbb{HP}^{\mathrm{max}}_{\mathrm{min}}$, with basis
+//!    $u^i$ in degree $4i$.
+//!  - `lens space`: the cells of $B\mathbb{Z}/p$ between degrees `min` and `max` at an odd prime,
+//!    with basis $y^i$ and $xy^i$, where $\beta x = y$.
+//!  - `thom spectrum`: the `max`-skeleton of $MO$, $MU$ or $MSp$, depending on `group`, which is
+//!    one of `O`, `U` and `Sp`. The basis consists of $U m_\lambda$, where $U$ is the Thom class
+//!    and $m_\lambda$ is the monomial symmetric function in the Chern roots (or Stiefel-Whitney or
+//!    symplectic Pontryagin roots).
+//!  - `A//E(n)`: the cohomology of $BP\langle n \rangle$, i.e. the quotient of the Steenrod
+//!    algebra by the left ideal generated by $Q_0, \ldots, Q_n$. This is a finitely presented
+//!    module and is not truncated.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::Arc,
+};
+
+use anyhow::{Context, anyhow, ensure};
+use bivec::BiVec;
+use fp::{
+    prime::{Binomial, Prime, ValidPrime},
+    vector::FpVector,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    algebra::{Algebra, GeneratedAlgebra, SteenrodAlgebra},
+    module::{FDModule, FPModule, Module},
+    steenrod_evaluator::SteenrodEvaluator,
+};
+
+/// A generator of the Steenrod algebra.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Generator {
+    /// The Milnor primitive $Q_k$. $Q_0$ is the Bockstein.
+    Q(u32),
+    /// The Steenrod power $P^k$, or $Sq^k$ at the prime 2.
+    Power(u32),
+}
+
+impl Generator {
+    fn from_index(algebra: &SteenrodAlgebra, degree: i32, idx: usize) -> anyhow::Result<Self> {
+        match algebra {
+            SteenrodAlgebra::AdemAlgebra(a) => {
+                let elt = a.basis_element_from_index(degree, idx);
+                match (elt.bocksteins, &elt.ps[..]) {
+                    (1, []) => Ok(Self::Q(0)),
+                    (0, &[k]) => Ok(Self::Power(k)),
+                    _ => Err(anyhow!("Unexpected generator {elt}")),
+                }
+            }
+            SteenrodAlgebra::MilnorAlgebra(a) => {
+                let elt = a.basis_element_from_index(degree, idx);
+                match (elt.q_part, &elt.p_part[..]) {
+                    (q, []) if q.is_power_of_two() => Ok(Self::Q(q.trailing_zeros())),
+                    (0, &[k]) => Ok(Self::Power(k)),
+                    _ => Err(anyhow!(
+                        "Unexpected generator {elt}; only the full Steenrod algebra is supported"
+                    )),
+                }
+            }
+            // The generators are the squares Sq^{2^k}
+            SteenrodAlgebra::ChangeOfBasisAlgebra(_) => Ok(Self::Power(degree as u32)),
+        }
+    }
+
+    /// The number of times $P^1$ (or $Sq^{\mathrm{degree}}$ at $p = 2$) has to be applied to a
+    /// polynomial generator of degree `degree` to get this operation, if it acts on polynomial
+    /// generators of this degree at all. At the prime 2, $Sq^{2k}$ acts on a class of degree 2 the
+    /// way $Sq^k$ acts on a class of degree 1, and odd squares act trivially.
+    fn steps(self, p: ValidPrime, degree: u32) -> Option<u32> {
+        match self {
+            Self::Q(_) => None,
+            Self::Power(k) if p == 2 => k.is_multiple_of(degree).then_some(k / degree),
+            Self::Power(k) => Some(k),
+        }
+    }
+}
+
+/// $\binom{n}{k}$ mod $p$, where $n$ may be negative.
+fn binomial(p: ValidPrime, n: i32, k: u32) -> u32 {
+    if n >= 0 {
+        if k > n as u32 {
+            0
+        } else {
+            u32::binomial(p, n as u32, k)
+        }
+    } else {
+        // binom(n, k) = (-1)^k binom(k - n - 1, k)
+        let c = u32::binomial(p, k + (-n) as u32 - 1, k);
+        if k.is_multiple_of(2) { c } else { (p - c) % p }
+    }
+}
+
+/// Builds a finite dimensional module with basis `basis[d]` in degree `d`, where the action of a
+/// generator on a basis element is given by `act` as a list of terms. Terms outside the module are
+/// ignored.
+fn build<B: Clone + Eq + Hash>(
+    algebra: Arc<SteenrodAlgebra>,
+    name: String,
+    basis: BiVec<Vec<B>>,
+    to_string: impl Fn(&B) -> String,
+    act: impl Fn(Generator, &B) -> Vec<(B, u32)>,
+) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    let p = algebra.prime();
+    let min_degree = basis.min_degree();
+    let max_degree = basis.max_degree();
+    algebra.compute_basis(max_degree - min_degree);
+
+    let mut graded_dimension = BiVec::with_capacity(min_degree, max_degree + 1);
+    let mut index = HashMap::new();
+    for (degree, elements) in basis.iter_enum() {
+        graded_dimension.push(elements.len());
+        for (idx, b) in elements.iter().enumerate() {
+            index.insert(b.clone(), (degree, idx));
+        }
+    }
+
+    let mut module = FDModule::new(Arc::clone(&algebra), name, graded_dimension);
+    for (degree, elements) in basis.iter_enum() {
+        for (idx, b) in elements.iter().enumerate() {
+            module.set_basis_element_name(degree, idx, to_string(b));
+        }
+    }
+
+    for (input_degree, elements) in basis.iter_enum() {
+        for (input_idx, b) in elements.iter().enumerate() {
+            for op_degree in 1..=max_degree - input_degree {
+                let output_dim = module.dimension(input_degree + op_degree);
+                if output_dim == 0 {
+                    continue;
+                }
+                for op_idx in algebra.generators(op_degree) {
+                    let generator = Generator::from_index(&algebra, op_degree, op_idx)?;
+                    let mut output = vec![0; output_dim];
+                    for (target, coef) in act(generator, b) {
+                        if let Some(&(degree, idx)) = index.get(&target) {
+                            assert_eq!(degree, input_degree + op_degree);
+                            output[idx] = (output[idx] + coef) % p;
+                        }
+                    }
+                    module.set_action(op_degree, op_idx, input_degree, input_idx, &output);
+                }
+            }
+        }
+    }
+
+    module
+        .complete_actions()
+        .context("Generated module does not satisfy the relations of the Steenrod algebra")?;
+    Ok(module)
+}
+
+/// The action of a generator on $x^e$, where $x$ is a polynomial generator of degree 2 with
+/// $P(x) = x + x^p$, as a power of $x$ and a coefficient.
+fn act_on_complex_power(p: ValidPrime, generator: Generator, e: i32) -> Option<(i32, u32)> {
+    let steps = generator.steps(p, 2)?;
+    let coef = binomial(p, e, steps);
+    (coef != 0).then(|| (e + (steps * (p.as_u32() - 1)) as i32, coef))
+}
+
+/// $\mathbb{CP}^{\mathrm{max}}_{\mathrm{min}}$. See the [module-level documentation](self).
+pub fn complex_projective_space(
+    algebra: Arc<SteenrodAlgebra>,
+    min: i32,
+    max: i32,
+) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    ensure!(min <= max, "min must be at most max");
+    let p = algebra.prime();
+
+    let mut basis = BiVec::new(2 * min);
+    for degree in 2 * min..=2 * max {
+        basis.push(if degree % 2 == 0 {
+            vec![degree / 2]
+        } else {
+            vec![]
+        });
+    }
+    build(
+        algebra,
+        format!("CP^{max}_{min}"),
+        basis,
+        |e| format!("x^{{{e}}}"),
+        |generator, &e| act_on_complex_power(p, generator, e).into_iter().collect(),
+    )
+}
+
+/// $\mathbb{HP}^{\mathrm{max}}_{\mathrm{min}}$. See the [module-level documentation](self).
+pub fn quaternionic_projective_space(
+    algebra: Arc<SteenrodAlgebra>,
+    min: i32,
+    max: i32,
+) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    ensure!(min <= max, "min must be at most max");
+    let p = algebra.prime();
+
+    let mut basis = BiVec::new(4 * min);
+    for degree in 4 * min..=4 * max {
+        basis.push(if degree % 4 == 0 {
+            vec![degree / 4]
+        } else {
+            vec![]
+        });
+    }
+    // The cohomology of HP^oo is the subring of that of CP^oo generated by u = x^2.
+    build(
+        algebra,
+        format!("HP^{max}_{min}"),
+        basis,
+        |e| format!("u^{{{e}}}"),
+        |generator, &e| {
+            act_on_complex_power(p, generator, 2 * e)
+                .map(|(e, coef)| {
+                    assert_eq!(e % 2, 0);
+                    (e / 2, coef)
+                })
+                .into_iter()
+                .collect()
+        },
+    )
+}
+
+/// The cells of $B\mathbb{Z}/p$ between degrees `min` and `max`. This is the cohomology of a
+/// (stunted) lens space. See the [module-level documentation](self).
+pub fn lens_space(
+    algebra: Arc<SteenrodAlgebra>,
+    min: i32,
+    max: i32,
+) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    let p = algebra.prime();
+    ensure!(
+        p != 2,
+        "Lens spaces are only supported at odd primes. Use real projective space instead"
+    );
+    ensure!(0 <= min && min <= max, "Need 0 <= min <= max");
+
+    // A basis element x^eps y^e is (eps, e)
+    let basis = BiVec::from_vec(
+        min,
+        (min..=max).map(|d| vec![(d % 2 == 1, d / 2)]).collect(),
+    );
+    build(
+        algebra,
+        format!("L^{max}_{min}"),
+        basis,
+        |&(x, e)| format!("{}y^{{{e}}}", if x { "x" } else { "" }),
+        |generator, &(x, e)| match generator {
+            // Q_k is a derivation with Q_k x = y^{p^k} and Q_k y = 0
+            Generator::Q(k) if x => vec![((false, e + p.as_i32().pow(k)), 1)],
+            Generator::Q(_) => vec![],
+            // P(x) = x, so P^k only acts on the y^e factor
+            Generator::Power(_) => act_on_complex_power(p, generator, e)
+                .map(|(e, coef)| ((x, e), coef))
+                .into_iter()
+                .collect(),
+        },
+    )
+}
+
+/// The structure groups of the Thom spectra supported by [`thom_spectrum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ThomGroup {
+    O,
+    U,
+    Sp,
+}
+
+impl ThomGroup {
+    /// The degree of the roots, and the power of the roots the classes are polynomials in.
+    fn roots(self) -> (u32, u32) {
+        match self {
+            Self::O => (1, 1),
+            Self::U => (2, 1),
+            // H^*(BSp) is the subring of H^*(BU) of symmetric polynomials in the squares of the
+            // Chern roots.
+            Self::Sp => (2, 2),
+        }
+    }
+}
+
+/// The partitions of `n`, with parts in decreasing order.
+fn partitions(n: u32) -> Vec<Vec<u32>> {
+    fn rec(n: u32, max_part: u32, current: &mut Vec<u32>, result: &mut Vec<Vec<u32>>) {
+        if n == 0 {
+            result.push(current.clone());
+            return;
+        }
+        for part in (1..=std::cmp::min(n, max_part)).rev() {
+            current.push(part);
+            rec(n - part, part, current, result);
+            current.pop();
+        }
+    }
+    let mut result = Vec::new();
+    rec(n, n, &mut Vec::new(), &mut result);
+    result
+}
+
+/// The coefficient of $U m_\nu$ in $P^k (U m_\lambda)$, where the variables have total power
+/// $x + x^p$ and `power` is the power of the roots the symmetric functions are in. The monomials of
+/// $U m_\lambda$ are $\prod_j x_j^{\mathrm{power} \cdot (1 + a_j)}$, where $a$ runs over the
+/// distinct rearrangements of $\lambda$. By the Cartan formula, the coefficient of the leading
+/// monomial of $U m_\nu$ is the sum over those $a$ of $\prod_j \binom{\mathrm{power} \cdot (1 +
+/// a_j)}{k_j}$, where $k_j$ is the number of steps it takes to go from $a_j$ to $\nu_j$. Variables
+/// beyond the length of $\nu$ must have $a_j = 0$ and contribute 1.
+fn thom_coefficient(p: ValidPrime, power: u32, lambda: &[u32], nu: &[u32]) -> u32 {
+    fn rec(p: ValidPrime, power: u32, nu: &[u32], remaining: &mut BTreeMap<u32, usize>) -> u32 {
+        let Some((&target, nu)) = nu.split_first() else {
+            return 1;
+        };
+        let mut total = 0;
+        let choices: Vec<u32> = remaining
+            .iter()
+            .filter(|&(&a, &count)| count > 0 && a <= target)
+            .map(|(&a, _)| a)
+            .collect();
+        for a in choices {
+            let diff = power * (target - a);
+            if !diff.is_multiple_of(p - 1) {
+                continue;
+            }
+            let coef = u32::binomial(p, power * (1 + a), diff / (p - 1));
+            if coef == 0 {
+                continue;
+            }
+            *remaining.get_mut(&a).unwrap() -= 1;
+            total = (total + coef * rec(p, power, nu, remaining)) % p;
+            *remaining.get_mut(&a).unwrap() += 1;
+        }
+        total
+    }
+
+    if lambda.len() > nu.len() {
+        return 0;
+    }
+    let mut remaining = BTreeMap::new();
+    remaining.insert(0, nu.len() - lambda.len());
+    for &a in lambda {
+        *remaining.entry(a).or_insert(0) += 1;
+    }
+    rec(p, power, nu, &mut remaining)
+}
+
+/// The `max`-skeleton of $MG$. See the [module-level documentation](self).
+pub fn thom_spectrum(
+    algebra: Arc<SteenrodAlgebra>,
+    group: ThomGroup,
+    max: i32,
+) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    let p = algebra.prime();
+    ensure!(
+        group != ThomGroup::O || p == 2,
+        "MO is only supported at the prime 2"
+    );
+    ensure!(max >= 0, "max must be non-negative");
+
+    let (root_degree, power) = group.roots();
+    // The degree of U m_lambda is unit * |lambda|
+    let unit = (root_degree * power) as i32;
+
+    let mut basis = BiVec::new(0);
+    for degree in 0..=max {
+        basis.push(if degree % unit == 0 {
+            partitions((degree / unit) as u32)
+        } else {
+            vec![]
+        });
+    }
+
+    let act = |generator: Generator, lambda: &Vec<u32>| {
+        let Some(steps) = generator.steps(p, root_degree) else {
+            return vec![];
+        };
+        // Each step raises the total exponent by p - 1, which is (p - 1) / power in |lambda|
+        let gain = steps * (p - 1);
+        if !gain.is_multiple_of(power) {
+            return vec![];
+        }
+        let size = lambda.iter().sum::<u32>() + gain / power;
+        partitions(size)
+            .into_iter()
+            .filter_map(|nu| {
+                let coef = thom_coefficient(p, power, lambda, &nu);
+                (coef != 0).then_some((nu, coef))
+            })
+            .collect()
+    };
+
+    build(
+        algebra,
+        format!("M{group:?}^{{({max})}}"),
+        basis,
+        |lambda| {
+            if lambda.is_empty() {
+                String::from("U")
+            } else {
+                let parts: Vec<String> = lambda.iter().map(u32::to_string).collect();
+                format!("Um_{{{}}}", parts.join(","))
+            }
+        },
+        act,
+    )
+}
+
+/// $A/\!/E(n)$, the quotient of the Steenrod algebra by the left ideal generated by $Q_0, \ldots,
+/// Q_n$.
+pub fn exterior_quotient(
+    algebra: Arc<SteenrodAlgebra>,
+    n: u32,
+) -> anyhow::Result<FPModule<SteenrodAlgebra>> {
+    let p = algebra.prime();
+    let evaluator = SteenrodEvaluator::new(p);
+
+    let mut result = FPModule::new(Arc::clone(&algebra), format!("A//E({n})"), 0);
+    result.add_generators(0, vec![String::from("x0")]);
+
+    let mut relations: Vec<(i32, FpVector)> = Vec::new();
+    for i in 0..=n {
+        let q = format!("Q{i}");
+        let (degree, element) = match &*algebra {
+            SteenrodAlgebra::AdemAlgebra(_) => evaluator.evaluate_algebra_adem(&q)?,
+            SteenrodAlgebra::MilnorAlgebra(_) => evaluator.evaluate_algebra_milnor(&q)?,
+            SteenrodAlgebra::ChangeOfBasisAlgebra(a) => {
+                let (degree, milnor) = evaluator.evaluate_algebra_milnor(&q)?;
+                a.compute_basis(degree);
+                let mut element = FpVector::new(p, a.dimension(degree));
+                a.from_milnor(element.as_slice_mut(), 1, degree, milnor.as_slice());
+                (degree, element)
+            }
+        };
+        algebra.compute_basis(degree);
+        let generators = result.generators();
+        generators.compute_basis(degree);
+
+        let mut v = FpVector::new(p, generators.dimension(degree));
+        for (op_idx, c) in element.iter_nonzero() {
+            v.add_basis_element(
+                generators.operation_generator_to_index(degree, op_idx, 0, 0),
+                c,
+            );
+        }
+        relations.push((degree, v));
+    }
+
+    // Relations have to be added in every degree in turn
+    let mut next_degree = 0;
+    for (degree, v) in relations {
+        for d in next_degree..degree {
+            result.add_relations(d, vec![]);
+        }
+        result.add_relations(degree, vec![v]);
+        next_degree = degree + 1;
+    }
+    Ok(result)
+}
+
+#[derive(Deserialize)]
+struct RangeSpec {
+    min: i32,
+    max: i32,
+}
+
+#[derive(Deserialize)]
+struct ThomSpec {
+    group: ThomGroup,
+    max: i32,
+}
+
+#[derive(Deserialize)]
+struct ExteriorQuotientSpec {
+    n: u32,
+}
+
+/// Constructs the finite dimensional module of type `ty` from its JSON specification. See the
+/// [module-level documentation](self) for the possible types.
+pub fn from_json(
+    algebra: Arc<SteenrodAlgebra>,
+    ty: &str,
+    json: &Value,
+) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    match ty {
+        "complex projective space" => {
+            let spec = RangeSpec::deserialize(json)?;
+            complex_projective_space(algebra, spec.min, spec.max)
+        }
+        "quaternionic projective space" => {
+            let spec = RangeSpec::deserialize(json)?;
+            quaternionic_projective_space(algebra, spec.min, spec.max)
+        }
+        "lens space" => {
+            let spec = RangeSpec::deserialize(json)?;
+            lens_space(algebra, spec.min, spec.max)
+        }
+        "thom spectrum" => {
+            let spec = ThomSpec::deserialize(json)?;
+            thom_spectrum(algebra, spec.group, spec.max)
+        }
+        _ => Err(anyhow!("Unknown module type: {ty}")),
+    }
+}
+
+/// Constructs $A/\!/E(n)$ from its JSON specification, which has a single field `n`.
+pub fn exterior_quotient_from_json(
+    algebra: Arc<SteenrodAlgebra>,
+    json: &Value,
+) -> anyhow::Result<FPModule<SteenrodAlgebra>> {
+    let spec = ExteriorQuotientSpec::deserialize(json)?;
+    exterior_quotient(algebra, spec.n)
+}
+
+#[cfg(test)]
+mod tests {
+    use fp::prime::TWO;
+
+    use super::*;
+    use crate::algebra::{AdemAlgebra, MilnorAlgebra};
+
+    fn algebras(p: u32) -> [Arc<SteenrodAlgebra>; 2] {
+        let p = ValidPrime::new(p);
+        [
+            Arc::new(SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(p, false))),
+            Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(p, false))),
+        ]
+    }
+
+    /// The relations of the Steenrod algebra are checked when the modules are built.
+    #[test]
+    fn relations_hold() {
+        for p in [2, 3, 5] {
+            for algebra in algebras(p) {
+                complex_projective_space(Arc::clone(&algebra), -4, 12).unwrap();
+                quaternionic_projective_space(Arc::clone(&algebra), -2, 6).unwrap();
+                thom_spectrum(Arc::clone(&algebra), ThomGroup::U, 24).unwrap();
+                thom_spectrum(Arc::clone(&algebra), ThomGroup::Sp, 24).unwrap();
+                if p == 2 {
+                    thom_spectrum(Arc::clone(&algebra), ThomGroup::O, 12).unwrap();
+                    assert!(lens_space(Arc::clone(&algebra), 0, 10).is_err());
+                } else {
+                    lens_space(Arc::clone(&algebra), 1, 40).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn complex_projective_space_action() {
+        let algebra = Arc::new(SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(TWO, false)));
+        let module = complex_projective_space(algebra, 1, 4).unwrap();
+        let mut result = FpVector::new(TWO, 1);
+
+        // Sq^2 x = x^2 and Sq^2 x^2 = 0
+        module.act_on_basis(result.as_slice_mut(), 1, 2, 0, 2, 0);
+        assert_eq!(result.entry(0), 1);
+        result.set_to_zero();
+        module.act_on_basis(result.as_slice_mut(), 1, 2, 0, 4, 0);
+        assert_eq!(result.entry(0), 0);
+    }
+
+    #[test]
+    fn thom_spectrum_dimensions() {
+        let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+            TWO, false,
+        )));
+        let module = thom_spectrum(algebra, ThomGroup::O, 6).unwrap();
+        let dims: Vec<usize> = (0..=6).map(|d| module.dimension(d)).collect();
+        assert_eq!(dims, [1, 1, 2, 3, 5, 7, 11]);
+    }
+
+    #[test]
+    fn exterior_quotient_dimensions() {
+        // The dual of A//E(0) is F_2[xi_1^2, xi_2, xi_3, ...]
+        for algebra in algebras(2) {
+            let module = exterior_quotient(algebra, 0).unwrap();
+            module.compute_basis(8);
+            let dims: Vec<usize> = (0..=8).map(|d| module.dimension(d)).collect();
+            assert_eq!(dims, [1, 0, 1, 1, 1, 1, 2, 2, 2]);
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Margolis homology of finite modules over the Steenrod algebra.
+//!
+//! If $d$ is an element of the Steenrod algebra with $d^n = 0$, the Margolis homology of a module
+//! $M$ with respect to $d$ is
+//! $$ H(M; d) = \ker(d) / \operatorname{im}(d^{n - 1}). $$
+//! We consider the Milnor primitives $Q_i$, which square to zero, and the elements $P^s_t =
+//! P(0, \ldots, 0, p^s)$ of the Milnor basis with $s < t$, whose $p$th power is zero.
+//!
+//! By a theorem of Adams and Margolis, a bounded below module is free over the exterior algebra
+//! $E(n)$ on $Q_0, \ldots, Q_n$ if and only if $H(M; Q_i) = 0$ for $0 \leq i \leq n$. See
+//! [`is_free_over_exterior`].
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{Context, anyhow, ensure};
+use bivec::BiVec;
+use fp::{
+    matrix::Matrix,
+    prime::{Prime, ValidPrime},
+    vector::FpVector,
+};
+
+use crate::{
+    algebra::{
+        Algebra, SteenrodAlgebra,
+        milnor_algebra::{MilnorBasisElement, PPartEntry},
+    },
+    module::Module,
+    steenrod_evaluator::SteenrodEvaluator,
+};
+
+/// An element of the Milnor basis whose Margolis homology can be computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MargolisOperator {
+    /// The Milnor primitive $Q_i$.
+    Q(u32),
+    /// $P^s_t = P(0, \ldots, 0, p^s)$, where $p^s$ is in the $t$th position. We need $s < t$.
+    P { s: u32, t: u32 },
+}
+
+impl MargolisOperator {
+    /// The element as a Milnor basis element.
+    pub fn milnor_basis_element(self, p: ValidPrime) -> MilnorBasisElement {
+        let xi_degree = |t: u32| {
+            let xi = (p.as_i32().pow(t) - 1) / (p.as_i32() - 1);
+            if p == 2 {
+                xi
+            } else {
+                2 * (p.as_i32() - 1) * xi
+            }
+        };
+        match self {
+            // At the prime 2, Q_i is Sq(0, ..., 0, 1) with the 1 in position i + 1
+            Self::Q(i) if p == 2 => Self::P { s: 0, t: i + 1 }.milnor_basis_element(p),
+            Self::Q(i) => MilnorBasisElement {
+                q_part: 1 << i,
+                p_part: vec![],
+                degree: 2 * p.as_i32().pow(i) - 1,
+            },
+            Self::P { s, t } => {
+                let mut p_part = vec![0; t as usize];
+                p_part[t as usize - 1] = p.pow(s) as PPartEntry;
+                MilnorBasisElement {
+                    q_part: 0,
+                    p_part,
+                    degree: p.as_i32().pow(s) * xi_degree(t),
+                }
+            }
+        }
+    }
+
+    pub fn degree(self, p: ValidPrime) -> i32 {
+        self.milnor_basis_element(p).degree
+    }
+
+    /// The smallest $n$ such that the $n$th power of the operator vanishes.
+    pub fn order(self, p: ValidPrime) -> u32 {
+        match self {
+            Self::Q(_) => 2,
+            Self::P { .. } => p.as_u32(),
+        }
+    }
+
+    /// Express the operator in the basis of `algebra`.
+    fn to_element(self, algebra: &SteenrodAlgebra) -> anyhow::Result<FpVector> {
+        let p = algebra.prime();
+        let elt = self.milnor_basis_element(p);
+        algebra.compute_basis(elt.degree);
+
+        match algebra {
+            SteenrodAlgebra::MilnorAlgebra(a) => {
+                let idx = a
+                    .try_basis_element_to_index(&elt)
+                    .ok_or_else(|| anyhow!("{self} is not in the algebra"))?;
+                let mut v = FpVector::new(p, a.dimension(elt.degree));
+                v.set_entry(idx, 1);
+                Ok(v)
+            }
+            SteenrodAlgebra::AdemAlgebra(a) => {
+                let evaluator = SteenrodEvaluator::new(p);
+                evaluator.adem.compute_basis(elt.degree);
+                evaluator.milnor.compute_basis(elt.degree);
+                let mut milnor = FpVector::new(p, evaluator.milnor.dimension(elt.degree));
+                milnor.set_entry(evaluator.milnor.basis_element_to_index(&elt), 1);
+                let mut v = FpVector::new(p, a.dimension(elt.degree));
+                evaluator.milnor_to_adem(&mut v, 1, elt.degree, &milnor);
+                Ok(v)
+            }
+            SteenrodAlgebra::ChangeOfBasisAlgebra(a) => {
+                let mut milnor = FpVector::new(p, a.dimension(elt.degree));
+                milnor.set_entry(a.milnor().basis_element_to_index(&elt), 1);
+                let mut v = FpVector::new(p, a.dimension(elt.degree));
+                a.from_milnor(v.as_slice_mut(), 1, elt.degree, milnor.as_slice());
+                Ok(v)
+            }
+        }
+    }
+}
+
+impl fmt::Display for MargolisOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Q(i) => write!(f, "Q_{i}"),
+            Self::P { s, t } => write!(f, "P^{s}_{t}"),
+        }
+    }
+}
+
+impl FromStr for MargolisOperator {
+    type Err = anyhow::Error;
+
+    /// Parses `Q1`, `Q_1`, `P^0_2` and `Sq^0_2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(i) = s.strip_prefix('Q') {
+            let i = i.strip_prefix('_').unwrap_or(i);
+            return Ok(Self::Q(
+                i.parse().with_context(|| format!("Invalid index in {s}"))?,
+            ));
+        }
+
+        let rest = s
+            .strip_prefix("P^")
+            .or_else(|| s.strip_prefix("Sq^"))
+            .ok_or_else(|| anyhow!("Expected Q_i or P^s_t, found {s}"))?;
+        let (exponent, index) = rest
+            .split_once('_')
+            .ok_or_else(|| anyhow!("Expected P^s_t, found {s}"))?;
+        let exponent: u32 = exponent
+            .parse()
+            .with_context(|| format!("Invalid exponent in {s}"))?;
+        let index: u32 = index
+            .parse()
+            .with_context(|| format!("Invalid index in {s}"))?;
+        ensure!(
+            exponent < index,
+            "P^s_t only has vanishing powers when s < t, but {s} has s = {exponent} and t = \
+             {index}"
+        );
+        Ok(Self::P {
+            s: exponent,
+            t: index,
+        })
+    }
+}
+
+/// The matrix of the operator `op` of degree `op_degree` raised to the `power`th power, from
+/// degree `degree` of `module` to degree `degree + power * op_degree`. The rows are the images of
+/// the basis elements.
+fn power_matrix<M: Module<Algebra = SteenrodAlgebra>>(
+    module: &M,
+    op: &FpVector,
+    op_degree: i32,
+    power: u32,
+    degree: i32,
+) -> Matrix {
+    let p = module.prime();
+    let target_degree = degree + power as i32 * op_degree;
+    let mut matrix = Matrix::new(p, module.dimension(degree), module.dimension(target_degree));
+    for i in 0..module.dimension(degree) {
+        let mut v = FpVector::new(p, module.dimension(degree));
+        v.set_entry(i, 1);
+        for k in 0..power as i32 {
+            let input_degree = degree + k * op_degree;
+            let mut w = FpVector::new(p, module.dimension(input_degree + op_degree));
+            module.act_by_element(
+                w.as_slice_mut(),
+                1,
+                op_degree,
+                op.as_slice(),
+                input_degree,
+                v.as_slice(),
+            );
+            v = w;
+        }
+        matrix.row_mut(i).assign(v.as_slice());
+    }
+    matrix
+}
+
+/// The dimensions of the Margolis homology $H(M; d)$ of a finite module in each degree, where $d$
+/// is `op`.
+pub fn margolis_homology<M: Module<Algebra = SteenrodAlgebra>>(
+    module: &M,
+    op: MargolisOperator,
+) -> anyhow::Result<BiVec<usize>> {
+    let algebra = module.algebra();
+    let p = algebra.prime();
+    let min_degree = module.min_degree();
+    let max_degree = module
+        .max_degree()
+        .ok_or_else(|| anyhow!("Margolis homology is only computed for finite modules"))?;
+    module.compute_basis(max_degree);
+
+    let op_degree = op.degree(p);
+    let element = op.to_element(&algebra)?;
+    let order = op.order(p);
+
+    let mut result = BiVec::with_capacity(min_degree, max_degree + 1);
+    for degree in min_degree..=max_degree {
+        let dimension = module.dimension(degree);
+        let kernel = if degree + op_degree > max_degree {
+            dimension
+        } else {
+            dimension - power_matrix(module, &element, op_degree, 1, degree).row_reduce()
+        };
+        let source = degree - (order as i32 - 1) * op_degree;
+        let image = if source < min_degree {
+            0
+        } else {
+            power_matrix(module, &element, op_degree, order - 1, source).row_reduce()
+        };
+        result.push(kernel - image);
+    }
+    Ok(result)
+}
+
+/// Whether a finite module is free over the exterior algebra $E(n)$ on $Q_0, \ldots, Q_n$. This
+/// uses the theorem of Adams and Margolis that this is the case if and only if $H(M; Q_i) = 0$ for
+/// $0 \leq i \leq n$.
+pub fn is_free_over_exterior<M: Module<Algebra = SteenrodAlgebra>>(
+    module: &M,
+    n: u32,
+) -> anyhow::Result<bool> {
+    for i in 0..=n {
+        if margolis_homology(module, MargolisOperator::Q(i))?
+            .iter()
+            .any(|&d| d > 0)
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        algebra::{AdemAlgebra, MilnorAlgebra},
+        module::FDModule,
+    };
+
+    fn joker(algebra: SteenrodAlgebra) -> FDModule<SteenrodAlgebra> {
+        FDModule::from_json(Arc::new(algebra), &crate::tests::joker_json()).unwrap()
+    }
+
+    #[test]
+    fn parse_operator() {
+        assert_eq!(
+            "Q1".parse::<MargolisOperator>().unwrap(),
+            MargolisOperator::Q(1)
+        );
+        assert_eq!(
+            "Q_0".parse::<MargolisOperator>().unwrap(),
+            MargolisOperator::Q(0)
+        );
+        assert_eq!(
+            "P^1_2".parse::<MargolisOperator>().unwrap(),
+            MargolisOperator::P { s: 1, t: 2 }
+        );
+        assert!("P^2_2".parse::<MargolisOperator>().is_err());
+        assert!("Sq3".parse::<MargolisOperator>().is_err());
+        assert_eq!(MargolisOperator::P { s: 1, t: 2 }.to_string(), "P^1_2");
+    }
+
+    #[test]
+    fn joker_homology() {
+        for algebra in [
+            SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(fp::prime::TWO, false)),
+            SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(fp::prime::TWO, false)),
+        ] {
+            let module = joker(algebra);
+            // Q_0 = Sq^1 pairs x0 with x1 and x3 with x4, leaving x2
+            let q0 = margolis_homology(&module, MargolisOperator::Q(0)).unwrap();
+            assert_eq!(q0.iter().copied().collect::<Vec<_>>(), [0, 0, 1, 0, 0]);
+            // Q_1 = Sq^3 + Sq^2 Sq^1 sends x0 to x3 and x1 to x4
+            let q1 = margolis_homology(&module, MargolisOperator::Q(1)).unwrap();
+            assert_eq!(q1.iter().copied().collect::<Vec<_>>(), [0, 0, 1, 0, 0]);
+            // P^0_1 = Sq^1 is Q_0
+            let p01 = margolis_homology(&module, MargolisOperator::P { s: 0, t: 1 }).unwrap();
+            assert_eq!(p01, q0);
+
+            assert!(!is_free_over_exterior(&module, 0).unwrap());
+        }
+    }
+
+    #[test]
+    fn free_over_exterior() {
+        let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+            fp::prime::TWO,
+            false,
+        )));
+        let json = serde_json::json!({
+            "type" : "finite dimensional module",
+            "p": 2,
+            "gens": {"x0": 0, "x1": 1},
+            "actions": ["Sq1 x0 = x1"]
+        });
+        let c2 = FDModule::from_json(algebra, &json).unwrap();
+        assert!(is_free_over_exterior(&c2, 0).unwrap());
+        assert!(!is_free_over_exterior(&c2, 1).unwrap());
+    }
+
+    #[test]
+    fn odd_prime() {
+        let p = ValidPrime::new(3);
+        let algebra = Arc::new(SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(p, false)));
+        let json = serde_json::json!({
+            "type" : "finite dimensional module",
+            "p": 3,
+            "gens": {"x0": 0, "x4": 4, "x8": 8},
+            "actions": ["P1 x0 = x4", "P1 x4 = x8"]
+        });
+        let module = FDModule::from_json(algebra, &json).unwrap();
+        // P^0_1 = P^1 has order 3, and P^1 P^1 x0 = 2 x8, so the homology vanishes
+        let h = margolis_homology(&module, MargolisOperator::P { s: 0, t: 1 }).unwrap();
+        assert!(h.iter().all(|&d| d == 0));
+        // Q_0 acts trivially
+        let h = margolis_homology(&module, MargolisOperator::Q(0)).unwrap();
+        assert_eq!(h.iter().sum::<usize>(), 3);
+    }
+}
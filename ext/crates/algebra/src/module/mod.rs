@@ -1,6 +1,9 @@
 mod finite_dimensional_module;
 mod finitely_presented_module;
 mod free_module;
+pub mod isomorphism;
+pub mod library;
+pub mod margolis;
 mod module_trait;
 mod rpn;
 pub mod steenrod_module;
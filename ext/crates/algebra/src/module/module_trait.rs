@@ -283,14 +283,18 @@ pub trait Module: std::fmt::Display + std::any::Any + Send + Sync {
 pub struct ModuleFailedRelationError {
     pub relation: String,
     pub value: String,
+    /// The degree of the basis element on which the relation fails.
+    pub degree: i32,
+    /// The name of the basis element on which the relation fails.
+    pub element: String,
 }
 
 impl std::fmt::Display for ModuleFailedRelationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Relation failed:\n    {}  !=  0\nInstead it is equal to {}\n",
-            self.relation, self.value
+            "Relation failed on {} in degree {}:\n    {}  !=  0\nInstead it is equal to {}\n",
+            self.element, self.degree, self.relation, self.value
         )
     }
 }
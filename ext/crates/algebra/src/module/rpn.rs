@@ -122,6 +122,14 @@ where
             Ok(SteenrodAlgebra::MilnorAlgebra(a)) => {
                 coef_milnor(a, op_degree, op_index, mod_degree)
             }
+            Ok(SteenrodAlgebra::ChangeOfBasisAlgebra(a)) => {
+                a.basis_element_to_milnor(op_degree, op_index)
+                    .iter_nonzero()
+                    .filter(|&(i, _)| coef_milnor(a.milnor(), op_degree, i, mod_degree))
+                    .count()
+                    % 2
+                    == 1
+            }
             Err(_) => unreachable!(),
         } {
             result.add_basis_element(0, 1);
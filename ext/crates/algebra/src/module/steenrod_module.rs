@@ -14,7 +14,7 @@ mod json {
     use anyhow::anyhow;
 
     use super::*;
-    use crate::module::{FDModule, FPModule, RealProjectiveSpace, SuspensionModule};
+    use crate::module::{FDModule, FPModule, RealProjectiveSpace, SuspensionModule, library};
 
     pub fn from_json(
         algebra: Arc<SteenrodAlgebra>,
@@ -42,6 +42,16 @@ mod json {
             Some("finitely presented module") => {
                 Ok(box_new(FPModule::from_json(algebra, json)?, json))
             }
+            Some(
+                ty @ ("complex projective space"
+                | "quaternionic projective space"
+                | "lens space"
+                | "thom spectrum"),
+            ) => Ok(box_new(library::from_json(algebra, ty, json)?, json)),
+            Some("A//E(n)") => Ok(box_new(
+                library::exterior_quotient_from_json(algebra, json)?,
+                json,
+            )),
             Some(x) => Err(anyhow!("Unknown module type: {}", x)),
             None => Err(anyhow!("Missing module type")),
         }
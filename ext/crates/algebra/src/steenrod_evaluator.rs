@@ -1,30 +1,95 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use fp::{
     prime::{Prime, ValidPrime},
     vector::FpVector,
 };
 
 use crate::{
-    algebra::{AdemAlgebra, Algebra, MilnorAlgebra, adem_algebra::AdemBasisElement},
+    algebra::{
+        AdemAlgebra, Algebra, ChangeOfBasisAlgebra, MilnorAlgebra, SteenrodAlgebra,
+        adem_algebra::AdemBasisElement, change_of_basis::AlternativeBasis,
+    },
     milnor_algebra::{MilnorBasisElement, PPartEntry},
+    module::{FDModule, Module},
     steenrod_parser::*,
 };
 
+/// A basis of the Steenrod algebra in which [`SteenrodEvaluator`] can write its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+    /// The basis of admissible monomials, also known as the Serre–Cartan basis.
+    Adem,
+    Milnor,
+    /// One of the bases of [`ChangeOfBasisAlgebra`]. These are only defined at the prime 2.
+    Alternative(AlternativeBasis),
+}
+
+impl FromStr for Basis {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "adem" | "admissible" | "serre-cartan" | "serre_cartan" => Ok(Self::Adem),
+            "milnor" => Ok(Self::Milnor),
+            s => s.parse().map(Self::Alternative).map_err(|_| {
+                anyhow!(
+                    "Unknown basis {s}. Should be one of adem, serre-cartan, milnor, pst, arnona, \
+                     arnonc or wall"
+                )
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Basis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Adem => write!(f, "adem"),
+            Self::Milnor => write!(f, "milnor"),
+            Self::Alternative(basis) => basis.fmt(f),
+        }
+    }
+}
+
 pub struct SteenrodEvaluator {
     pub adem: AdemAlgebra,
     pub milnor: MilnorAlgebra,
+    /// The algebras of [`Basis::Alternative`]. This is empty at odd primes.
+    alternative: Vec<ChangeOfBasisAlgebra>,
 }
 
 impl SteenrodEvaluator {
     pub fn new(p: ValidPrime) -> Self {
+        let alternative = if p == 2 {
+            [
+                AlternativeBasis::PSt,
+                AlternativeBasis::ArnonA,
+                AlternativeBasis::ArnonC,
+                AlternativeBasis::Wall,
+            ]
+            .into_iter()
+            .map(|basis| ChangeOfBasisAlgebra::new(p, basis))
+            .collect()
+        } else {
+            Vec::new()
+        };
         Self {
             adem: AdemAlgebra::new(p, false),
             milnor: MilnorAlgebra::new(p, false),
+            alternative,
         }
     }
 
+    /// The algebra in the alternative basis `basis`. This fails at odd primes.
+    pub fn alternative(&self, basis: AlternativeBasis) -> anyhow::Result<&ChangeOfBasisAlgebra> {
+        self.alternative
+            .iter()
+            .find(|a| a.basis() == basis)
+            .ok_or_else(|| anyhow!("The {basis} basis is only defined at the prime 2"))
+    }
+
     pub fn milnor_to_adem(&self, result: &mut FpVector, coeff: u32, degree: i32, input: &FpVector) {
         let p = self.prime();
         for (i, v) in input.iter_nonzero() {
@@ -76,6 +141,256 @@ impl SteenrodEvaluator {
         Ok(result)
     }
 
+    /// Writes an element given in the Adem basis in the basis `basis`.
+    pub fn from_adem(
+        &self,
+        basis: Basis,
+        degree: i32,
+        adem: &FpVector,
+    ) -> anyhow::Result<FpVector> {
+        let p = self.prime();
+        let mut milnor = FpVector::new(p, adem.len());
+        match basis {
+            Basis::Adem => Ok(adem.clone()),
+            Basis::Milnor => {
+                self.adem_to_milnor(&mut milnor, 1, degree, adem);
+                Ok(milnor)
+            }
+            Basis::Alternative(basis) => {
+                let algebra = self.alternative(basis)?;
+                algebra.compute_basis(degree);
+                self.adem_to_milnor(&mut milnor, 1, degree, adem);
+                let mut result = FpVector::new(p, adem.len());
+                algebra.from_milnor(result.as_slice_mut(), 1, degree, milnor.as_slice());
+                Ok(result)
+            }
+        }
+    }
+
+    /// Writes an element given in the basis `basis` in the Adem basis.
+    pub fn to_adem(&self, basis: Basis, degree: i32, input: &FpVector) -> anyhow::Result<FpVector> {
+        let p = self.prime();
+        let mut adem = FpVector::new(p, input.len());
+        match basis {
+            Basis::Adem => adem.assign(input),
+            Basis::Milnor => self.milnor_to_adem(&mut adem, 1, degree, input),
+            Basis::Alternative(basis) => {
+                let algebra = self.alternative(basis)?;
+                algebra.compute_basis(degree);
+                let mut milnor = FpVector::new(p, input.len());
+                algebra.to_milnor(milnor.as_slice_mut(), 1, degree, input.as_slice());
+                self.milnor_to_adem(&mut adem, 1, degree, &milnor);
+            }
+        }
+        Ok(adem)
+    }
+
+    /// Writes an element given in the Adem basis as a string in the basis `basis`.
+    pub fn element_to_string(
+        &self,
+        basis: Basis,
+        degree: i32,
+        adem: &FpVector,
+    ) -> anyhow::Result<String> {
+        let result = self.from_adem(basis, degree, adem)?;
+        Ok(match basis {
+            Basis::Adem => self.adem.element_to_string(degree, result.as_slice()),
+            Basis::Milnor => self.milnor.element_to_string(degree, result.as_slice()),
+            Basis::Alternative(basis) => self
+                .alternative(basis)?
+                .element_to_string(degree, result.as_slice()),
+        })
+    }
+
+    /// The excess of an element given in the Adem basis, i.e. the smallest degree of a class on
+    /// which it can act non-trivially. This is the minimum of the excesses of the terms in either
+    /// the Adem or the Milnor basis, and is `None` if the element is zero.
+    pub fn excess(&self, degree: i32, adem: &FpVector) -> Option<u32> {
+        let p = self.prime();
+        let mut milnor = FpVector::new(p, adem.len());
+        self.adem_to_milnor(&mut milnor, 1, degree, adem);
+        milnor
+            .iter_nonzero()
+            .map(|(i, _)| self.milnor.basis_element_from_index(degree, i).excess(p))
+            .min()
+    }
+
+    /// The coproduct of an element given in the Adem basis, written in the Milnor basis. The keys
+    /// of the result are `(left_degree, left_idx, right_degree, right_idx)` and the values are the
+    /// non-zero coefficients.
+    ///
+    /// We use $\Delta P(R) = \sum_{R' + R'' = R} P(R') \otimes P(R'')$ and that the $Q_i$ are
+    /// primitive.
+    pub fn coproduct(
+        &self,
+        degree: i32,
+        adem: &FpVector,
+    ) -> BTreeMap<(i32, usize, i32, usize), u32> {
+        let p = self.prime();
+        let mut milnor = FpVector::new(p, adem.len());
+        self.adem_to_milnor(&mut milnor, 1, degree, adem);
+
+        let mut result = BTreeMap::new();
+        for (i, c) in milnor.iter_nonzero() {
+            let elt = self.milnor.basis_element_from_index(degree, i).clone();
+            let mut left_p_part = vec![0; elt.p_part.len()];
+            loop {
+                // Iterate over the subsets of the Q_i that go to the left
+                let mut left_q_part = elt.q_part;
+                loop {
+                    let right_q_part = elt.q_part & !left_q_part;
+                    // The sign from moving the Q_i on the left past the Q_j on the right with j < i
+                    let swaps: u32 = (0..u32::BITS)
+                        .filter(|&i| left_q_part & (1 << i) != 0)
+                        .map(|i| (right_q_part & ((1 << i) - 1)).count_ones())
+                        .sum();
+                    let mut left = MilnorBasisElement {
+                        q_part: left_q_part,
+                        p_part: left_p_part.clone(),
+                        degree: 0,
+                    };
+                    let mut right = MilnorBasisElement {
+                        q_part: right_q_part,
+                        p_part: std::iter::zip(&elt.p_part, &left_p_part)
+                            .map(|(r, s)| r - s)
+                            .collect(),
+                        degree: 0,
+                    };
+                    for x in [&mut left, &mut right] {
+                        while x.p_part.last() == Some(&0) {
+                            x.p_part.pop();
+                        }
+                        x.compute_degree(p);
+                    }
+                    let key = (
+                        left.degree,
+                        self.milnor.basis_element_to_index(&left),
+                        right.degree,
+                        self.milnor.basis_element_to_index(&right),
+                    );
+                    let coeff = if swaps.is_multiple_of(2) { c } else { p - c };
+                    let entry = result.entry(key).or_insert(0);
+                    *entry = (*entry + coeff) % p;
+                    if *entry == 0 {
+                        result.remove(&key);
+                    }
+
+                    if left_q_part == 0 {
+                        break;
+                    }
+                    left_q_part = (left_q_part - 1) & elt.q_part;
+                }
+
+                // Move to the next p_part below elt.p_part
+                let Some(k) = (0..left_p_part.len()).find(|&k| left_p_part[k] < elt.p_part[k])
+                else {
+                    break;
+                };
+                left_p_part[k] += 1;
+                left_p_part[..k].fill(0);
+            }
+        }
+        result
+    }
+
+    /// Writes the output of [`SteenrodEvaluator::coproduct`] as a string.
+    pub fn coproduct_to_string(
+        &self,
+        coproduct: &BTreeMap<(i32, usize, i32, usize), u32>,
+    ) -> String {
+        if coproduct.is_empty() {
+            return "0".to_string();
+        }
+        let mut terms = Vec::new();
+        for (&(left_deg, left_idx, right_deg, right_idx), &c) in coproduct {
+            let left = self.milnor.basis_element_to_string(left_deg, left_idx);
+            let right = self.milnor.basis_element_to_string(right_deg, right_idx);
+            if c == 1 {
+                terms.push(format!("{left} ⊗ {right}"));
+            } else {
+                terms.push(format!("{c} * {left} ⊗ {right}"));
+            }
+        }
+        terms.join(" + ")
+    }
+
+    /// Evaluates an expression such as `Sq2 * x0 + Sq1 * x1` in a finite dimensional module,
+    /// where the names are those of the basis elements of the module. The module can be defined
+    /// over either basis, but its algebra must contain the operations involved.
+    pub fn evaluate_module_action(
+        &self,
+        module: &FDModule<SteenrodAlgebra>,
+        input: &str,
+    ) -> anyhow::Result<(i32, FpVector)> {
+        let p = self.prime();
+        let mut result: Option<(i32, FpVector)> = None;
+        for (op, g) in parse_module(input)? {
+            let (input_deg, input_idx) = module
+                .string_to_basis_element(&g)
+                .ok_or_else(|| anyhow!("Unknown basis element {g}"))?;
+            let (op_deg, adem) = self.evaluate_algebra_node(None, op)?;
+            let output_deg = op_deg + input_deg;
+            let (degree, output) = result.get_or_insert_with(|| {
+                (output_deg, FpVector::new(p, module.dimension(output_deg)))
+            });
+            ensure!(
+                *degree == output_deg,
+                "Mismatched degree: the terms have degrees {degree} and {output_deg}"
+            );
+            let op = self.to_algebra(&module.algebra(), op_deg, &adem)?;
+            module.act_by_element_on_basis(
+                output.as_slice_mut(),
+                1,
+                op_deg,
+                op.as_slice(),
+                input_deg,
+                input_idx,
+            );
+        }
+        result.ok_or_else(|| anyhow!("Empty expression"))
+    }
+
+    /// Expresses an element given in the Adem basis in the basis of `algebra`.
+    fn to_algebra(
+        &self,
+        algebra: &SteenrodAlgebra,
+        degree: i32,
+        adem: &FpVector,
+    ) -> anyhow::Result<FpVector> {
+        let p = self.prime();
+        algebra.compute_basis(degree);
+        let mut result = FpVector::new(p, algebra.dimension(degree));
+        let not_in_algebra = |elt: &dyn fmt::Display| anyhow!("{elt} is not in the algebra");
+        match algebra {
+            SteenrodAlgebra::AdemAlgebra(a) => {
+                for (i, c) in adem.iter_nonzero() {
+                    let elt = self.adem.basis_element_from_index(degree, i);
+                    let idx = a
+                        .try_basis_element_to_index(elt)
+                        .ok_or_else(|| not_in_algebra(elt))?;
+                    result.add_basis_element(idx, c);
+                }
+            }
+            SteenrodAlgebra::MilnorAlgebra(a) => {
+                let mut milnor = FpVector::new(p, adem.len());
+                self.adem_to_milnor(&mut milnor, 1, degree, adem);
+                for (i, c) in milnor.iter_nonzero() {
+                    let elt = self.milnor.basis_element_from_index(degree, i);
+                    let idx = a
+                        .try_basis_element_to_index(elt)
+                        .ok_or_else(|| not_in_algebra(elt))?;
+                    result.add_basis_element(idx, c);
+                }
+            }
+            SteenrodAlgebra::ChangeOfBasisAlgebra(a) => {
+                let mut milnor = FpVector::new(p, adem.len());
+                self.adem_to_milnor(&mut milnor, 1, degree, adem);
+                a.from_milnor(result.as_slice_mut(), 1, degree, milnor.as_slice());
+            }
+        }
+        Ok(result)
+    }
+
     fn prime(&self) -> ValidPrime {
         self.adem.prime()
     }
@@ -186,6 +501,22 @@ impl SteenrodEvaluator {
                 self.adem_q(&mut result, 1, x);
                 (degree, result)
             }
+            AlgebraBasisElt::PSt { s, t } => {
+                let mut p_part = vec![0; t as usize];
+                p_part[t as usize - 1] = p.pow(s) as PPartEntry;
+                return self.evaluate_basis_element(output_degree, AlgebraBasisElt::PList(p_part));
+            }
+            AlgebraBasisElt::Wall { m, k } => self.evaluate_p_or_b_list(
+                &(k..=m)
+                    .map(|i| BocksteinOrSq::Sq(p.pow(i)))
+                    .collect::<Vec<_>>(),
+            ),
+            AlgebraBasisElt::Arnon { m, k } => self.evaluate_p_or_b_list(
+                &(k..=m)
+                    .rev()
+                    .map(|i| BocksteinOrSq::Sq(p.pow(i)))
+                    .collect::<Vec<_>>(),
+            ),
         };
         if let Some(requested_degree) = output_degree
             && degree != requested_degree
@@ -393,6 +724,7 @@ impl SteenrodEvaluator {
 #[cfg(test)]
 mod tests {
     use expect_test::{Expect, expect};
+    use itertools::Itertools;
     use rstest::rstest;
 
     use super::*;
@@ -435,6 +767,12 @@ mod tests {
             expect![[r#"Sq6 Sq3 Sq1"#]],
             expect![[r#"P(7, 1) + P(3, 0, 1) + P(0, 1, 1)"#]],
         );
+        check("P^0_2", expect!["Sq3 + Sq2 Sq1"], expect!["P(0, 1)"]);
+        check(
+            "Q^1_0 + X^1_0",
+            expect!["Sq3 + Sq2 Sq1"],
+            expect!["P(0, 1)"],
+        );
     }
 
     #[test]
@@ -469,6 +807,129 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_alternative_bases() {
+        let ev = SteenrodEvaluator::new(ValidPrime::new(2));
+        let check = |input, output: Expect| {
+            let (degree, adem) = ev.evaluate_algebra_adem(input).unwrap();
+            let mut lines = Vec::new();
+            for basis in ["adem", "milnor", "pst", "arnona", "arnonc", "wall"] {
+                let basis: Basis = basis.parse().unwrap();
+                let converted = ev.from_adem(basis, degree, &adem).unwrap();
+                assert_eq!(ev.to_adem(basis, degree, &converted).unwrap(), adem);
+
+                // The output can be parsed again once we make the products explicit
+                let string = ev.element_to_string(basis, degree, &adem).unwrap();
+                let product = string
+                    .replace(", ", ",")
+                    .split(" + ")
+                    .map(|term| term.replace(' ', " * "))
+                    .join(" + ");
+                let (_, reparsed) = ev.evaluate_algebra_adem(&product).unwrap();
+                assert_eq!(reparsed, adem, "{basis}: {string}");
+                lines.push(format!("{basis}: {string}"));
+            }
+            output.assert_eq(&lines.join("\n"));
+        };
+
+        check(
+            "Sq2 * Sq2",
+            expect![[r#"
+                adem: Sq3 Sq1
+                milnor: P(1, 1)
+                pst: P^0_1 P^0_2
+                arnona: X^0_0 X^1_0
+                arnonc: Sq2 Sq2
+                wall: Q^1_0 Q^0_0"#]],
+        );
+        check(
+            "P(0, 0, 1) + Sq4 * Sq2 * Sq1",
+            expect![[r#"
+                adem: Sq7 + Sq5 Sq2 + Sq6 Sq1
+                milnor: P(7) + P(4, 1) + P(1, 2)
+                pst: P^0_1 P^1_1 P^2_1 + P^0_1 P^1_2 + P^2_1 P^0_2 + P^0_3
+                arnona: X^0_0 X^1_1 X^2_2 + X^0_0 X^2_1 + X^1_0 X^2_2
+                arnonc: Sq2 Sq5 + Sq4 Sq3 + Sq7
+                wall: Q^2_2 Q^1_0 + Q^2_1 Q^0_0 + Q^2_0"#]],
+        );
+        assert!(
+            SteenrodEvaluator::new(ValidPrime::new(3))
+                .element_to_string(
+                    Basis::Alternative(AlternativeBasis::PSt),
+                    0,
+                    &FpVector::new(ValidPrime::new(3), 1)
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_excess_and_coproduct() {
+        let check = |p, input, excess: Expect, coproduct: Expect| {
+            let ev = SteenrodEvaluator::new(ValidPrime::new(p));
+            let (degree, result) = ev.evaluate_algebra_adem(input).unwrap();
+            excess.assert_eq(&format!("{:?}", ev.excess(degree, &result)));
+            coproduct.assert_eq(&ev.coproduct_to_string(&ev.coproduct(degree, &result)));
+        };
+
+        check(
+            2,
+            "Sq2 * Sq2",
+            expect!["Some(2)"],
+            expect!["1 ⊗ P(1, 1) + P(1) ⊗ P(0, 1) + P(0, 1) ⊗ P(1) + P(1, 1) ⊗ 1"],
+        );
+        check(
+            2,
+            "Sq4 + Sq3 * Sq1",
+            expect!["Some(2)"],
+            expect![
+                "1 ⊗ P(4) + 1 ⊗ P(1, 1) + P(1) ⊗ P(3) + P(1) ⊗ P(0, 1) + P(2) ⊗ P(2) + P(3) ⊗ \
+                 P(1) + P(0, 1) ⊗ P(1) + P(4) ⊗ 1 + P(1, 1) ⊗ 1"
+            ],
+        );
+        check(
+            2,
+            "Sq2 * Sq2 * Sq2 + Sq3 * Sq3",
+            expect!["None"],
+            expect!["0"],
+        );
+        check(3, "Q1", expect!["Some(1)"], expect!["1 ⊗ Q_1 + Q_1 ⊗ 1"]);
+        check(
+            3,
+            "Q0 * Q1",
+            expect!["Some(2)"],
+            expect!["1 ⊗ Q_0 Q_1 + Q_0 ⊗ Q_1 + 2 * Q_1 ⊗ Q_0 + Q_0 Q_1 ⊗ 1"],
+        );
+        check(
+            3,
+            "b * P1",
+            expect!["Some(3)"],
+            expect!["1 ⊗ Q_0 P(1) + Q_0 ⊗ P(1) + P(1) ⊗ Q_0 + Q_0 P(1) ⊗ 1"],
+        );
+    }
+
+    #[test]
+    fn test_module_action() {
+        let ev = SteenrodEvaluator::new(ValidPrime::new(2));
+        let json = crate::tests::joker_json();
+        for algebra in [
+            SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(ValidPrime::new(2), false)),
+            SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(ValidPrime::new(2), false)),
+        ] {
+            let module = FDModule::from_json(std::sync::Arc::new(algebra), &json).unwrap();
+            let check = |input, output: Expect| {
+                let (degree, result) = ev.evaluate_module_action(&module, input).unwrap();
+                output.assert_eq(&module.element_to_string(degree, result.as_slice()));
+            };
+            check("Sq2 * Sq1 * x0", expect!["x3"]);
+            check("Sq3 * x1 + Sq1 * x3", expect!["0"]);
+            check("Q1 * x1 + P(1) * x3", expect!["0"]);
+            check("(Sq2 + Sq1 * Sq1) * x2", expect!["x4"]);
+            assert!(ev.evaluate_module_action(&module, "Sq1 * x0 + x0").is_err());
+            assert!(ev.evaluate_module_action(&module, "Sq1 * y0").is_err());
+        }
+    }
+
     #[rstest(p, max_degree, case(2, 32), case(3, 60))]
     #[trace]
     fn test_cob_adem_to_milnor(p: u32, max_degree: i32) {
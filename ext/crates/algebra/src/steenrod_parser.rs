@@ -9,7 +9,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alpha1, alphanumeric0, char, digit1 as digit, space0},
-    combinator::{map, map_res, opt, peek},
+    combinator::{map, map_res, opt, peek, verify},
     error::{ErrorKind, ParseError, context},
     multi::{many0, separated_list1},
     sequence::{delimited, pair, preceded},
@@ -25,6 +25,21 @@ pub enum AlgebraBasisElt {
     PList(PPart),
     P(u32),
     Q(u32),
+    /// $P^s_t$, the Milnor basis element dual to $\xi_t^{p^s}$.
+    PSt {
+        s: u32,
+        t: u32,
+    },
+    /// Wall's $Q^m_k = P^{p^k} P^{p^{k + 1}} \cdots P^{p^m}$.
+    Wall {
+        m: u32,
+        k: u32,
+    },
+    /// Arnon's $X^m_k = P^{p^m} P^{p^{m - 1}} \cdots P^{p^k}$.
+    Arnon {
+        m: u32,
+        k: u32,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -119,8 +134,29 @@ impl BocksteinOrSq {
     }
 }
 
+/// Parses `{prefix}{a}_{b}`, e.g. `P^0_2`.
+fn superscript_subscript<'a>(
+    prefix: &'static str,
+) -> impl Parser<&'a str, Output = (u32, u32), Error = nom::error::Error<&'a str>> {
+    map((tag(prefix), digits, char('_'), digits), |(_, a, _, b)| {
+        (a, b)
+    })
+}
+
 fn algebra_generator(i: &str) -> IResult<&str, AlgebraBasisElt> {
     alt((
+        map(
+            verify(superscript_subscript("P^"), |&(_, t)| t > 0),
+            |(s, t)| AlgebraBasisElt::PSt { s, t },
+        ),
+        map(
+            verify(superscript_subscript("Q^"), |&(m, k)| m >= k),
+            |(m, k)| AlgebraBasisElt::Wall { m, k },
+        ),
+        map(
+            verify(superscript_subscript("X^"), |&(m, k)| m >= k),
+            |(m, k)| AlgebraBasisElt::Arnon { m, k },
+        ),
         map(char('b'), |_| AlgebraBasisElt::Q(0)),
         map(preceded(char('Q'), digits), AlgebraBasisElt::Q),
         map(preceded(p_or_sq, digits), AlgebraBasisElt::P),
@@ -293,6 +329,14 @@ mod tests {
                 r#"Product(Product(BasisElt(Q(0)), BasisElt(Q(3))), Sum(Product(BasisElt(P(1)), BasisElt(AList([Sq(2), Bockstein, Sq(5)]))), Product(Product(BasisElt(PList([0, 0, 2])), BasisElt(PList([0, 1]))), BasisElt(PList([1, 0])))))"#
             ]],
         );
+
+        check(
+            "P^1_2 * Q^2_0 + X^1_1",
+            expect![
+                "Sum(Product(BasisElt(PSt { s: 1, t: 2 }), BasisElt(Wall { m: 2, k: 0 })), \
+                 BasisElt(Arnon { m: 1, k: 1 }))"
+            ],
+        );
     }
 
     #[test]
@@ -3,10 +3,12 @@ use std::fmt::Display;
 use crate::coordinates::{Bidegree, BidegreeGenerator};
 
 pub mod seqsee;
+pub mod spectralsequences;
 pub mod svg;
 pub mod tikz;
 
 pub use seqsee::SeqSeeBackend;
+pub use spectralsequences::SpectralSequencesBackend;
 pub use svg::SvgBackend;
 pub use tikz::TikzBackend;
 
@@ -50,10 +52,23 @@ pub trait Backend {
         orientation: Orientation,
     ) -> Result<(), Self::Error>;
 
+    /// Label the classes in bidegree `b`, e.g. with their names. The content is TeX math.
+    fn label(&mut self, b: Bidegree, content: impl Display) -> Result<(), Self::Error> {
+        self.text(b, content, Orientation::Above)
+    }
+
     // We don't use BidegreeGenerator here because `n` represents the order of a bidegree instead of
     // an index of an element within a bidegree
     fn node(&mut self, b: Bidegree, n: usize) -> Result<(), Self::Error>;
 
+    /// Set the style of a class that has been drawn with [`Backend::node`]. The styles used are
+    /// `permanent` and `dying` for classes that survive or die later on, and `dead` for classes
+    /// that have died on an earlier page. By default this is ignored.
+    fn class_style(&mut self, g: BidegreeGenerator, style: &str) -> Result<(), Self::Error> {
+        let _ = (g, style);
+        Ok(())
+    }
+
     fn structline(
         &mut self,
         source: BidegreeGenerator,
@@ -61,6 +76,18 @@ pub trait Backend {
         style: Option<&str>,
     ) -> Result<(), Self::Error>;
 
+    /// Draw a hidden extension by the product `name`. By default this is a structline with style
+    /// `hidden`.
+    fn extension(
+        &mut self,
+        source: BidegreeGenerator,
+        target: BidegreeGenerator,
+        name: &str,
+    ) -> Result<(), Self::Error> {
+        let _ = name;
+        self.structline(source, target, Some("hidden"))
+    }
+
     fn init(&mut self, max: Bidegree) -> Result<(), Self::Error> {
         self.header(max)?;
 
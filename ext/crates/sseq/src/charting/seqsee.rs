@@ -26,8 +26,8 @@ pub struct SeqSeeBackend<T: io::Write> {
     nodes: Map<String, Value>,
     /// The list of SeqSee edge objects.
     edges: Vec<Value>,
-    /// The set of style names referenced by edges. Each becomes an attribute alias in the header so
-    /// that the edges referencing it validate against the schema.
+    /// The set of style names referenced by nodes and edges. Each becomes an attribute alias in the
+    /// header so that the nodes and edges referencing it validate against the schema.
     styles: BTreeSet<String>,
 }
 
@@ -43,6 +43,12 @@ impl<T: io::Write> SeqSeeBackend<T> {
     }
 }
 
+/// The style of hidden extensions.
+const HIDDEN: &str = "hidden";
+
+/// The style of classes that have died on an earlier page.
+const DEAD: &str = "dead";
+
 /// Whether a style name denotes a differential, i.e. it is `d` followed by a page number.
 ///
 /// Differentials are given a distinct color to match the other backends in this crate.
@@ -99,6 +105,20 @@ impl<T: io::Write> Backend for SeqSeeBackend<T> {
         Ok(())
     }
 
+    fn class_style(&mut self, g: BidegreeGenerator, style: &str) -> Result<(), Self::Error> {
+        let Some(Value::Object(node)) = self.nodes.get_mut(&format!("{g:#}")) else {
+            return Ok(());
+        };
+        self.styles.insert(style.to_string());
+        match node.get_mut("attributes") {
+            Some(Value::Array(attributes)) => attributes.push(json!(style)),
+            _ => {
+                node.insert("attributes".to_string(), json!([style]));
+            }
+        }
+        Ok(())
+    }
+
     fn structline(
         &mut self,
         source: BidegreeGenerator,
@@ -123,15 +143,44 @@ impl<T: io::Write> Backend for SeqSeeBackend<T> {
         self.edges.push(Value::Object(edge));
         Ok(())
     }
+
+    fn extension(
+        &mut self,
+        source: BidegreeGenerator,
+        target: BidegreeGenerator,
+        name: &str,
+    ) -> Result<(), Self::Error> {
+        if source.x() > self.max.x()
+            || source.y() > self.max.y()
+            || target.x() > self.max.x()
+            || target.y() > self.max.y()
+        {
+            return Ok(());
+        }
+
+        // The `hidden` alias comes last so that its color takes precedence.
+        self.styles.insert(name.to_string());
+        self.styles.insert(HIDDEN.to_string());
+        self.edges.push(json!({
+            "source": format!("{source:#}"),
+            "target": format!("{target:#}"),
+            "attributes": [name, HIDDEN],
+        }));
+        Ok(())
+    }
 }
 
 impl<T: io::Write> Drop for SeqSeeBackend<T> {
     fn drop(&mut self) {
-        // Register every referenced style as an attribute alias so that the edges validate.
+        // Register every referenced style as an attribute alias so that the nodes and edges validate.
         let mut attributes = Map::new();
         for style in &self.styles {
             let attr = if is_differential(style) {
                 json!([{ "color": "blue" }])
+            } else if style == HIDDEN {
+                json!([{ "color": "red" }])
+            } else if style == DEAD {
+                json!([{ "color": "lightgray" }])
             } else {
                 json!([])
             };
@@ -0,0 +1,243 @@
+//! A backend for the [`spectralsequences`](https://ctan.org/pkg/spectralsequences) LaTeX package.
+//!
+//! Instead of drawing a picture, this emits a `sseqdata` environment, which can then be drawn with
+//! `\printpage[name = ..., page = ...]`. Each class is a `\class`, products are `\structline`s
+//! whose style is named after the product, and differentials are `\d`s. The styles of the
+//! products and of `permanent`, `dying`, `dead` and `hidden` are declared with `\sseqset` before the
+//! environment, so that the chart can be restyled by overriding them in the document.
+//!
+//! Nothing is written until [`SpectralSequencesBackend::finish`] is called.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    io,
+};
+
+use crate::{
+    SseqProfile,
+    charting::{Backend, Orientation},
+    coordinates::{Bidegree, BidegreeGenerator},
+};
+
+pub struct SpectralSequencesBackend<T: io::Write> {
+    out: T,
+    name: String,
+    /// The value of the `degree` key, i.e. the offset of a $d_r$ in terms of `#1 = r`.
+    degree: [String; 2],
+    max: Bidegree,
+    /// The bidegrees with classes, in the order they were drawn, and the number of classes.
+    nodes: Vec<(Bidegree, usize)>,
+    /// The options of each class, e.g. its label and style.
+    options: HashMap<BidegreeGenerator, Vec<String>>,
+    /// The structlines and differentials.
+    lines: Vec<String>,
+    /// The styles of the structlines that are products.
+    product_styles: BTreeSet<String>,
+}
+
+impl<T: io::Write> SpectralSequencesBackend<T> {
+    const STYLES: &'static str = r"  permanent/.sseq style = { fill },
+  dying/.sseq style = { },
+  dead/.sseq style = { gray },
+  hidden/.sseq style = { red, dashed },";
+
+    /// Create a backend writing a `sseqdata` environment named `name` to `out`. Characters that
+    /// are not ASCII alphanumeric are removed from the name. The differentials are those of the
+    /// Adams spectral sequence until [`Self::set_profile`] is called.
+    pub fn new(out: T, name: &str) -> Self {
+        Self {
+            out,
+            name: Self::key(name),
+            degree: [String::from("-1"), String::from("#1")],
+            max: Bidegree::zero(),
+            nodes: Vec::new(),
+            options: HashMap::new(),
+            lines: Vec::new(),
+            product_styles: BTreeSet::new(),
+        }
+    }
+
+    /// Use the differentials of the profile `P`. This assumes that the offset of a $d_r$ is affine
+    /// in $r$, which is the case for all the profiles in this crate.
+    pub fn set_profile<P: SseqProfile<2>>(&mut self) {
+        let constant = P::profile(0, Bidegree::zero());
+        let slope = P::profile(1, Bidegree::zero()) - constant;
+        let affine = |c: i32, k: i32| {
+            let r = match k {
+                0 => return c.to_string(),
+                1 => String::from("#1"),
+                -1 => String::from("-#1"),
+                k => format!("{k}*#1"),
+            };
+            match c {
+                0 => r,
+                c if c > 0 => format!("{r}+{c}"),
+                c => format!("{r}{c}"),
+            }
+        };
+        self.degree = [
+            affine(constant.x(), slope.x()),
+            affine(constant.y(), slope.y()),
+        ];
+    }
+
+    /// Turn a product name such as `h_0` into something usable as a pgf key.
+    fn key(name: &str) -> String {
+        name.chars().filter(char::is_ascii_alphanumeric).collect()
+    }
+
+    fn in_range(&self, g: BidegreeGenerator) -> bool {
+        g.x() <= self.max.x() && g.y() <= self.max.y()
+    }
+
+    /// The coordinate of `g` in the syntax of the package, whose class indices are 1-based.
+    fn coord(g: BidegreeGenerator) -> String {
+        format!("({},{},{})", g.x(), g.y(), g.idx() + 1)
+    }
+
+    /// Label the class `g`. The content is TeX math.
+    pub fn class_label(&mut self, g: BidegreeGenerator, content: impl Display) {
+        self.options
+            .entry(g)
+            .or_default()
+            .push(format!("\"{content}\""));
+    }
+
+    /// Write the styles and the `sseqdata` environment.
+    pub fn finish(mut self) -> io::Result<()> {
+        writeln!(self.out, r"\sseqset{{")?;
+        writeln!(self.out, "{}", Self::STYLES)?;
+        for style in &self.product_styles {
+            writeln!(self.out, "  {style}/.sseq style = {{ }},")?;
+        }
+        writeln!(self.out, "}}")?;
+
+        writeln!(
+            self.out,
+            "\\begin{{sseqdata}}[name = {name}, degree = {{{dx}}}{{{dy}}}, xrange = {{0}}{{{x}}}, \
+             yrange = {{0}}{{{y}}}]",
+            name = self.name,
+            dx = self.degree[0],
+            dy = self.degree[1],
+            x = self.max.x(),
+            y = self.max.y(),
+        )?;
+        for &(b, n) in &self.nodes {
+            for i in 0..n {
+                let g = BidegreeGenerator::new(b, i);
+                match self.options.get(&g) {
+                    Some(options) => writeln!(
+                        self.out,
+                        r"\class[{}]({},{})",
+                        options.join(", "),
+                        b.x(),
+                        b.y()
+                    )?,
+                    None => writeln!(self.out, r"\class({},{})", b.x(), b.y())?,
+                }
+            }
+        }
+        for line in &self.lines {
+            writeln!(self.out, "{line}")?;
+        }
+        writeln!(self.out, r"\end{{sseqdata}}")?;
+        self.out.flush()
+    }
+}
+
+impl<T: io::Write> Backend for SpectralSequencesBackend<T> {
+    type Error = std::io::Error;
+
+    const EXT: &'static str = "tex";
+
+    fn header(&mut self, max: Bidegree) -> Result<(), Self::Error> {
+        self.max = max;
+        Ok(())
+    }
+
+    // The package draws its own grid and axes
+    fn line(&mut self, _start: Bidegree, _end: Bidegree, _style: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn text(
+        &mut self,
+        _b: Bidegree,
+        _content: impl Display,
+        _orientation: Orientation,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    // Without more information, we label the first class in the bidegree
+    fn label(&mut self, b: Bidegree, content: impl Display) -> Result<(), Self::Error> {
+        self.class_label(BidegreeGenerator::new(b, 0), content);
+        Ok(())
+    }
+
+    fn node(&mut self, b: Bidegree, n: usize) -> Result<(), Self::Error> {
+        if n == 0 || b.x() > self.max.x() || b.y() > self.max.y() {
+            return Ok(());
+        }
+        self.nodes.push((b, n));
+        Ok(())
+    }
+
+    fn class_style(&mut self, g: BidegreeGenerator, style: &str) -> Result<(), Self::Error> {
+        // Put the style first, so that the label comes last
+        self.options
+            .entry(g)
+            .or_default()
+            .insert(0, style.to_owned());
+        Ok(())
+    }
+
+    fn structline(
+        &mut self,
+        source: BidegreeGenerator,
+        target: BidegreeGenerator,
+        style: Option<&str>,
+    ) -> Result<(), Self::Error> {
+        if !self.in_range(source) || !self.in_range(target) {
+            return Ok(());
+        }
+        let (source, target) = (Self::coord(source), Self::coord(target));
+
+        let line = match style {
+            Some(style) => match style.strip_prefix('d').and_then(|r| r.parse::<i32>().ok()) {
+                Some(r) if r < 10 => format!(r"\d{r}{source}{target}"),
+                Some(r) => format!(r"\d{{{r}}}{source}{target}"),
+                None => {
+                    let style = Self::key(style);
+                    if style.is_empty() {
+                        format!(r"\structline{source}{target}")
+                    } else {
+                        self.product_styles.insert(style.clone());
+                        format!(r"\structline[{style}]{source}{target}")
+                    }
+                }
+            },
+            None => format!(r"\structline{source}{target}"),
+        };
+        self.lines.push(line);
+        Ok(())
+    }
+
+    fn extension(
+        &mut self,
+        source: BidegreeGenerator,
+        target: BidegreeGenerator,
+        _name: &str,
+    ) -> Result<(), Self::Error> {
+        if !self.in_range(source) || !self.in_range(target) {
+            return Ok(());
+        }
+        self.lines.push(format!(
+            r"\structline[hidden]{}{}",
+            Self::coord(source),
+            Self::coord(target)
+        ));
+        Ok(())
+    }
+}
@@ -25,6 +25,13 @@ impl<T: io::Write> SvgBackend<T> {
     .d2 {
         stroke: blue;
     }
+    .hidden {
+        stroke: red;
+        stroke-dasharray: 2 1;
+    }
+    .dead {
+        fill: lightgray;
+    }
     .major-grid {
         stroke: black;
         opacity: 20%;
@@ -45,6 +52,11 @@ impl<T: io::Write> SvgBackend<T> {
      text-anchor: end;
      dominant-baseline: middle;
     }
+    .class-label {
+     text-anchor: middle;
+     dominant-baseline: text-after-edge;
+     font-size: 6px;
+    }
     "#;
 
     /// Print the legend for node patterns
@@ -133,8 +145,8 @@ impl<T: io::Write> Backend for SvgBackend<T> {
         )
     }
 
-    // SvgBackend only supports printing text to the left of or below a certain bidegree. This is
-    // used for printing axis labels.
+    // SvgBackend only supports printing text to the left of, below or above a certain bidegree. The
+    // first two are used for printing axis labels, and the last for labelling classes.
     fn text(
         &mut self,
         b: Bidegree,
@@ -145,7 +157,7 @@ impl<T: io::Write> Backend for SvgBackend<T> {
             Orientation::Left => ((-5, 0), "y-label"),
             Orientation::Right => unimplemented!(),
             Orientation::Below => ((0, 3), "x-label"),
-            Orientation::Above => unimplemented!(),
+            Orientation::Above => ((0, -4), "class-label"),
         };
 
         writeln!(
@@ -169,6 +181,18 @@ impl<T: io::Write> Backend for SvgBackend<T> {
         Ok(())
     }
 
+    // The class has already been drawn, so we draw it again on top with the new style
+    fn class_style(&mut self, g: BidegreeGenerator, style: &str) -> Result<(), Self::Error> {
+        if g.x() > self.max.x() || g.y() > self.max.y() {
+            return Ok(());
+        }
+        let (r, x, y) = self.get_coords(g);
+        writeln!(
+            self.out,
+            r#"<circle class="{style}" cx="{x}" cy="{y}" r="{r}"/>"#,
+        )
+    }
+
     fn structline(
         &mut self,
         source: BidegreeGenerator,
@@ -197,6 +221,15 @@ impl<T: io::Write> Backend for SvgBackend<T> {
 
         Ok(())
     }
+
+    fn extension(
+        &mut self,
+        source: BidegreeGenerator,
+        target: BidegreeGenerator,
+        name: &str,
+    ) -> Result<(), Self::Error> {
+        self.structline(source, target, Some(&format!("hidden {name}")))
+    }
 }
 
 impl<T: io::Write> Drop for SvgBackend<T> {
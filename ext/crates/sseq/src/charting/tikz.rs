@@ -16,6 +16,10 @@ impl<T: io::Write> TikzBackend<T> {
   major-grid/.style={ opacity = 0.2 },
   grid/.style={ opacity = 0.1 },
   d2/.style={ blue },
+  hidden/.style={ red, dashed },
+  permanent/.style={ },
+  dying/.style={ },
+  dead/.style={ black!20 },
 ]";
 
     pub fn new(out: T) -> Self {
@@ -89,6 +93,10 @@ impl<T: io::Write> Backend for TikzBackend<T> {
         )
     }
 
+    fn label(&mut self, b: Bidegree, content: impl Display) -> Result<(), Self::Error> {
+        self.text(b, format_args!("${content}$"), Orientation::Above)
+    }
+
     fn node(&mut self, b: Bidegree, n: usize) -> Result<(), Self::Error> {
         if n == 0 || b.x() > self.max.x() || b.y() > self.max.y() {
             return Ok(());
@@ -102,6 +110,18 @@ impl<T: io::Write> Backend for TikzBackend<T> {
         Ok(())
     }
 
+    // The class has already been drawn, so we draw it again on top with the new style
+    fn class_style(&mut self, g: BidegreeGenerator, style: &str) -> Result<(), Self::Error> {
+        if g.x() > self.max.x() || g.y() > self.max.y() {
+            return Ok(());
+        }
+        let (r, x, y) = self.get_coords(g);
+        writeln!(
+            self.out,
+            r#"\draw [fill, {style}] ({x}, {y}) circle ({r});"#
+        )
+    }
+
     fn structline(
         &mut self,
         source: BidegreeGenerator,
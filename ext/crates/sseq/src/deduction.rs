@@ -0,0 +1,861 @@
+//! Automatic deduction of differentials.
+//!
+//! A [`Deducer`] wraps an [`Sseq`] together with a list of products by permanent classes, and
+//! exhaustively propagates the consequences of the differentials and permanent classes it is told
+//! about. It uses the following rules:
+//!
+//!  - **Leibniz rule.** If $d_r x = y$, then $d_r(ax) = \pm ay$ for every product $a$, and if $x$
+//!    is permanent then so is $ax$.
+//!  - **Backwards Leibniz rule.** If $d_r(ax) \neq 0$, then $x$ is not permanent, so it must
+//!    support a differential of length at most $r$.
+//!  - **Sparseness.** If every possible target of a differential on a degree vanishes, then every
+//!    class in that degree is permanent. If a class must support a differential and there is only
+//!    one page on which the target can be non-zero, and that target is one-dimensional, then the
+//!    differential is determined.
+//!  - **Counting.** If the dimension of $E_\infty$ is known at a degree (say from a different
+//!    computation of the homotopy groups), then the classes beyond that must die. If there is only
+//!    one differential that can kill them, it must happen. If the number of remaining classes
+//!    equals the dimension of $E_\infty$, they are all permanent.
+//!
+//! Every deduction records the facts it was deduced from, and [`Deducer::explain`] produces the
+//! chain of reasoning leading to it. Contradictions are recorded rather than treated as errors,
+//! since the point is usually to rule out a hypothetical differential. See
+//! [`Deducer::contradictions`].
+//!
+//! Differentials longer than `max_r` are assumed not to exist, and degrees that are not defined
+//! in the spectral sequence are assumed to be non-zero unless
+//! [`Deducer::set_undefined_is_zero`] is called. As in the rest of [`Sseq`], pages are computed
+//! under the assumption that differentials that are not specified are zero. The deductions that
+//! pin down a specific differential only fire at the prime 2, since at odd primes a
+//! one-dimensional target only determines the differential up to a unit.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display, Formatter},
+};
+
+use fp::{matrix::Subspace, vector::FpVector};
+
+use crate::{
+    coordinates::{MultiDegree, MultiDegreeElement},
+    sseq::{Product, Sseq, SseqProfile},
+};
+
+/// A statement about the spectral sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fact<const N: usize> {
+    /// $d_r(\mathrm{source}) = \mathrm{target}$. A zero target means that the source survives to
+    /// $E_{r + 1}$.
+    Differential {
+        r: i32,
+        source: MultiDegreeElement<N>,
+        target: FpVector,
+    },
+    /// The class is a permanent cycle.
+    Permanent(MultiDegreeElement<N>),
+    /// The class is not a permanent cycle, and supports a non-zero $d_r$ for some $r \leq$
+    /// `max_r`.
+    MustSupport {
+        class: MultiDegreeElement<N>,
+        max_r: i32,
+    },
+}
+
+impl<const N: usize> Display for Fact<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Differential { r, source, target } => write!(f, "d_{r} {source} = {target}"),
+            Self::Permanent(class) => write!(f, "{class} is permanent"),
+            Self::MustSupport { class, max_r } => {
+                write!(f, "{class} supports a non-zero d_r for some r <= {max_r}")
+            }
+        }
+    }
+}
+
+/// The rule used to deduce a [`Fact`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason<const N: usize> {
+    /// The fact was supplied by the user.
+    Given,
+    Leibniz {
+        product: String,
+    },
+    BackwardsLeibniz {
+        product: String,
+    },
+    /// Every possible target of a differential vanishes.
+    Sparseness,
+    /// The class must support a differential and there is only one possible target.
+    OnlyTarget,
+    /// The dimension of $E_\infty$ at `degree` is `dimension`.
+    Counting {
+        degree: MultiDegree<N>,
+        dimension: usize,
+    },
+}
+
+impl<const N: usize> Display for Reason<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Given => write!(f, "given"),
+            Self::Leibniz { product } => write!(f, "Leibniz rule with {product}"),
+            Self::BackwardsLeibniz { product } => {
+                write!(f, "backwards Leibniz rule with {product}")
+            }
+            Self::Sparseness => write!(f, "sparseness"),
+            Self::OnlyTarget => write!(f, "only possible target"),
+            Self::Counting { degree, dimension } => {
+                write!(f, "E_inf has dimension {dimension} at {degree}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Deduction<const N: usize> {
+    pub fact: Fact<N>,
+    pub reason: Reason<N>,
+    /// The indices of the deductions this was deduced from.
+    pub from: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContradictionKind<const N: usize> {
+    /// [`Sseq::inconsistent`] holds at the degree.
+    InconsistentDifferentials,
+    /// A class that must support a differential is permanent.
+    PermanentSupports(MultiDegreeElement<N>),
+    /// A class must support a differential but every possible target vanishes.
+    NoTarget(MultiDegreeElement<N>),
+    /// Fewer classes remain than the known dimension of $E_\infty$.
+    TooFewClasses { einf: usize, remaining: usize },
+    /// More classes remain than the known dimension of $E_\infty$, and there are not enough
+    /// possible differentials to kill them.
+    TooManyClasses {
+        einf: usize,
+        remaining: usize,
+        max_killed: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Contradiction<const N: usize> {
+    pub degree: MultiDegree<N>,
+    pub kind: ContradictionKind<N>,
+    /// The indices of the deductions involved.
+    pub from: Vec<usize>,
+}
+
+impl<const N: usize> Display for Contradiction<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let degree = self.degree;
+        match &self.kind {
+            ContradictionKind::InconsistentDifferentials => {
+                write!(f, "Inconsistent differentials at {degree}")
+            }
+            ContradictionKind::PermanentSupports(class) => {
+                write!(f, "{class} is permanent but must support a differential")
+            }
+            ContradictionKind::NoTarget(class) => write!(
+                f,
+                "{class} must support a differential but has no possible target"
+            ),
+            ContradictionKind::TooFewClasses { einf, remaining } => write!(
+                f,
+                "E_inf has dimension {einf} at {degree} but only {remaining} classes remain"
+            ),
+            ContradictionKind::TooManyClasses {
+                einf,
+                remaining,
+                max_killed,
+            } => write!(
+                f,
+                "E_inf has dimension {einf} at {degree} but {remaining} classes remain and at \
+                 most {max_killed} can be killed"
+            ),
+        }
+    }
+}
+
+pub struct Deducer<'a, const N: usize, P: SseqProfile<N>> {
+    sseq: &'a mut Sseq<N, P>,
+    /// Products by permanent classes.
+    products: Vec<(String, Product<N>)>,
+    max_r: i32,
+    undefined_is_zero: bool,
+    /// Known dimensions of $E_\infty$.
+    einf: Vec<(MultiDegree<N>, usize)>,
+    deductions: Vec<Deduction<N>>,
+    contradictions: Vec<Contradiction<N>>,
+    /// Deductions whose consequences under the Leibniz rule are yet to be computed.
+    queue: VecDeque<usize>,
+}
+
+impl<'a, const N: usize, P: SseqProfile<N>> Deducer<'a, N, P> {
+    pub fn new(sseq: &'a mut Sseq<N, P>, max_r: i32) -> Self {
+        Self {
+            sseq,
+            products: Vec::new(),
+            max_r,
+            undefined_is_zero: false,
+            einf: Vec::new(),
+            deductions: Vec::new(),
+            contradictions: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn sseq(&self) -> &Sseq<N, P> {
+        &*self.sseq
+    }
+
+    /// Adds a product by a permanent class.
+    pub fn add_product(&mut self, name: &str, product: Product<N>) {
+        self.products.push((name.to_owned(), product));
+    }
+
+    /// Whether degrees that are not defined in the spectral sequence should be treated as zero.
+    /// This is false by default.
+    pub fn set_undefined_is_zero(&mut self, undefined_is_zero: bool) {
+        self.undefined_is_zero = undefined_is_zero;
+    }
+
+    /// Records that $E_\infty$ has dimension `dimension` at `b`.
+    pub fn set_einf_dimension(&mut self, b: MultiDegree<N>, dimension: usize) {
+        self.einf.retain(|&(b_, _)| b_ != b);
+        self.einf.push((b, dimension));
+    }
+
+    /// Adds a known differential. Returns the index of the deduction, or `None` if the
+    /// differential was already known.
+    pub fn add_differential(
+        &mut self,
+        r: i32,
+        source: MultiDegreeElement<N>,
+        target: FpVector,
+    ) -> Option<usize> {
+        self.apply(
+            Fact::Differential { r, source, target },
+            Reason::Given,
+            Vec::new(),
+        )
+    }
+
+    /// Adds a known permanent class. Returns the index of the deduction, or `None` if the class
+    /// was already known to be permanent.
+    pub fn add_permanent_class(&mut self, class: MultiDegreeElement<N>) -> Option<usize> {
+        self.apply(Fact::Permanent(class), Reason::Given, Vec::new())
+    }
+
+    pub fn deductions(&self) -> &[Deduction<N>] {
+        &self.deductions
+    }
+
+    pub fn contradictions(&self) -> &[Contradiction<N>] {
+        &self.contradictions
+    }
+
+    /// Propagates the known facts until nothing new can be deduced.
+    pub fn run(&mut self) {
+        loop {
+            while let Some(i) = self.queue.pop_front() {
+                self.leibniz(i);
+            }
+            self.sseq.update();
+            self.find_inconsistencies();
+
+            // Each rule reads the pages, so we start over after one of them makes progress.
+            if !(self.sparseness()
+                || self.backwards_leibniz()
+                || self.resolve_must_support()
+                || self.count())
+            {
+                break;
+            }
+        }
+    }
+
+    /// The indices of the deductions that `roots` were deduced from, including `roots` themselves,
+    /// in the order they were deduced.
+    pub fn ancestors(&self, roots: &[usize]) -> Vec<usize> {
+        let mut seen = vec![false; self.deductions.len()];
+        let mut stack = roots.to_vec();
+        while let Some(i) = stack.pop() {
+            if !std::mem::replace(&mut seen[i], true) {
+                stack.extend_from_slice(&self.deductions[i].from);
+            }
+        }
+        (0..self.deductions.len()).filter(|&i| seen[i]).collect()
+    }
+
+    /// A human readable explanation of how deduction `i` was obtained, one step per line.
+    pub fn explain(&self, i: usize) -> String {
+        self.format_chain(&[i])
+    }
+
+    /// A human readable explanation of a contradiction, one step per line.
+    pub fn explain_contradiction(&self, contradiction: &Contradiction<N>) -> String {
+        format!(
+            "{}{contradiction}\n",
+            self.format_chain(&contradiction.from)
+        )
+    }
+
+    fn format_chain(&self, roots: &[usize]) -> String {
+        let mut result = String::new();
+        for i in self.ancestors(roots) {
+            let d = &self.deductions[i];
+            result.push_str(&format!("[{i}] {} ({}", d.fact, d.reason));
+            if !d.from.is_empty() {
+                result.push_str(&format!(" from {:?}", d.from));
+            }
+            result.push_str(")\n");
+        }
+        result
+    }
+
+    /// Applies a fact to the spectral sequence and records it if it is new.
+    fn apply(&mut self, fact: Fact<N>, reason: Reason<N>, from: Vec<usize>) -> Option<usize> {
+        let is_new = match &fact {
+            Fact::Differential { r, source, target } => {
+                self.sseq.add_differential(*r, source, target.as_slice())
+            }
+            Fact::Permanent(class) => self.sseq.add_permanent_class(class),
+            Fact::MustSupport { class, max_r } => !self.deductions.iter().any(|d| {
+                matches!(&d.fact, Fact::MustSupport { class: c, max_r: m } if c == class && m <= max_r)
+            }),
+        };
+        if !is_new {
+            return None;
+        }
+        let i = self.deductions.len();
+        self.deductions.push(Deduction { fact, reason, from });
+        self.queue.push_back(i);
+        Some(i)
+    }
+
+    fn contradiction(
+        &mut self,
+        degree: MultiDegree<N>,
+        kind: ContradictionKind<N>,
+        from: Vec<usize>,
+    ) {
+        if !self
+            .contradictions
+            .iter()
+            .any(|c| c.degree == degree && c.kind == kind)
+        {
+            self.contradictions
+                .push(Contradiction { degree, kind, from });
+        }
+    }
+
+    /// The deductions that involve one of `degrees`.
+    fn touching(&self, degrees: &[MultiDegree<N>]) -> Vec<usize> {
+        self.deductions
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| match &d.fact {
+                Fact::Differential { r, source, .. } => {
+                    degrees.contains(&source.degree())
+                        || degrees.contains(&P::profile(*r, source.degree()))
+                }
+                Fact::Permanent(class) | Fact::MustSupport { class, .. } => {
+                    degrees.contains(&class.degree())
+                }
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The dimension of $E_r$ at `b`, or `None` if `b` is not defined and we don't treat undefined
+    /// degrees as zero.
+    fn page_dimension(&self, b: MultiDegree<N>, r: i32) -> Option<usize> {
+        if self.sseq.defined(b) {
+            Some(self.sseq.page_data(b).get_max(r).dimension())
+        } else if self.undefined_is_zero {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    fn possibly_nonzero(&self, b: MultiDegree<N>, r: i32) -> bool {
+        self.page_dimension(b, r) != Some(0)
+    }
+
+    /// The dimension of $E_r$ at `b` modulo the permanent classes, i.e. the number of classes that
+    /// can support a differential.
+    fn supportable(&self, b: MultiDegree<N>, r: i32) -> Option<usize> {
+        if !self.sseq.defined(b) {
+            return self.page_dimension(b, r);
+        }
+        let p = self.sseq.prime();
+        let page = self.sseq.page_data(b).get_max(r);
+        let mut permanent = Subspace::new(p, page.dimension());
+        for class in self.sseq.permanent_classes(b).basis() {
+            let mut class = class.to_owned();
+            let coords = page.reduce(class.as_slice_mut());
+            permanent.add_vector(FpVector::from_slice(p, &coords).as_slice());
+        }
+        Some(page.dimension() - permanent.dimension())
+    }
+
+    /// The unique generator of $E_r$ at `b` if it is one-dimensional.
+    fn unique_generator(&self, b: MultiDegree<N>, r: i32) -> Option<FpVector> {
+        if !self.sseq.defined(b) {
+            return None;
+        }
+        let page = self.sseq.page_data(b).get_max(r);
+        if page.dimension() != 1 {
+            return None;
+        }
+        page.gens().next().map(|g| g.to_owned())
+    }
+
+    /// The generators of the last page at `b`.
+    fn last_page_gens(&self, b: MultiDegree<N>) -> Vec<FpVector> {
+        self.sseq
+            .page_data(b)
+            .last()
+            .unwrap()
+            .gens()
+            .map(|g| g.to_owned())
+            .collect()
+    }
+
+    /// The shortest known non-zero differential on `class`.
+    fn known_support(&self, class: &MultiDegreeElement<N>) -> Option<i32> {
+        let b = class.degree();
+        let differentials = self.sseq.differentials(b);
+        differentials.range().find(|&r| {
+            let Some(mut value) = differentials[r].evaluate_known(class.vec()) else {
+                return false;
+            };
+            self.sseq
+                .page_data(P::profile(r, b))
+                .get_max(r)
+                .zeros()
+                .reduce(value.as_slice_mut());
+            !value.is_zero()
+        })
+    }
+
+    fn leibniz(&mut self, i: usize) {
+        let fact = self.deductions[i].fact.clone();
+        let p = self.sseq.prime();
+        for j in 0..self.products.len() {
+            let (name, prod) = &self.products[j];
+            let reason = Reason::Leibniz {
+                product: name.clone(),
+            };
+            let new_fact = match &fact {
+                Fact::Differential { r, source, target } => {
+                    let Some(new_source) = self.sseq.multiply(source, prod) else {
+                        continue;
+                    };
+                    if new_source.vec().is_zero() {
+                        continue;
+                    }
+                    let target_b = P::profile(*r, new_source.degree());
+                    let Some(target_dim) = self.sseq.get_dimension(target_b) else {
+                        continue;
+                    };
+                    let mut new_target = FpVector::new(p, target_dim);
+                    if !target.is_zero() {
+                        let target = MultiDegreeElement::new(
+                            P::profile(*r, source.degree()),
+                            target.clone(),
+                        );
+                        let Some(product) = self.sseq.multiply(&target, prod) else {
+                            continue;
+                        };
+                        // This is the sign convention of `Sseq::leibniz`.
+                        let c = if prod.left && P::total_degree(prod.b) % 2 != 0 {
+                            p - 1
+                        } else {
+                            1
+                        };
+                        new_target.add(&product.into_vec(), c);
+                    }
+                    Fact::Differential {
+                        r: *r,
+                        source: new_source,
+                        target: new_target,
+                    }
+                }
+                Fact::Permanent(class) => {
+                    let Some(new_class) = self.sseq.multiply(class, prod) else {
+                        continue;
+                    };
+                    if new_class.vec().is_zero() {
+                        continue;
+                    }
+                    Fact::Permanent(new_class)
+                }
+                Fact::MustSupport { .. } => return,
+            };
+            self.apply(new_fact, reason, vec![i]);
+        }
+    }
+
+    fn find_inconsistencies(&mut self) {
+        let degrees: Vec<_> = self
+            .sseq
+            .iter_degrees()
+            .filter(|&b| self.sseq.inconsistent(b))
+            .collect();
+        for b in degrees {
+            let from = self.touching(&[b]);
+            self.contradiction(b, ContradictionKind::InconsistentDifferentials, from);
+        }
+    }
+
+    fn sparseness(&mut self) -> bool {
+        let mut progress = false;
+        let degrees: Vec<_> = self.sseq.iter_degrees().collect();
+        for b in degrees {
+            if self.sseq.complete(b)
+                || (P::MIN_R..=self.max_r).any(|r| self.possibly_nonzero(P::profile(r, b), r))
+            {
+                continue;
+            }
+            let from = self.touching(&[b]);
+            // No differential can be non-zero, so every class is permanent.
+            let p = self.sseq.prime();
+            let dim = self.sseq.dimension(b);
+            for i in 0..dim {
+                let mut v = FpVector::new(p, dim);
+                v.set_entry(i, 1);
+                progress |= self
+                    .apply(
+                        Fact::Permanent(MultiDegreeElement::new(b, v)),
+                        Reason::Sparseness,
+                        from.clone(),
+                    )
+                    .is_some();
+            }
+        }
+        progress
+    }
+
+    fn backwards_leibniz(&mut self) -> bool {
+        let mut found = Vec::new();
+        for b in self.sseq.iter_degrees() {
+            for (name, prod) in &self.products {
+                let prod_b = b + prod.b;
+                if !self.sseq.defined(prod_b) {
+                    continue;
+                }
+                for g in self.last_page_gens(b) {
+                    if self.sseq.permanent_classes(b).contains(g.as_slice()) {
+                        continue;
+                    }
+                    let class = MultiDegreeElement::new(b, g);
+                    let Some(product) = self.sseq.multiply(&class, prod) else {
+                        continue;
+                    };
+                    if product.vec().is_zero() {
+                        continue;
+                    }
+                    if let Some(r) = self.known_support(&product) {
+                        let reason = Reason::BackwardsLeibniz {
+                            product: name.clone(),
+                        };
+                        found.push((Fact::MustSupport { class, max_r: r }, reason, prod_b));
+                    }
+                }
+            }
+        }
+
+        let mut progress = false;
+        for (fact, reason, prod_b) in found {
+            let from = self.touching(&[prod_b]);
+            progress |= self.apply(fact, reason, from).is_some();
+        }
+        progress
+    }
+
+    fn resolve_must_support(&mut self) -> bool {
+        let mut progress = false;
+        for i in 0..self.deductions.len() {
+            let Fact::MustSupport { class, max_r } = self.deductions[i].fact.clone() else {
+                continue;
+            };
+            let b = class.degree();
+            if self.sseq.permanent_classes(b).contains(class.vec()) {
+                let from = self.touching(&[b]);
+                self.contradiction(b, ContradictionKind::PermanentSupports(class), from);
+                continue;
+            }
+            if self.known_support(&class).is_some_and(|r| r <= max_r) {
+                continue;
+            }
+            let targets: Vec<i32> = (P::MIN_R..=max_r)
+                .filter(|&r| self.possibly_nonzero(P::profile(r, b), r))
+                .collect();
+            match targets[..] {
+                [] => self.contradiction(b, ContradictionKind::NoTarget(class), vec![i]),
+                [r] if self.sseq.prime() == 2 => {
+                    let Some(target) = self.unique_generator(P::profile(r, b), r) else {
+                        continue;
+                    };
+                    let fact = Fact::Differential {
+                        r,
+                        source: class,
+                        target,
+                    };
+                    progress |= self.apply(fact, Reason::OnlyTarget, vec![i]).is_some();
+                }
+                _ => {}
+            }
+        }
+        progress
+    }
+
+    fn count(&mut self) -> bool {
+        let mut progress = false;
+        for (b, einf) in self.einf.clone() {
+            if !self.sseq.defined(b) {
+                continue;
+            }
+            let remaining = self.sseq.page_data(b).last().unwrap().dimension();
+            let reason = Reason::Counting {
+                degree: b,
+                dimension: einf,
+            };
+            if remaining < einf {
+                let from = self.touching(&[b]);
+                self.contradiction(
+                    b,
+                    ContradictionKind::TooFewClasses { einf, remaining },
+                    from,
+                );
+            } else if remaining == einf {
+                // Nothing else can happen here, so all remaining classes are permanent.
+                let from = self.touching(&[b]);
+                for g in self.last_page_gens(b) {
+                    let fact = Fact::Permanent(MultiDegreeElement::new(b, g));
+                    progress |= self.apply(fact, reason.clone(), from.clone()).is_some();
+                }
+            } else {
+                progress |= self.count_excess(b, einf, remaining, reason);
+            }
+        }
+        progress
+    }
+
+    /// Handles a degree where `remaining > einf`, so some classes still have to die.
+    fn count_excess(
+        &mut self,
+        b: MultiDegree<N>,
+        einf: usize,
+        remaining: usize,
+        reason: Reason<N>,
+    ) -> bool {
+        // A possible differential, recorded as (r, source, target) together with the maximum
+        // number of classes at `b` it can kill.
+        let mut options = Vec::new();
+        let supportable = self.supportable(b, i32::MAX).unwrap_or(usize::MAX);
+        for r in P::MIN_R..=self.max_r {
+            let target = P::profile(r, b);
+            if supportable > 0 && self.possibly_nonzero(target, r) {
+                let capacity = self
+                    .page_dimension(target, r)
+                    .unwrap_or(usize::MAX)
+                    .min(supportable);
+                options.push((r, b, target, capacity));
+            }
+            let source = P::profile_inverse(r, b);
+            let here = self.sseq.page_data(b).get_max(r).dimension();
+            if here > 0 && self.possibly_nonzero(source, r) {
+                let capacity = self.supportable(source, r).unwrap_or(usize::MAX).min(here);
+                if capacity > 0 {
+                    options.push((r, source, b, capacity));
+                }
+            }
+        }
+
+        let max_killed = options
+            .iter()
+            .fold(0usize, |acc, o| acc.saturating_add(o.3))
+            .min(remaining);
+        let from = self.touching(&[b]);
+        if max_killed < remaining - einf {
+            self.contradiction(
+                b,
+                ContradictionKind::TooManyClasses {
+                    einf,
+                    remaining,
+                    max_killed,
+                },
+                from,
+            );
+            return false;
+        }
+
+        // If there is only one possible differential and both ends are one-dimensional, it must
+        // happen.
+        let [(r, source_b, target_b, _)] = options[..] else {
+            return false;
+        };
+        if remaining != 1 || self.sseq.prime() != 2 {
+            return false;
+        }
+        let (Some(source), Some(target)) = (
+            self.unique_generator(source_b, r),
+            self.unique_generator(target_b, r),
+        ) else {
+            return false;
+        };
+        let fact = Fact::Differential {
+            r,
+            source: MultiDegreeElement::new(source_b, source),
+            target,
+        };
+        self.apply(fact, reason, from).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fp::prime::{P2, ValidPrime};
+    use once::MultiIndexed;
+
+    use super::*;
+    use crate::{
+        coordinates::{Bidegree, BidegreeElement},
+        sseq::Adams,
+    };
+
+    fn element(x: i32, y: i32, v: &[u32]) -> BidegreeElement {
+        BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(P2, v))
+    }
+
+    /// A product of degree `b` which is the identity on the one-dimensional degrees `sources`.
+    fn identity_product(b: Bidegree, sources: &[Bidegree]) -> Product<2> {
+        let matrices = MultiIndexed::new();
+        for &s in sources {
+            matrices.insert(
+                s,
+                fp::matrix::Matrix::from_vec(ValidPrime::new(2), &[vec![1]]),
+            );
+        }
+        Product {
+            b,
+            left: true,
+            matrices,
+        }
+    }
+
+    /// Two $h_0$-towers in stems 0 and 1, with $h_0$ acting by the identity.
+    fn towers(height: i32) -> (Sseq<2, Adams>, Product<2>) {
+        let mut sseq = Sseq::new(ValidPrime::new(2));
+        let mut sources = Vec::new();
+        for x in 0..2 {
+            for y in 0..height {
+                sseq.set_dimension(Bidegree::x_y(x, y), 1);
+                if y + 1 < height {
+                    sources.push(Bidegree::x_y(x, y));
+                }
+            }
+        }
+        (sseq, identity_product(Bidegree::x_y(0, 1), &sources))
+    }
+
+    #[test]
+    fn test_leibniz() {
+        let (mut sseq, h0) = towers(8);
+        let mut deducer = Deducer::new(&mut sseq, 2);
+        deducer.add_product("h0", h0);
+        deducer.add_differential(2, element(1, 0, &[1]), FpVector::from_slice(P2, &[1]));
+        deducer.run();
+
+        assert!(deducer.contradictions().is_empty());
+        // d_2(h_0^k x) = h_0^{k + 2} for k + 2 < 8
+        let last = deducer
+            .deductions()
+            .iter()
+            .position(|d| {
+                d.fact
+                    == Fact::Differential {
+                        r: 2,
+                        source: element(1, 5, &[1]),
+                        target: FpVector::from_slice(P2, &[1]),
+                    }
+            })
+            .unwrap();
+        assert_eq!(deducer.ancestors(&[last]).len(), 6);
+        expect_test::expect![[r#"
+            [0] d_2 (1, 0, [1]) = [1] (given)
+            [1] d_2 (1, 1, [1]) = [1] (Leibniz rule with h0 from [0])
+        "#]]
+        .assert_eq(&deducer.explain(1));
+    }
+
+    #[test]
+    fn test_backwards_leibniz() {
+        let (mut sseq, h0) = towers(4);
+        let mut deducer = Deducer::new(&mut sseq, 3);
+        deducer.add_product("h0", h0);
+        deducer.set_undefined_is_zero(true);
+        // d_2(h_0 x) != 0 forces d_2(x) != 0, and the only possible target is h_0^2.
+        deducer.add_differential(2, element(1, 1, &[1]), FpVector::from_slice(P2, &[1]));
+        deducer.run();
+
+        assert!(deducer.contradictions().is_empty());
+        assert!(deducer.deductions().iter().any(|d| {
+            d.reason == Reason::OnlyTarget
+                && d.fact
+                    == Fact::Differential {
+                        r: 2,
+                        source: element(1, 0, &[1]),
+                        target: FpVector::from_slice(P2, &[1]),
+                    }
+        }));
+    }
+
+    #[test]
+    fn test_counting() {
+        let mut sseq = Sseq::<2, Adams>::new(ValidPrime::new(2));
+        sseq.set_dimension(Bidegree::x_y(0, 0), 0);
+        sseq.set_dimension(Bidegree::x_y(0, 1), 0);
+        sseq.set_dimension(Bidegree::x_y(0, 2), 1);
+        sseq.set_dimension(Bidegree::x_y(1, 0), 1);
+        let mut deducer = Deducer::new(&mut sseq, 3);
+        deducer.set_undefined_is_zero(true);
+        deducer.set_einf_dimension(Bidegree::x_y(1, 0), 0);
+        deducer.run();
+
+        assert!(deducer.contradictions().is_empty());
+        assert!(deducer.deductions().iter().any(|d| {
+            matches!(d.reason, Reason::Counting { .. })
+                && d.fact
+                    == Fact::Differential {
+                        r: 2,
+                        source: element(1, 0, &[1]),
+                        target: FpVector::from_slice(P2, &[1]),
+                    }
+        }));
+    }
+
+    #[test]
+    fn test_contradiction() {
+        let mut sseq = Sseq::<2, Adams>::new(ValidPrime::new(2));
+        sseq.set_dimension(Bidegree::x_y(1, 0), 1);
+        let mut deducer = Deducer::new(&mut sseq, 3);
+        deducer.set_undefined_is_zero(true);
+        deducer.set_einf_dimension(Bidegree::x_y(1, 0), 0);
+        deducer.run();
+
+        let [contradiction] = deducer.contradictions() else {
+            panic!("Expected exactly one contradiction");
+        };
+        expect_test::expect![[r#"
+            [0] (1, 0, [1]) is permanent (sparseness)
+            E_inf has dimension 0 at (1, 0) but 1 classes remain and at most 0 can be killed
+        "#]]
+        .assert_eq(&deducer.explain_contradiction(contradiction));
+    }
+}
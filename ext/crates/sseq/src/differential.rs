@@ -122,6 +122,34 @@ impl Differential {
         }
     }
 
+    /// Evaluates the differential on `source` if it is determined by the known differentials, i.e.
+    /// if `source` is in the span of the sources of the differentials added so far. Unlike
+    /// [`Self::evaluate`], this does not assume unspecified differentials are zero.
+    pub fn evaluate_known(&self, source: FpSlice) -> Option<FpVector> {
+        let p = self.prime();
+        let mut scratch = FpVector::new(p, self.source_dim + self.target_dim);
+        scratch.slice_mut(0, self.source_dim).assign(source);
+        for i in 0..self.source_dim {
+            let c = scratch.entry(i);
+            if c == 0 {
+                continue;
+            }
+            let row = self.matrix.pivots()[i];
+            if row < 0 {
+                return None;
+            }
+            scratch
+                .as_slice_mut()
+                .add(self.matrix.row(row as usize), p - c);
+        }
+        // We subtracted the differentials, so the target part is minus the value.
+        let mut result = scratch
+            .slice(self.source_dim, self.source_dim + self.target_dim)
+            .to_owned();
+        result.scale(p - 1);
+        Some(result)
+    }
+
     pub fn prime(&self) -> ValidPrime {
         self.matrix.prime()
     }
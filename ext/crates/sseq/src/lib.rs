@@ -2,6 +2,7 @@
 
 pub mod charting;
 pub mod coordinates;
+pub mod deduction;
 mod differential;
 mod sseq;
 
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use std::{collections::HashSet, marker::PhantomData};
 
 use bivec::BiVec;
 use fp::{
@@ -19,8 +19,26 @@ pub trait SseqProfile<const N: usize> {
     fn profile(r: i32, b: MultiDegree<N>) -> MultiDegree<N>;
     fn profile_inverse(r: i32, b: MultiDegree<N>) -> MultiDegree<N>;
     fn differential_length(offset: MultiDegree<N>) -> i32;
+
+    /// The degree that determines the signs in the Leibniz rule, i.e. the total degree of the
+    /// spectral sequence. This is the `x` coordinate by default.
+    fn total_degree(b: MultiDegree<N>) -> i32 {
+        b.x()
+    }
+
+    /// If classes in degrees differing by `offset` converge to the same degree of the abutment,
+    /// this is the difference of their filtrations, normalized so that differentials increase the
+    /// filtration. Otherwise this is `None`. Hidden extensions jump to positive filtration.
+    ///
+    /// By default, the filtration is the `y` coordinate and the other coordinates are preserved.
+    fn filtration_jump(offset: MultiDegree<N>) -> Option<i32> {
+        let coords = offset.coords();
+        (N >= 2 && coords.iter().enumerate().all(|(i, &c)| i == 1 || c == 0)).then(|| coords[1])
+    }
 }
 
+/// The Adams spectral sequence, drawn with `x` the stem and `y` the Adams filtration. A $d_r$
+/// goes from $(x, y)$ to $(x - 1, y + r)$.
 pub struct Adams;
 
 impl SseqProfile<2> for Adams {
@@ -39,6 +57,140 @@ impl SseqProfile<2> for Adams {
     }
 }
 
+/// The cohomological Serre spectral sequence $E_2^{p, q} = H^p(B; H^q(F))$, drawn with `x = p`
+/// and `y = q`. A $d_r$ goes from $(p, q)$ to $(p + r, q - r + 1)$.
+pub struct Serre;
+
+impl SseqProfile<2> for Serre {
+    const MIN_R: i32 = 2;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(r, 1 - r)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        b - Bidegree::x_y(r, 1 - r)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        offset.x()
+    }
+
+    fn total_degree(b: Bidegree) -> i32 {
+        b.x() + b.y()
+    }
+
+    fn filtration_jump(offset: Bidegree) -> Option<i32> {
+        (Self::total_degree(offset) == 0).then(|| offset.x())
+    }
+}
+
+/// The Atiyah–Hirzebruch spectral sequence $E^2_{p, q} = H_p(X; E_q) \Rightarrow E_{p + q}(X)$,
+/// drawn with `x = p` and `y = q`. A $d_r$ goes from $(p, q)$ to $(p - r, q + r - 1)$. See
+/// [`Cellular`] for the version that starts at the $E^1$ page.
+pub struct AtiyahHirzebruch;
+
+impl SseqProfile<2> for AtiyahHirzebruch {
+    const MIN_R: i32 = 2;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(-r, r - 1)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        b - Bidegree::x_y(-r, r - 1)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        -offset.x()
+    }
+
+    fn total_degree(b: Bidegree) -> i32 {
+        b.x() + b.y()
+    }
+
+    fn filtration_jump(offset: Bidegree) -> Option<i32> {
+        (Self::total_degree(offset) == 0).then(|| -offset.x())
+    }
+}
+
+/// The spectral sequence of the cellular filtration, $E^1_{p, q} = E_{p + q}(X^p, X^{p - 1})$.
+/// This is the [`AtiyahHirzebruch`] spectral sequence, except it starts at the $E^1$ page, whose
+/// $d_1$ is the cellular boundary.
+pub struct Cellular;
+
+impl SseqProfile<2> for Cellular {
+    const MIN_R: i32 = 1;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        AtiyahHirzebruch::profile(r, b)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        AtiyahHirzebruch::profile_inverse(r, b)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        AtiyahHirzebruch::differential_length(offset)
+    }
+
+    fn total_degree(b: Bidegree) -> i32 {
+        AtiyahHirzebruch::total_degree(b)
+    }
+
+    fn filtration_jump(offset: Bidegree) -> Option<i32> {
+        AtiyahHirzebruch::filtration_jump(offset)
+    }
+}
+
+/// The Bockstein spectral sequence, drawn with `x` the degree and `y` the power of $p$. It starts
+/// at the $E_1$ page, whose $d_1$ is the Bockstein, and a $d_r$ goes from $(x, y)$ to
+/// $(x - 1, y + r)$, so that a $d_r$ detects a copy of $\mathbb{Z}/p^r$.
+pub struct Bockstein;
+
+impl SseqProfile<2> for Bockstein {
+    const MIN_R: i32 = 1;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        Adams::profile(r, b)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        Adams::profile_inverse(r, b)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        Adams::differential_length(offset)
+    }
+}
+
+/// The May spectral sequence, drawn with `x` the stem, `y` the Adams filtration and `z` the May
+/// filtration. It starts at the $E_1$ page, and a $d_r$ goes from $(x, y, z)$ to $(x - 1, y + 1, z
+/// + 1 - r)$.
+pub struct May;
+
+impl SseqProfile<3> for May {
+    const MIN_R: i32 = 1;
+
+    fn profile(r: i32, b: MultiDegree<3>) -> MultiDegree<3> {
+        b + MultiDegree::new([-1, 1, 1 - r])
+    }
+
+    fn profile_inverse(r: i32, b: MultiDegree<3>) -> MultiDegree<3> {
+        b - MultiDegree::new([-1, 1, 1 - r])
+    }
+
+    fn differential_length(offset: MultiDegree<3>) -> i32 {
+        1 - offset.coords()[2]
+    }
+
+    fn filtration_jump(offset: MultiDegree<3>) -> Option<i32> {
+        let [x, y, z] = offset.coords();
+        (x == 0 && y == 0).then_some(-z)
+    }
+}
+
+#[derive(Clone)]
 pub struct Product<const N: usize> {
     pub b: MultiDegree<N>,
     /// Whether the product acts on the left or not. This affects the sign in the Leibniz rule.
@@ -46,6 +198,17 @@ pub struct Product<const N: usize> {
     pub matrices: MultiIndexed<N, Matrix>,
 }
 
+/// A hidden extension in $E_\infty$. This records that the product of the permanent classes
+/// represented by `source` with the class defining `product` is detected by `target`, which is in
+/// higher filtration than the product in $E_\infty$ (which is zero).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiddenExtension<const N: usize> {
+    /// The name of the product
+    pub product: String,
+    pub source: MultiDegreeElement<N>,
+    pub target: MultiDegreeElement<N>,
+}
+
 struct DegreeData {
     /// The dimension of the module at this degree (i.e. the number of generators).
     dimension: usize,
@@ -80,6 +243,8 @@ pub struct Sseq<const N: usize, P: SseqProfile<N>> {
     ///   `data[target].page_data[r + 1]` are always defined,
     data: MultiIndexed<N, DegreeData>,
 
+    hidden_extensions: Vec<HiddenExtension<N>>,
+
     // `P` is itself a marker, so it's safe to claim that we own one. As opposed to
     // `PhantomData<*const P>`, this lets us implement `Send` and `Sync`.
     profile: PhantomData<P>,
@@ -90,10 +255,15 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         Self {
             p,
             data: MultiIndexed::new(),
+            hidden_extensions: Vec::new(),
             profile: PhantomData,
         }
     }
 
+    pub fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
     pub fn min(&self) -> MultiDegree<N> {
         self.data
             .min_coords()
@@ -141,6 +311,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
             bd.page_data.iter_mut().for_each(Subquotient::set_to_full);
             bd.invalid = true;
         }
+        self.hidden_extensions.clear();
     }
 
     pub fn dimension(&self, b: MultiDegree<N>) -> usize {
@@ -368,6 +539,15 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         &self.data[b].page_data
     }
 
+    /// The last page on which the spectral sequence changes, i.e. the page after the longest
+    /// differential. This is $E_\infty$ as far as the spectral sequence knows.
+    pub fn last_page(&self) -> i32 {
+        self.iter_degrees()
+            .map(|b| self.page_data(b).max_degree())
+            .max()
+            .unwrap_or(P::MIN_R)
+    }
+
     /// Compute the product between `product` and the class `class`. Returns `None` if
     /// the product is not yet computed.
     pub fn multiply(
@@ -382,6 +562,127 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         Some(MultiDegreeElement::new(target_b, result))
     }
 
+    /// Whether `elem` is a permanent class that is non-zero on the last page.
+    fn nonzero_permanent(&self, elem: &MultiDegreeElement<N>) -> bool {
+        let Some(bd) = self.data.get(elem.degree()) else {
+            return false;
+        };
+        let mut v = elem.vec().to_owned();
+        bd.page_data
+            .last()
+            .unwrap()
+            .reduce_by_quotient(v.as_slice_mut());
+        bd.permanent_classes.contains(elem.vec()) && !v.is_zero()
+    }
+
+    /// Record a hidden extension by `product`. This checks that
+    ///  - `source` and `target` are permanent classes that are non-zero on the last page;
+    ///  - the product of `source` with `product` is zero on the last page; and
+    ///  - `target` is in the degree of this product shifted up by a positive
+    ///    [filtration jump](SseqProfile::filtration_jump).
+    ///
+    /// # Return
+    ///
+    /// Whether the hidden extension is new, or an error explaining why it is invalid.
+    pub fn add_hidden_extension(
+        &mut self,
+        name: &str,
+        product: &Product<N>,
+        source: MultiDegreeElement<N>,
+        target: MultiDegreeElement<N>,
+    ) -> Result<bool, String> {
+        let jump = target.degree() - (source.degree() + product.b);
+        if P::filtration_jump(jump).is_none_or(|k| k <= 0) {
+            return Err(format!(
+                "{target} is not in higher filtration than the product of {source} with {name}"
+            ));
+        }
+        for elem in [&source, &target] {
+            if !self.nonzero_permanent(elem) {
+                return Err(format!("{elem} is not a non-zero permanent class"));
+            }
+        }
+        let Some(naive) = self.multiply(&source, product) else {
+            return Err(format!(
+                "The product of {source} with {name} is not yet computed"
+            ));
+        };
+        let naive_b = naive.degree();
+        let mut naive = naive.into_vec();
+        self.data[naive_b]
+            .page_data
+            .last()
+            .unwrap()
+            .reduce_by_quotient(naive.as_slice_mut());
+        if !naive.is_zero() {
+            return Err(format!(
+                "The product of {source} with {name} is non-zero, so the extension is not hidden"
+            ));
+        }
+
+        let extension = HiddenExtension {
+            product: name.to_owned(),
+            source,
+            target,
+        };
+        if self.hidden_extensions.contains(&extension) {
+            return Ok(false);
+        }
+        self.hidden_extensions.push(extension);
+        Ok(true)
+    }
+
+    pub fn hidden_extensions(&self) -> &[HiddenExtension<N>] {
+        &self.hidden_extensions
+    }
+
+    /// Propagate the hidden extensions along `products`, which are products by permanent classes.
+    /// If $a x = y$ is a hidden extension and $c$ is one of the products, then $a (cx) = \pm cy$,
+    /// and this is a hidden extension if $cx$ and $cy$ are non-zero on the last page. The sign is
+    /// the one from graded commutativity in the [total degree](SseqProfile::total_degree).
+    ///
+    /// The extensions are looked up in `products` by name, and those whose product is missing are
+    /// not propagated.
+    ///
+    /// # Return
+    ///
+    /// The number of new hidden extensions.
+    pub fn propagate_hidden_extensions(&mut self, products: &[(String, Product<N>)]) -> usize {
+        let mut count = 0;
+        let mut i = 0;
+        while i < self.hidden_extensions.len() {
+            let extension = self.hidden_extensions[i].clone();
+            i += 1;
+            let Some((name, a)) = products.iter().find(|(name, _)| *name == extension.product)
+            else {
+                continue;
+            };
+            for (_, c) in products {
+                let (Some(source), Some(target)) = (
+                    self.multiply(&extension.source, c),
+                    self.multiply(&extension.target, c),
+                ) else {
+                    continue;
+                };
+                let target = if (P::total_degree(a.b) * P::total_degree(c.b)) % 2 != 0 {
+                    let target_b = target.degree();
+                    let mut v = target.into_vec();
+                    v.scale(self.p - 1);
+                    MultiDegreeElement::new(target_b, v)
+                } else {
+                    target
+                };
+                // These are products of permanent classes, hence permanent.
+                self.add_permanent_class(&source);
+                self.add_permanent_class(&target);
+                if self.add_hidden_extension(name, a, source, target) == Ok(true) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
     /// Apply the Leibniz rule to obtain new differentials. The differential we start with is a d_r
     /// differential with source `s` and target `t`. If the source is permanent, then r
     /// should be set to [`i32::MAX`].
@@ -434,7 +735,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
             let d = MultiDegreeElement::new(d_b, dx);
             let target = self.multiply(&d, source_product)?;
 
-            if source_product.left && source_product.b.x() % 2 != 0 {
+            if source_product.left && P::total_degree(source_product.b) % 2 != 0 {
                 result.add(&target.into_vec(), neg_1);
             } else {
                 result.add(&target.into_vec(), 1);
@@ -445,7 +746,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
             let target = self.multiply(elem, target_product.unwrap())?;
             // why is this x - 1 but not x? This is what the original code does and came from trial
             // and error(?)
-            if !source_product.left && (elem.x() - 1) % 2 != 0 {
+            if !source_product.left && (P::total_degree(elem.degree()) - 1) % 2 != 0 {
                 result.add(&target.into_vec(), neg_1);
             } else {
                 result.add(&target.into_vec(), 1);
@@ -529,7 +830,7 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
                     let target_b = P::profile(r, b);
                     let shifted_target = target_b - min;
 
-                    if target_b.x() < 0 {
+                    if !self.defined(target_b) {
                         continue;
                     }
                     let d = self.differentials(b);
@@ -569,6 +870,35 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
                 }
             }
 
+            for extension in &self.hidden_extensions {
+                let source_b = extension.source.degree();
+                let target_b = extension.target.degree();
+                let source_data = self.page_data(source_b).get_max(r);
+                let target_data = self.page_data(target_b).get_max(r);
+                if source_data.is_empty() || target_data.is_empty() {
+                    continue;
+                }
+                let mut source = extension.source.vec().to_owned();
+                let mut target = extension.target.vec().to_owned();
+                let source = source_data.reduce(source.as_slice_mut());
+                let target = target_data.reduce(target.as_slice_mut());
+                for (i, &v) in source.iter().enumerate() {
+                    if v == 0 {
+                        continue;
+                    }
+                    for (j, &w) in target.iter().enumerate() {
+                        if w == 0 {
+                            continue;
+                        }
+                        g.extension(
+                            BidegreeGenerator::new(source_b - min, i),
+                            BidegreeGenerator::new(target_b - min, j),
+                            &extension.product,
+                        )?;
+                    }
+                }
+            }
+
             Ok(())
         })())
     }
@@ -584,6 +914,272 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
         self.try_write_to_graph(g, r, differentials, products, header)
             .expect("write_to_graph requires the minimum y-coordinate to be 0")
     }
+
+    /// Write the whole spectral sequence to `g`, as opposed to a single page as in
+    /// [`Self::write_to_graph`]. This draws the classes and products of the $E_{\mathrm{MIN\_R}}$
+    /// page, together with the differentials on every page, with style `d{r}` as usual. This is
+    /// meant for the [`SpectralSequencesBackend`](crate::charting::SpectralSequencesBackend), which
+    /// computes the later pages itself. The differentials of the environment are those of `P`, and
+    /// the environment is written once everything has been drawn.
+    ///
+    /// Classes are indexed by the basis of the $E_{\mathrm{MIN\_R}}$ page, and a differential
+    /// between $E_r$ classes is drawn from and to the basis vectors that are the pivots of these
+    /// classes. Each class is given the style `dying` if a differential is drawn from or to it,
+    /// and otherwise the style `permanent` if it is a permanent class that survives to
+    /// $E_\infty$. The class `g` is labelled with `label(g)`, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the minimum y-coordinate is not 0.
+    pub fn write_sseqdata<'a, W: std::io::Write, L: std::fmt::Display>(
+        &self,
+        mut g: crate::charting::SpectralSequencesBackend<W>,
+        products: impl Iterator<Item = &'a (String, Product<2>)>,
+        label: impl Fn(BidegreeGenerator) -> Option<L>,
+    ) -> std::io::Result<()> {
+        use crate::charting::Backend;
+
+        let min = self.min();
+        assert_eq!(
+            min.y(),
+            0,
+            "write_sseqdata requires the minimum y-coordinate to be 0"
+        );
+
+        g.set_profile::<P>();
+        g.init(self.max() - min)?;
+
+        let mut differentials = Vec::new();
+        for b in self.iter_degrees() {
+            for r in self.differentials(b).range() {
+                differentials.extend(
+                    self.pivot_differentials(b, r)
+                        .into_iter()
+                        .map(|(source, target)| (r, source, target)),
+                );
+            }
+        }
+        let dying: HashSet<BidegreeGenerator> = differentials
+            .iter()
+            .flat_map(|&(_, source, target)| [source, target])
+            .collect();
+
+        for b in self.iter_degrees() {
+            let dimension = self.dimension(b);
+            if dimension == 0 {
+                continue;
+            }
+            g.node(b - min, dimension)?;
+
+            let permanent = self.permanent_classes(b);
+            let last_page = self.page_data(b).last().unwrap();
+            for i in 0..dimension {
+                let style = if dying.contains(&BidegreeGenerator::new(b, i)) {
+                    Some("dying")
+                } else {
+                    let mut v = FpVector::new(self.p, dimension);
+                    v.set_entry(i, 1);
+                    let survives = permanent.contains(v.as_slice()) && {
+                        last_page.reduce_by_quotient(v.as_slice_mut());
+                        !v.is_zero()
+                    };
+                    survives.then_some("permanent")
+                };
+                if let Some(style) = style {
+                    g.class_style(BidegreeGenerator::new(b - min, i), style)?;
+                }
+                if let Some(label) = label(BidegreeGenerator::new(b, i)) {
+                    g.class_label(BidegreeGenerator::new(b - min, i), label);
+                }
+            }
+        }
+
+        for (name, prod) in products {
+            for b in self.iter_degrees() {
+                let target_b = b + prod.b;
+                if !self.defined(target_b) {
+                    continue;
+                }
+                if let Some(matrix) = prod.matrices.get(b) {
+                    let matrix = Subquotient::reduce_matrix(
+                        matrix,
+                        &self.page_data(b)[P::MIN_R],
+                        &self.page_data(target_b)[P::MIN_R],
+                    );
+                    g.structline_matrix(b - min, target_b - min, matrix, Some(name))?;
+                }
+            }
+        }
+
+        for (r, source, target) in differentials {
+            g.structline(
+                BidegreeGenerator::new(source.degree() - min, source.idx()),
+                BidegreeGenerator::new(target.degree() - min, target.idx()),
+                Some(&format!("d{r}")),
+            )?;
+        }
+
+        self.write_hidden_extensions(&mut g, min)?;
+
+        g.finish()
+    }
+
+    /// Write the pages $E_{\mathrm{MIN\_R}}, \ldots, E_\infty$, where $E_\infty$ is
+    /// [`Self::last_page`]. Page `r` is written to the backend returned by `backend(r)`, which is
+    /// only called once the previous page has been dropped, so that several pages can be written
+    /// to the same file. `header` is called on each page after the axes are drawn, e.g. to add
+    /// labels.
+    ///
+    /// Unlike [`Self::write_to_graph`], every page shows all the classes of the
+    /// $E_{\mathrm{MIN\_R}}$ page, so that classes stay in place from page to page. As in
+    /// [`Self::write_sseqdata`], a basis vector survives to $E_r$ if it is the pivot of a generator
+    /// of $E_r$, and the classes that do not survive are given the style `dead`, which backends draw
+    /// faded. Page `r` shows the products between surviving classes and the differentials $d_r$,
+    /// and $E_\infty$ also shows the hidden extensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the minimum y-coordinate is not 0.
+    pub fn write_pages<'a, T: crate::charting::Backend>(
+        &self,
+        mut backend: impl FnMut(i32) -> Result<T, T::Error>,
+        products: impl Iterator<Item = &'a (String, Product<2>)> + Clone,
+        mut header: impl FnMut(&mut T, i32) -> Result<(), T::Error>,
+    ) -> Result<(), T::Error> {
+        let min = self.min();
+        assert_eq!(
+            min.y(),
+            0,
+            "write_pages requires the minimum y-coordinate to be 0"
+        );
+        let last_page = self.last_page();
+
+        for r in P::MIN_R..=last_page {
+            let mut g = backend(r)?;
+            g.init(self.max() - min)?;
+            header(&mut g, r)?;
+
+            for b in self.iter_degrees() {
+                let dimension = self.dimension(b);
+                if dimension == 0 {
+                    continue;
+                }
+                g.node(b - min, dimension)?;
+
+                let alive = pivots(self.page_data(b).get_max(r));
+                for i in (0..dimension).filter(|i| !alive.contains(i)) {
+                    g.class_style(BidegreeGenerator::new(b - min, i), "dead")?;
+                }
+            }
+
+            for (name, prod) in products.clone() {
+                for b in self.iter_degrees() {
+                    let target_b = b + prod.b;
+                    if !self.defined(target_b) {
+                        continue;
+                    }
+                    let Some(matrix) = prod.matrices.get(b) else {
+                        continue;
+                    };
+                    let source_data = self.page_data(b).get_max(r);
+                    let target_data = self.page_data(target_b).get_max(r);
+                    let source_pivots = pivots(source_data);
+                    let target_pivots = pivots(target_data);
+                    let matrix = Subquotient::reduce_matrix(matrix, source_data, target_data);
+
+                    for (k, row) in matrix.into_iter().enumerate() {
+                        for (l, v) in row.into_iter().enumerate() {
+                            if v != 0 {
+                                g.structline(
+                                    BidegreeGenerator::new(b - min, source_pivots[k]),
+                                    BidegreeGenerator::new(target_b - min, target_pivots[l]),
+                                    Some(name),
+                                )?;
+                            }
+                        }
+                    }
+                }
+            }
+
+            for b in self.iter_degrees() {
+                for (source, target) in self.pivot_differentials(b, r) {
+                    g.structline(
+                        BidegreeGenerator::new(source.degree() - min, source.idx()),
+                        BidegreeGenerator::new(target.degree() - min, target.idx()),
+                        Some(&format!("d{r}")),
+                    )?;
+                }
+            }
+
+            if r == last_page {
+                self.write_hidden_extensions(&mut g, min)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The differentials $d_r$ from `b`, as pairs of basis vectors of the $E_{\mathrm{MIN\_R}}$
+    /// page. A differential between generators of $E_r$ becomes a differential between their
+    /// pivots.
+    fn pivot_differentials(
+        &self,
+        b: Bidegree,
+        r: i32,
+    ) -> Vec<(BidegreeGenerator, BidegreeGenerator)> {
+        let mut result = Vec::new();
+        let target_b = P::profile(r, b);
+        let Some(d) = self.differentials(b).get(r) else {
+            return result;
+        };
+        if !self.defined(target_b) {
+            return result;
+        }
+        let source_data = self.page_data(b).get_max(r);
+        let target_data = self.page_data(target_b).get_max(r);
+        let source_pivots = pivots(source_data);
+        let target_pivots = pivots(target_data);
+
+        for (mut s, mut t) in d.get_source_target_pairs() {
+            let source = source_data.reduce(s.as_slice_mut());
+            let target = target_data.reduce(t.as_slice_mut());
+            for (i, _) in source.iter().enumerate().filter(|(_, v)| **v != 0) {
+                for (j, _) in target.iter().enumerate().filter(|(_, v)| **v != 0) {
+                    result.push((
+                        BidegreeGenerator::new(b, source_pivots[i]),
+                        BidegreeGenerator::new(target_b, target_pivots[j]),
+                    ));
+                }
+            }
+        }
+        result
+    }
+
+    /// Draw the hidden extensions between basis vectors of the $E_{\mathrm{MIN\_R}}$ page.
+    fn write_hidden_extensions<T: crate::charting::Backend>(
+        &self,
+        g: &mut T,
+        min: Bidegree,
+    ) -> Result<(), T::Error> {
+        for extension in &self.hidden_extensions {
+            let source_b = extension.source.degree();
+            let target_b = extension.target.degree();
+            for (i, _) in extension.source.vec().iter_nonzero() {
+                for (j, _) in extension.target.vec().iter_nonzero() {
+                    g.extension(
+                        BidegreeGenerator::new(source_b - min, i),
+                        BidegreeGenerator::new(target_b - min, j),
+                        &extension.product,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The index of the basis vector that is the pivot of each generator of `page`.
+fn pivots(page: &Subquotient) -> Vec<usize> {
+    page.gens().map(|v| v.first_nonzero().unwrap().0).collect()
 }
 
 #[cfg(test)]
@@ -937,6 +1533,376 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hidden_extension() {
+        use crate::charting::SvgBackend;
+
+        let p = ValidPrime::new(2);
+        let element =
+            |x, y| BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(p, &[1]));
+        let product = |b, sources: &[(Bidegree, usize)]| {
+            let matrices = MultiIndexed::new();
+            for &(s, v) in sources {
+                matrices.insert(s, Matrix::from_vec(p, &[vec![v as u32]]));
+            }
+            Product {
+                b,
+                left: true,
+                matrices,
+            }
+        };
+
+        let mut sseq = Sseq::<2, Adams>::new(p);
+        for (x, y) in [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2), (1, 3)] {
+            sseq.set_dimension(Bidegree::x_y(x, y), 1);
+            sseq.add_permanent_class(&element(x, y));
+        }
+        // h0 x_{0, 0} = 0 in E_2, and h1 acts by the identity.
+        let h0 = product(
+            Bidegree::x_y(0, 1),
+            &[(Bidegree::x_y(0, 0), 0), (Bidegree::x_y(1, 1), 0)],
+        );
+        let h1 = product(
+            Bidegree::x_y(1, 1),
+            &[(Bidegree::x_y(0, 0), 1), (Bidegree::x_y(0, 2), 1)],
+        );
+
+        // The target must jump filtration.
+        assert!(
+            sseq.add_hidden_extension("h0", &h0, element(0, 0), element(0, 1))
+                .is_err()
+        );
+        // The product must vanish in E_infinity.
+        assert!(
+            sseq.add_hidden_extension("h1", &h1, element(0, 0), element(1, 2))
+                .is_err()
+        );
+        assert_eq!(
+            sseq.add_hidden_extension("h0", &h0, element(0, 0), element(0, 2)),
+            Ok(true)
+        );
+        assert_eq!(
+            sseq.add_hidden_extension("h0", &h0, element(0, 0), element(0, 2)),
+            Ok(false)
+        );
+
+        let products = [(String::from("h0"), h0), (String::from("h1"), h1)];
+        assert_eq!(sseq.propagate_hidden_extensions(&products), 1);
+        assert_eq!(
+            sseq.hidden_extensions()[1],
+            HiddenExtension {
+                product: String::from("h0"),
+                source: element(1, 1),
+                target: element(1, 3),
+            }
+        );
+
+        let mut out = Vec::new();
+        sseq.write_to_graph(SvgBackend::new(&mut out), 2, false, [].iter(), |_| Ok(()))
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches(r#"class="structline hidden h0""#).count(), 2);
+
+        sseq.clear();
+        assert!(sseq.hidden_extensions().is_empty());
+    }
+
+    #[test]
+    fn test_hidden_extension_serre() {
+        let p = ValidPrime::new(2);
+        let element =
+            |x, y| BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(p, &[1]));
+
+        // A class a in E_2^{0, 1} whose product with b in E_2^{1, 0} vanishes in E_2^{1, 1}.
+        let mut sseq = Sseq::<2, Serre>::new(p);
+        for (x, y) in [(0, 1), (1, 1), (2, 0), (1, 2)] {
+            sseq.set_dimension(Bidegree::x_y(x, y), 1);
+            sseq.add_permanent_class(&element(x, y));
+        }
+        let matrices = MultiIndexed::new();
+        matrices.insert(Bidegree::x_y(0, 1), Matrix::from_vec(p, &[vec![0]]));
+        let b = Product {
+            b: Bidegree::x_y(1, 0),
+            left: true,
+            matrices,
+        };
+
+        // The filtration is the x coordinate, so E_2^{1, 2} has the same filtration as ab
+        // but the wrong total degree
+        assert!(
+            sseq.add_hidden_extension("b", &b, element(0, 1), element(1, 2))
+                .is_err()
+        );
+        assert_eq!(
+            sseq.add_hidden_extension("b", &b, element(0, 1), element(2, 0)),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_write_sseqdata() {
+        use crate::charting::SpectralSequencesBackend;
+
+        let p = ValidPrime::new(2);
+        let element =
+            |x, y| BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(p, &[1]));
+
+        let mut sseq = Sseq::<2, Adams>::new(p);
+        for (x, y, dim) in [
+            (0, 0, 1),
+            (0, 1, 1),
+            (0, 2, 1),
+            (1, 0, 1),
+            (1, 1, 0),
+            (1, 2, 0),
+        ] {
+            sseq.set_dimension(Bidegree::x_y(x, y), dim);
+        }
+        sseq.add_permanent_class(&element(0, 0));
+        sseq.add_permanent_class(&element(0, 1));
+        sseq.add_differential(2, &element(1, 0), FpVector::from_slice(p, &[1]).as_slice());
+        sseq.update();
+
+        let matrices = MultiIndexed::new();
+        for y in 0..2 {
+            matrices.insert(Bidegree::x_y(0, y), Matrix::from_vec(p, &[vec![1]]));
+        }
+        let products = [(
+            String::from("h_0"),
+            Product {
+                b: Bidegree::x_y(0, 1),
+                left: true,
+                matrices,
+            },
+        )];
+
+        let mut out = Vec::new();
+        sseq.write_sseqdata(
+            SpectralSequencesBackend::new(&mut out, "S_2"),
+            products.iter(),
+            |g| (g == BidegreeGenerator::new(Bidegree::x_y(0, 1), 0)).then_some("h_0"),
+        )
+        .unwrap();
+        expect![[r#"
+            \sseqset{
+              permanent/.sseq style = { fill },
+              dying/.sseq style = { },
+              dead/.sseq style = { gray },
+              hidden/.sseq style = { red, dashed },
+              h0/.sseq style = { },
+            }
+            \begin{sseqdata}[name = S2, degree = {-1}{#1}, xrange = {0}{1}, yrange = {0}{2}]
+            \class[permanent](0,0)
+            \class[permanent, "h_0"](0,1)
+            \class[dying](0,2)
+            \class[dying](1,0)
+            \structline[h0](0,0,1)(0,1,1)
+            \structline[h0](0,1,1)(0,2,1)
+            \d2(1,0,1)(0,2,1)
+            \end{sseqdata}
+        "#]]
+        .assert_eq(&String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_write_sseqdata_serre() {
+        use crate::charting::SpectralSequencesBackend;
+
+        let p = ValidPrime::new(2);
+        let element =
+            |x, y| BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(p, &[1]));
+
+        // A transgression from E_2^{0, 1} to E_2^{2, 0}
+        let mut sseq = Sseq::<2, Serre>::new(p);
+        for x in 0..3 {
+            for y in 0..2 {
+                sseq.set_dimension(Bidegree::x_y(x, y), usize::from(x == 0 || y == 0));
+            }
+        }
+        sseq.add_permanent_class(&element(0, 0));
+        sseq.add_differential(2, &element(0, 1), FpVector::from_slice(p, &[1]).as_slice());
+        sseq.update();
+
+        let mut out = Vec::new();
+        sseq.write_sseqdata(
+            SpectralSequencesBackend::new(&mut out, "Serre"),
+            [].iter(),
+            |_| None::<&str>,
+        )
+        .unwrap();
+        expect![[r#"
+            \sseqset{
+              permanent/.sseq style = { fill },
+              dying/.sseq style = { },
+              dead/.sseq style = { gray },
+              hidden/.sseq style = { red, dashed },
+            }
+            \begin{sseqdata}[name = Serre, degree = {#1}{-#1+1}, xrange = {0}{2}, yrange = {0}{1}]
+            \class[permanent](0,0)
+            \class[dying](0,1)
+            \class(1,0)
+            \class[dying](2,0)
+            \d2(0,1,1)(2,0,1)
+            \end{sseqdata}
+        "#]]
+        .assert_eq(&String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_write_pages() {
+        use std::{cell::RefCell, rc::Rc};
+
+        use crate::charting::SeqSeeBackend;
+
+        /// Appends everything written to the last of a shared list of pages.
+        struct Page(Rc<RefCell<Vec<Vec<u8>>>>);
+
+        impl std::io::Write for Page {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.borrow_mut().last_mut().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let p = ValidPrime::new(2);
+        let element =
+            |x, y| BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(p, &[1]));
+
+        let mut sseq = Sseq::<2, Adams>::new(p);
+        for (x, y, dim) in [
+            (0, 0, 1),
+            (0, 1, 1),
+            (0, 2, 1),
+            (1, 0, 1),
+            (1, 1, 0),
+            (1, 2, 0),
+        ] {
+            sseq.set_dimension(Bidegree::x_y(x, y), dim);
+        }
+        sseq.add_differential(2, &element(1, 0), FpVector::from_slice(p, &[1]).as_slice());
+        sseq.update();
+        assert_eq!(sseq.last_page(), 3);
+
+        let matrices = MultiIndexed::new();
+        for y in 0..2 {
+            matrices.insert(Bidegree::x_y(0, y), Matrix::from_vec(p, &[vec![1]]));
+        }
+        let products = [(
+            String::from("h0"),
+            Product {
+                b: Bidegree::x_y(0, 1),
+                left: true,
+                matrices,
+            },
+        )];
+
+        let pages = Rc::new(RefCell::new(Vec::new()));
+        sseq.write_pages(
+            |_| {
+                pages.borrow_mut().push(Vec::new());
+                Ok(SeqSeeBackend::new(Page(Rc::clone(&pages))))
+            },
+            products.iter(),
+            |_, _| Ok(()),
+        )
+        .unwrap();
+
+        let pages: Vec<serde_json::Value> = pages
+            .borrow()
+            .iter()
+            .map(|page| serde_json::from_slice(page).unwrap())
+            .collect();
+        assert_eq!(pages.len(), 2);
+
+        // All classes are drawn on every page, and the dead ones are faded.
+        assert_eq!(pages[0]["nodes"].as_object().unwrap().len(), 4);
+        assert_eq!(pages[1]["nodes"].as_object().unwrap().len(), 4);
+        assert!(pages[0]["nodes"]["(1,0,0)"].get("attributes").is_none());
+        assert_eq!(
+            pages[1]["nodes"]["(1,0,0)"]["attributes"],
+            serde_json::json!(["dead"])
+        );
+        assert_eq!(
+            pages[1]["nodes"]["(0,2,0)"]["attributes"],
+            serde_json::json!(["dead"])
+        );
+
+        // The differential is only drawn on E_2, and products only between surviving classes.
+        let edge = |source, target, style| serde_json::json!({ "source": source, "target": target, "attributes": [style] });
+        assert_eq!(
+            pages[0]["edges"],
+            serde_json::json!([
+                edge("(0,0,0)", "(0,1,0)", "h0"),
+                edge("(0,1,0)", "(0,2,0)", "h0"),
+                edge("(1,0,0)", "(0,2,0)", "d2"),
+            ])
+        );
+        assert_eq!(
+            pages[1]["edges"],
+            serde_json::json!([edge("(0,0,0)", "(0,1,0)", "h0")])
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        fn check<P: SseqProfile<2>>() {
+            let b = Bidegree::x_y(3, 5);
+            for r in P::MIN_R..6 {
+                let target = P::profile(r, b);
+                assert_eq!(P::profile_inverse(r, target), b);
+                assert_eq!(P::differential_length(target - b), r);
+                // Differentials change the total degree by one.
+                assert_eq!((P::total_degree(target) - P::total_degree(b)).abs(), 1);
+            }
+        }
+        check::<Adams>();
+        check::<Serre>();
+        check::<AtiyahHirzebruch>();
+        check::<Cellular>();
+        check::<Bockstein>();
+    }
+
+    #[test]
+    fn test_serre_chart() {
+        use crate::charting::SeqSeeBackend;
+
+        // The Serre spectral sequence of the path-loop fibration ΩS^3 -> PS^3 -> S^3, in low
+        // degrees. The transgression d_3(x) = y kills everything.
+        let p = ValidPrime::new(2);
+        let mut sseq = Sseq::<2, Serre>::new(p);
+        for b in [(0, 0), (0, 2), (3, 0), (3, 2)] {
+            sseq.set_dimension(Bidegree::x_y(b.0, b.1), 1);
+        }
+        for b in [
+            (0, 1),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (3, 1),
+        ] {
+            sseq.set_dimension(Bidegree::x_y(b.0, b.1), 0);
+        }
+        let x = BidegreeElement::new(Bidegree::x_y(0, 2), FpVector::from_slice(p, &[1]));
+        sseq.add_differential(3, &x, FpVector::from_slice(p, &[1]).as_slice());
+        sseq.update();
+        assert!(sseq.page_data(Bidegree::x_y(3, 0))[4].is_empty());
+
+        let mut out = Vec::new();
+        sseq.write_to_graph(SeqSeeBackend::new(&mut out), 3, true, [].iter(), |_| Ok(()))
+            .unwrap();
+        let out: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            out["edges"],
+            serde_json::json!([{ "source": "(0,2,0)", "target": "(3,0,0)", "attributes": ["d3"] }])
+        );
+    }
+
     #[test]
     fn test_try_write_to_graph_precondition() {
         use crate::charting::TikzBackend;
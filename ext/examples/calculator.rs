@@ -0,0 +1,187 @@
+//! A calculator for the Steenrod algebra and its action on finite modules.
+//!
+//! After choosing a prime and optionally a module, this reads commands from stdin, one per line,
+//! until the end of the input. Commands can be typed interactively or supplied as a script, e.g.
+//! `cargo run --example calculator -- 2 Joker < script`. Empty lines and lines starting with `#`
+//! are ignored.
+//!
+//! Elements of the algebra are written as in the module json, e.g. `Sq2 * Sq2 + P(0, 1)` or `b *
+//! P1`. Here `Sq{n}`, `P{n}`, `b` and `Q{n}` are the usual generators and Milnor primitives,
+//! `P(r1, r2, ...)` and `M(r1 r2 ...)` are elements of the Milnor basis and `A(...)` is a product of
+//! Steenrod powers and Bocksteins. The commands are
+//!
+//!  - `<element>`: print the element in the current basis.
+//!  - `adem <element>`, `milnor <element>`, `pst <element>`, `arnona <element>`, `arnonc <element>`,
+//!    `wall <element>`: print the element in the given basis. `serre-cartan` is an alias for
+//!    `adem`. The $P^s_t$, Arnon and Wall bases are only available at the prime 2.
+//!  - `basis [name]`: print or set the current basis.
+//!  - `excess <element>`: print the excess of the element.
+//!  - `coproduct <element>`: print the coproduct of the element in the Milnor basis.
+//!  - `module <name>`: load a finite module, specified as in the other examples.
+//!  - `act <expression>`: evaluate an expression such as `Sq2 * x0 + Sq1 * x1` in the module.
+//!  - `help`: print a summary of the commands.
+//!
+//! # Sample output
+//! ```text
+//! Prime (default: 2): 2
+//! Module (optional): Joker
+//! > Sq2 * Sq2
+//! Sq3 Sq1
+//! > milnor Sq2 * Sq2
+//! P(1, 1)
+//! > pst Sq2 * Sq2
+//! P^0_1 P^0_2
+//! > coproduct Sq3
+//! 1 ⊗ P(3) + P(1) ⊗ P(2) + P(2) ⊗ P(1) + P(3) ⊗ 1
+//! > excess Sq4 + Sq3 * Sq1
+//! 2
+//! > act Sq2 * Sq1 * x0
+//! x3
+//! ```
+
+use std::{
+    io::{BufRead, IsTerminal, Write},
+    sync::Arc,
+};
+
+use algebra::{
+    Algebra, AlgebraType, SteenrodAlgebra,
+    module::{FDModule, Module, steenrod_module},
+    steenrod_evaluator::{Basis, SteenrodEvaluator},
+};
+use anyhow::{Context, anyhow};
+use fp::prime::{Prime, ValidPrime};
+
+const HELP: &str = "\
+<element>               print the element in the current basis
+adem <element>          print the element in the Adem (Serre-Cartan) basis
+milnor <element>        print the element in the Milnor basis
+pst <element>           print the element in the P^s_t basis
+arnona <element>        print the element in the Arnon A basis
+arnonc <element>        print the element in the Arnon C basis
+wall <element>          print the element in the Wall basis
+basis [name]            print or set the current basis (any of the above)
+excess <element>        print the excess of the element
+coproduct <element>     print the coproduct of the element in the Milnor basis
+module <name>           load a finite module
+act <expression>        evaluate an expression such as `Sq2 * x0 + Sq1 * x1` in the module
+help                    print this message";
+
+struct Calculator {
+    evaluator: SteenrodEvaluator,
+    basis: Basis,
+    module: Option<FDModule<SteenrodAlgebra>>,
+}
+
+impl Calculator {
+    fn load_module(&mut self, name: &str) -> anyhow::Result<()> {
+        let json = ext::utils::parse_module_name(name)?;
+        let p = self.evaluator.adem.prime();
+        if json["p"].as_u64() != Some(p.as_u32() as u64) {
+            return Err(anyhow!("The module must be over the prime {p}"));
+        }
+        let algebra = Arc::new(SteenrodAlgebra::from_json(
+            &json,
+            AlgebraType::Milnor,
+            false,
+        )?);
+        let module = steenrod_module::from_json(algebra, &json)?;
+        if module.max_degree().is_none() {
+            return Err(anyhow!("Module {module} is not finite dimensional"));
+        }
+        self.module = Some(FDModule::from(&module));
+        Ok(())
+    }
+
+    fn evaluate(&self, basis: Basis, input: &str) -> anyhow::Result<String> {
+        let (degree, result) = self.evaluator.evaluate_algebra_adem(input)?;
+        self.evaluator.element_to_string(basis, degree, &result)
+    }
+
+    fn run(&mut self, line: &str) -> anyhow::Result<String> {
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        if let Ok(basis) = command.parse() {
+            return self.evaluate(basis, rest);
+        }
+        match command {
+            "help" => Ok(HELP.to_string()),
+            "basis" if rest.is_empty() => Ok(self.basis.to_string()),
+            "basis" => {
+                let basis = rest.parse()?;
+                if let Basis::Alternative(basis) = basis {
+                    self.evaluator.alternative(basis)?;
+                }
+                self.basis = basis;
+                Ok(format!("Using the {} basis", self.basis))
+            }
+            "excess" => {
+                let (degree, result) = self.evaluator.evaluate_algebra_adem(rest)?;
+                Ok(match self.evaluator.excess(degree, &result) {
+                    Some(excess) => excess.to_string(),
+                    None => "The element is zero".to_string(),
+                })
+            }
+            "coproduct" => {
+                let (degree, result) = self.evaluator.evaluate_algebra_adem(rest)?;
+                let coproduct = self.evaluator.coproduct(degree, &result);
+                Ok(self.evaluator.coproduct_to_string(&coproduct))
+            }
+            "module" => {
+                self.load_module(rest)?;
+                Ok(format!("Loaded {rest}"))
+            }
+            "act" => {
+                let module = self
+                    .module
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("No module loaded. Use `module <name>` first"))?;
+                let (degree, result) = self.evaluator.evaluate_module_action(module, rest)?;
+                Ok(module.element_to_string(degree, result.as_slice()))
+            }
+            _ => self.evaluate(self.basis, line),
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let p: ValidPrime = query::with_default("Prime", "2", str::parse);
+    let module: Option<String> = query::optional("Module", |x| Ok::<_, String>(x.to_string()));
+
+    let mut calculator = Calculator {
+        evaluator: SteenrodEvaluator::new(p),
+        basis: Basis::Adem,
+        module: None,
+    };
+    if let Some(module) = module {
+        calculator
+            .load_module(&module)
+            .with_context(|| format!("Failed to load module {module}"))?;
+    }
+
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    let mut stdout = std::io::stdout();
+    let prompt = || {
+        if interactive {
+            eprint!("> ");
+            std::io::stderr().flush().unwrap();
+        }
+    };
+
+    prompt();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            match calculator.run(line) {
+                Ok(output) => writeln!(stdout, "{output}")?,
+                Err(e) => eprintln!("Error: {e:#}"),
+            }
+        }
+        prompt();
+    }
+    Ok(())
+}
@@ -1,20 +1,52 @@
+//! Draws the $E_2$ page of the Adams spectral sequence of a module.
+//!
+//! The `sseqdata` format is a `sseqdata` environment of the `spectralsequences` LaTeX package,
+//! which can be drawn with `\printpage[name = ...]`.
+//!
+//! Classes can optionally be labelled with their names, as computed by
+//! [`ext::ext_algebra::names`]. Answer `standard` to use the standard names of the indecomposables
+//! of $\Ext$, or give a file of definitions in the format described there.
+
+use std::sync::Arc;
+
 use algebra::Algebra;
+use anyhow::Context;
 use ext::{
     chain_complex::{ChainComplex, FreeChainComplex},
+    ext_algebra::{
+        ExtAlgebra,
+        names::{ClassNamer, ClassNames},
+    },
     utils::query_module,
 };
-use sseq::charting::{SeqSeeBackend, SvgBackend, TikzBackend};
+use sseq::{
+    charting::{Backend, SeqSeeBackend, SpectralSequencesBackend, SvgBackend, TikzBackend},
+    coordinates::Bidegree,
+};
 
 fn main() -> anyhow::Result<()> {
     ext::utils::init_logging()?;
 
-    let resolution = query_module(None, false)?;
+    let resolution = query_module(None, true)?;
 
-    let format = query::with_default("Output format (svg/tikz/seqsee)", "svg", |x| match x {
-        "svg" | "tikz" | "seqsee" => Ok(x.to_string()),
-        _ => Err(format!(
-            "unknown format '{x}'; expected one of svg, tikz, seqsee"
-        )),
+    let format = query::with_default(
+        "Output format (svg/tikz/seqsee/sseqdata)",
+        "svg",
+        |x| match x {
+            "svg" | "tikz" | "seqsee" | "sseqdata" => Ok(x.to_string()),
+            _ => Err(format!(
+                "unknown format '{x}'; expected one of svg, tikz, seqsee, sseqdata"
+            )),
+        },
+    );
+
+    let namer = query::optional("Class names (standard or a file of definitions)", |x| {
+        if x == "standard" {
+            return Ok(ClassNamer::standard(resolution.prime()));
+        }
+        std::fs::read_to_string(x)
+            .with_context(|| format!("Failed to read {x}"))?
+            .parse::<ClassNamer>()
     });
 
     let sseq = resolution.to_sseq();
@@ -25,20 +57,51 @@ fn main() -> anyhow::Result<()> {
         .map(|(name, op_deg, op_idx)| (name, resolution.filtration_one_products(op_deg, op_idx)))
         .collect();
 
+    let min = Bidegree::n_s(resolution.min_degree(), 0);
+    let name = resolution.name().to_owned();
+    let names = match namer {
+        Some(namer) => {
+            let ext = ExtAlgebra::from_resolution(Arc::new(resolution))?;
+            let names = namer.name_through_stem(&ext, Bidegree::n_s(i32::MAX, i32::MAX))?;
+            for warning in names.warnings() {
+                eprintln!("Warning: {warning}");
+            }
+            for (g, names) in names.ambiguous() {
+                eprintln!("Ambiguous name for {g}: {}", names.join(" = "));
+            }
+            for g in names.undecomposable() {
+                eprintln!("No name for {g}");
+            }
+            Some(names)
+        }
+        None => None,
+    };
     let out = std::io::stdout();
     match format.as_str() {
-        "svg" => {
-            sseq.write_to_graph(SvgBackend::new(out), 2, false, products.iter(), |_| Ok(()))?
-        }
-        "tikz" => {
-            sseq.write_to_graph(TikzBackend::new(out), 2, false, products.iter(), |_| Ok(()))?
-        }
+        "svg" => sseq.write_to_graph(SvgBackend::new(out), 2, false, products.iter(), |g| {
+            label(g, names.as_ref(), min)
+        })?,
+        "tikz" => sseq.write_to_graph(TikzBackend::new(out), 2, false, products.iter(), |g| {
+            label(g, names.as_ref(), min)
+        })?,
         "seqsee" => {
-            sseq.write_to_graph(SeqSeeBackend::new(out), 2, false, products.iter(), |_| {
-                Ok(())
+            sseq.write_to_graph(SeqSeeBackend::new(out), 2, false, products.iter(), |g| {
+                label(g, names.as_ref(), min)
             })?
         }
+        "sseqdata" => sseq.write_sseqdata(
+            SpectralSequencesBackend::new(out, &name),
+            products.iter(),
+            |g| names.as_ref().and_then(|names| names.name(g)),
+        )?,
         _ => unreachable!(),
     }
     Ok(())
 }
+
+fn label<B: Backend>(g: &mut B, names: Option<&ClassNames>, min: Bidegree) -> Result<(), B::Error> {
+    match names {
+        Some(names) => names.write_labels(g, min),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,95 @@
+//! Computes Ext of a finite module with the cobar complex of the dual comodule, and compares the
+//! result with a minimal resolution.
+//!
+//! The input is either a finite dimensional module $M$, in which case we use the comodule $M^*$,
+//! or a comodule in the json format described in [`algebra::comodule`]. The cobar complex grows
+//! quickly, so this is only practical in low degrees. This prints the dimensions of the Ext groups
+//! in the same format as `resolve_through_stem`, and then whether they agree with the resolution.
+//!
+//! # Sample output
+//! ```text
+//! Module (default: S_2): Joker
+//! Max n (default: 8): 8
+//! Max s (default: 3): 3
+//!     ·         · ·
+//!     ·     · · · ·
+//!     · ·   · · ·
+//! ·
+//! The cobar complex agrees with the resolution
+//! ```
+
+use std::sync::Arc;
+
+use algebra::{AlgebraType, SteenrodAlgebra, comodule::Comodule, module::FDModule};
+use anyhow::anyhow;
+use ext::{
+    chain_complex::{FreeChainComplex, cobar_complex, cobar_homology_dimension},
+    utils::unicode_num,
+};
+use sseq::coordinates::Bidegree;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let json = query::with_default("Module", "S_2", ext::utils::parse_module_name);
+    let max_n: i32 = query::with_default("Max n", "8", str::parse);
+    let max_s: u32 = query::with_default("Max s", "3", str::parse);
+
+    let algebra = Arc::new(SteenrodAlgebra::from_json(
+        &json,
+        AlgebraType::Milnor,
+        false,
+    )?);
+    let comodule = match json["type"].as_str() {
+        Some("comodule") => Comodule::from_json(algebra, &json)?,
+        Some("finite dimensional module") => {
+            Comodule::from_dual(FDModule::from_json(algebra, &json)?)?
+        }
+        _ => {
+            return Err(anyhow!(
+                "Expected a comodule or a finite dimensional module, but the module has type {}",
+                json["type"]
+            ));
+        }
+    };
+
+    let min_degree = comodule.min_degree();
+    let max_t = min_degree + max_n + max_s as i32;
+    let cobar = cobar_complex(&comodule, max_s, max_t);
+
+    // The resolution needs the dual module in the format of a finite dimensional module.
+    let mut module_json = json.clone();
+    comodule.dual().to_json(&mut module_json);
+    module_json.as_object_mut().unwrap().remove("coactions");
+    let resolution = ext::utils::construct((module_json, "milnor"), None)?;
+    resolution.compute_through_stem(Bidegree::n_s(min_degree + max_n, max_s as i32));
+
+    let mut mismatches = Vec::new();
+    for s in (0..=max_s as i32).rev() {
+        let mut line = String::new();
+        for n in min_degree..=min_degree + max_n {
+            let b = Bidegree::n_s(n, s);
+            let dim = cobar_homology_dimension(&cobar, b);
+            if dim != resolution.number_of_gens_in_bidegree(b) {
+                mismatches.push(b);
+            }
+            line.push(unicode_num(dim));
+            line.push(' ');
+        }
+        println!("{}", line.trim_end());
+    }
+
+    if mismatches.is_empty() {
+        println!("The cobar complex agrees with the resolution");
+    } else {
+        println!(
+            "The cobar complex disagrees with the resolution at {}",
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(())
+}
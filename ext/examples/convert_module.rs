@@ -0,0 +1,83 @@
+//! Converts a module to a finite dimensional module or to a finitely presented module, and prints
+//! the result as json.
+//!
+//! - `fd` writes the part of the module in a range of degrees as a finite dimensional module.
+//!   This works for any module, e.g. one given by generators and relations.
+//! - `fp` writes a minimal presentation of a finite module. A finite module usually needs
+//!   relations in infinitely many degrees, so we only include the relations up to a given
+//!   degree. The result agrees with the original module up to that degree, which suffices for
+//!   resolving through the corresponding internal degree.
+//!
+//! # Sample output
+//! ```text
+//! Module (default: S_2): Joker
+//! Output type (fd or fp): fp
+//! Max degree of relations: 12
+//! {"p":2,"type":"finitely presented module","gens":{"x0":0},"milnor_relations":["P(3) x0","P(4) x0","P(0, 2) x0","P(0, 0, 1) x0","P(8) x0"],"adem_relations":["Sq3 x0","Sq4 x0","Sq4 Sq2 x0","Sq4 Sq2 Sq1 x0","Sq8 x0"]}
+//! ```
+
+use std::sync::Arc;
+
+use algebra::{
+    AlgebraType, SteenrodAlgebra,
+    module::{FDModule, Module, steenrod_module},
+};
+use anyhow::anyhow;
+use serde_json::{Value, json};
+
+fn load(
+    json: &Value,
+    algebra_type: AlgebraType,
+) -> anyhow::Result<steenrod_module::SteenrodModule> {
+    let algebra = Arc::new(SteenrodAlgebra::from_json(json, algebra_type, false)?);
+    steenrod_module::from_json(algebra, json)
+}
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let json = query::with_default("Module", "S_2", ext::utils::parse_module_name);
+    let fd = query::raw("Output type (fd or fp)", |x| match x {
+        "fd" => Ok(true),
+        "fp" => Ok(false),
+        _ => Err(format!(
+            "unrecognized output type '{x}'. Should be 'fd' or 'fp'"
+        )),
+    });
+
+    let mut output = json!({ "p": json["p"] });
+    for key in ["algebra", "profile"] {
+        if !json[key].is_null() {
+            output[key] = json[key].clone();
+        }
+    }
+
+    let module = load(&json, AlgebraType::Milnor)?;
+    if fd {
+        let min_degree =
+            query::with_default("Min degree", &module.min_degree().to_string(), str::parse);
+        let max_degree = query::raw("Max degree", str::parse);
+        FDModule::truncation(&module, min_degree, max_degree).to_json(&mut output);
+    } else {
+        let max_degree = query::raw("Max degree of relations", str::parse);
+        let Some(top) = module.max_degree() else {
+            return Err(anyhow!("Module {module} is not finite dimensional"));
+        };
+        // The relations are written in the basis of the algebra, so we compute the presentation
+        // once for each basis.
+        let mut adem_output = json!({});
+        for (algebra_type, output) in [
+            (AlgebraType::Milnor, &mut output),
+            (AlgebraType::Adem, &mut adem_output),
+        ] {
+            let module = load(&json, algebra_type)?;
+            FDModule::truncation(&module, module.min_degree(), top)
+                .minimal_presentation(max_degree)
+                .to_json(output);
+        }
+        output["adem_relations"] = adem_output["adem_relations"].take();
+    }
+
+    println!("{output}");
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Resolve a module up to a fixed $(n, s)$ using multiple processes that share a save directory.
+//!
+//! One process is started as the coordinator and the others as workers. The coordinator listens at
+//! the given address, which is either `tcp:host:port` or `unix:path`, and assigns bidegrees to the
+//! workers that connect to it. All processes must be given the same module and save directory.
+//! When the computation finishes, the coordinator loads the resolution from the save directory and
+//! prints the Ext groups as in [`resolve_through_stem`](../resolve_through_stem/index.html).
+//!
+//! If a worker is killed, the bidegree it was working on is handed to a different worker. If the
+//! coordinator is killed, rerunning it with the same save directory resumes the computation.
+//!
+//! See [`ext::distributed`] for more details.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, anyhow};
+use ext::{
+    chain_complex::FreeChainComplex,
+    distributed::{Address, Coordinator, run_worker},
+    utils::construct_standard,
+};
+use sseq::coordinates::Bidegree;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let is_coordinator = query::with_default("Role", "coordinator", |s| match s {
+        "coordinator" => Ok(true),
+        "worker" => Ok(false),
+        _ => Err(anyhow!("Role must be either coordinator or worker")),
+    });
+    let address: Address = query::with_default("Address", "tcp:127.0.0.1:5555", str::parse);
+
+    let module: String = query::with_default("Module", "S_2", str::parse);
+    let save_dir: PathBuf = query::raw("Module save directory", str::parse);
+    let res = construct_standard::<false, _, _>(module.as_str(), Some(save_dir))
+        .context("Failed to construct resolution")?;
+
+    if !is_coordinator {
+        return run_worker(&res, &address);
+    }
+
+    let max = Bidegree::n_s(
+        query::with_default("Max n", "30", str::parse),
+        query::with_default("Max s", "15", str::parse),
+    );
+
+    Coordinator::bind(&address)?.run(&res, max)?;
+
+    res.compute_through_stem(max);
+    println!("{}", res.graded_dimension_string());
+
+    Ok(())
+}
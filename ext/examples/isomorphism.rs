@@ -0,0 +1,98 @@
+//! Determines whether two finite modules are isomorphic.
+//!
+//! If they are, this prints an isomorphism by listing the image of each basis element of the
+//! first module. Otherwise, it prints the first invariant that tells them apart, such as the rank
+//! of the action of a Steenrod operation in some degree. The two modules must be defined over the
+//! same algebra.
+//!
+//! Optionally, this also writes the first module to a file in a normal form, in which the basis
+//! elements are named after their degrees and the actions are listed in order.
+//!
+//! # Sample output
+//! ```text
+//! Source module (default: S_2): C2 ^ Ceta
+//! Target module (default: S_2): Ceta ^ C2
+//! Normal form output (optional):
+//! The modules are isomorphic:
+//!   x0.x0 -> x0.x0
+//!   x1.x0 -> x0.x1
+//!   x0.x2 -> x2.x0
+//!   x1.x2 -> x2.x1
+//! ```
+
+use std::{path::PathBuf, sync::Arc};
+
+use algebra::{
+    AlgebraType, SteenrodAlgebra,
+    module::{
+        FDModule, Module,
+        isomorphism::{Isomorphism, canonical_json, find_isomorphism},
+        steenrod_module,
+    },
+};
+use anyhow::anyhow;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let source_json = query::with_default("Source module", "S_2", ext::utils::parse_module_name);
+    let target_json = query::with_default("Target module", "S_2", ext::utils::parse_module_name);
+    let output: Option<PathBuf> = query::optional("Normal form output", str::parse);
+
+    for key in ["p", "profile"] {
+        if source_json[key] != target_json[key] {
+            return Err(anyhow!(
+                "Modules must have the same {key}, but they are {} and {}",
+                source_json[key],
+                target_json[key]
+            ));
+        }
+    }
+
+    let algebra = Arc::new(SteenrodAlgebra::from_json(
+        &source_json,
+        AlgebraType::Milnor,
+        false,
+    )?);
+    let to_fd_module = |json| -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+        let module = steenrod_module::from_json(Arc::clone(&algebra), json)?;
+        if module.max_degree().is_none() {
+            return Err(anyhow!("Module {module} is not finite dimensional"));
+        }
+        Ok(FDModule::from(&module))
+    };
+    let source = to_fd_module(&source_json)?;
+    let target = to_fd_module(&target_json)?;
+
+    if let Some(output) = output {
+        let mut json = canonical_json(&source);
+        for key in ["algebra", "profile"] {
+            if !source_json[key].is_null() {
+                json[key] = source_json[key].clone();
+            }
+        }
+        std::fs::write(output, serde_json::to_string_pretty(&json)?)?;
+    }
+
+    match find_isomorphism(&source, &target)? {
+        Isomorphism::Found(map) => {
+            println!("The modules are isomorphic:");
+            for (degree, matrix) in map.iter_enum() {
+                for (i, row) in matrix.iter().enumerate() {
+                    println!(
+                        "  {} -> {}",
+                        source.basis_element_to_string(degree, i),
+                        target.element_to_string(degree, row)
+                    );
+                }
+            }
+        }
+        Isomorphism::Obstructed(obstruction) => {
+            println!("The modules are not isomorphic: {obstruction}");
+        }
+        Isomorphism::Unknown => {
+            println!("No isomorphism was found, but the modules could not be told apart");
+        }
+    }
+    Ok(())
+}
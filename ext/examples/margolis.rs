@@ -0,0 +1,76 @@
+//! Computes the Margolis homology of a finite module with respect to the Milnor primitives $Q_i$
+//! and the elements $P^s_t$ of the Milnor basis.
+//!
+//! The operators are given as a comma separated list such as `Q0, Q1, P^0_2`. For each of them,
+//! this prints the dimension of the Margolis homology in each degree where it is non-zero. If the
+//! list contains $Q_0, \ldots, Q_n$, this also reports whether the module is free over $E(n)$.
+//!
+//! # Sample output
+//! ```text
+//! Module (default: S_2): Joker
+//! Operators (default: Q0, Q1): Q0, Q1, P^1_2
+//! H(M; Q_0):
+//!   degree 2: 1
+//! H(M; Q_1):
+//!   degree 2: 1
+//! H(M; P^1_2):
+//!   degree 0: 1
+//!   degree 1: 1
+//!   degree 2: 1
+//!   degree 3: 1
+//!   degree 4: 1
+//! The module is not free over E(1)
+//! ```
+
+use std::sync::Arc;
+
+use algebra::{
+    AlgebraType, SteenrodAlgebra,
+    module::{
+        margolis::{MargolisOperator, margolis_homology},
+        steenrod_module,
+    },
+};
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let json = query::with_default("Module", "S_2", ext::utils::parse_module_name);
+    let algebra = Arc::new(SteenrodAlgebra::from_json(
+        &json,
+        AlgebraType::Milnor,
+        false,
+    )?);
+    let module = steenrod_module::from_json(algebra, &json)?;
+
+    let operators: Vec<MargolisOperator> = query::with_default("Operators", "Q0, Q1", |s| {
+        s.split(',').map(str::parse).collect::<anyhow::Result<_>>()
+    });
+
+    let mut vanishing = Vec::new();
+    for &op in &operators {
+        let homology = margolis_homology(&module, op)?;
+        println!("H(M; {op}):");
+        for (degree, &dim) in homology.iter_enum() {
+            if dim > 0 {
+                println!("  degree {degree}: {dim}");
+            }
+        }
+        if homology.iter().all(|&dim| dim == 0) {
+            vanishing.push(op);
+        }
+    }
+
+    // The largest n such that Q_0, ..., Q_n are all in the list
+    let n = (0..)
+        .take_while(|&i| operators.contains(&MargolisOperator::Q(i)))
+        .last();
+    if let Some(n) = n {
+        if (0..=n).all(|i| vanishing.contains(&MargolisOperator::Q(i))) {
+            println!("The module is free over E({n})");
+        } else {
+            println!("The module is not free over E({n})");
+        }
+    }
+    Ok(())
+}
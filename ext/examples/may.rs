@@ -0,0 +1,63 @@
+//! Computes the May spectral sequence converging to $\Ext(\F_2, \F_2)$, and names the generators
+//! of $\Ext$ by the classes in $E_\infty$ detecting them. See [`ext::may`] for conventions.
+//!
+//! This first prints the nonzero differentials, and then every generator of $\Ext$ followed by the
+//! class detecting it. The computation uses the cobar complex, so this is only practical in low
+//! degrees.
+//!
+//! # Sample output
+//! ```text
+//! Max n (default: 8): 8
+//! Max s (default: 3): 3
+//! d_1(h_{20}) = h_{10} h_{11}
+//! d_1(h_{21}) = h_{11} h_{12}
+//! d_1(h_{30}) = h_{10} h_{21} + h_{12} h_{20}
+//! d_1(h_{10} h_{20}) = h_{10}^2 h_{11}
+//! d_1(h_{11} h_{20}) = h_{10} h_{11}^2
+//! d_2(h_{20}^2) = h_{10}^2 h_{12} + h_{11}^3
+//! ...
+//! (8, 3, 0): h_{11}^2 h_{30} + h_{11} h_{20} h_{21}
+//! ```
+
+use ext::{chain_complex::ChainComplex, may::MaySpectralSequence};
+use sseq::{SseqProfile, coordinates::Bidegree};
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let max_n: i32 = query::with_default("Max n", "8", str::parse);
+    let max_s: i32 = query::with_default("Max s", "3", str::parse);
+    let max = Bidegree::s_t(max_s, max_n + max_s);
+
+    let may = MaySpectralSequence::new(max);
+    let sseq = may.sseq();
+
+    let mut degrees = sseq
+        .iter_degrees()
+        .filter(|b| b.s() <= max_s && b.n() <= max_n)
+        .collect::<Vec<_>>();
+    degrees.sort_by_key(|b| (b.s(), b.n(), b.coords()[2]));
+    for &b in &degrees {
+        for (r, d) in sseq.differentials(b).iter_enum() {
+            let target_b = sseq::May::profile(r, b);
+            for (source, target) in d.get_source_target_pairs() {
+                if !target.is_zero() {
+                    println!(
+                        "d_{r}({}) = {}",
+                        may.element_to_string(b, source.as_slice()),
+                        may.element_to_string(target_b, target.as_slice())
+                    );
+                }
+            }
+        }
+    }
+
+    let resolution = ext::utils::construct("S_2@milnor", None)?;
+    resolution.compute_through_bidegree(max);
+    for (g, elem) in may.detect_generators(&resolution)? {
+        if g.n() <= max_n {
+            println!("{g}: {}", may.element_to_string(elem.degree(), elem.vec()));
+        }
+    }
+    Ok(())
+}
@@ -18,8 +18,11 @@
 //! · ·   ·       ·               ·
 //! ·
 //! ```
+//!
+//! If a save directory is given, pressing Ctrl-C during the computation stops it gracefully,
+//! and rerunning the example with the same save directory resumes it. See [`ext::checkpoint`].
 
-use ext::chain_complex::FreeChainComplex;
+use ext::{chain_complex::FreeChainComplex, checkpoint::CancellationToken};
 use sseq::coordinates::Bidegree;
 
 fn main() -> anyhow::Result<()> {
@@ -32,7 +35,7 @@ fn main() -> anyhow::Result<()> {
         query::with_default("Max s", "15", str::parse),
     );
 
-    res.compute_through_stem(max);
+    res.compute_through_stem_with_cancel(max, &CancellationToken::ctrlc())?;
 
     println!("{}", res.graded_dimension_string());
 
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use algebra::module::Module;
+use algebra::{SteenrodAlgebra, module::Module};
 use double::DoubleChainComplex;
 use ext::{
     chain_complex::{AugmentedChainComplex, BoundedChainComplex, ChainComplex, FreeChainComplex},
@@ -14,11 +14,19 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator};
 fn main() -> anyhow::Result<()> {
     ext::utils::init_logging()?;
 
-    let res = utils::query_module(None, true)?;
+    let res = utils::query_module_only("Module", None, true)?;
     assert!(
         res.prime() == 2 && res.target().max_s() == 1 && res.target().module(0).is_unit(),
         "Sq^0 can only be computed for the sphere at the prime 2"
     );
+    anyhow::ensure!(
+        !matches!(*res.algebra(), SteenrodAlgebra::ChangeOfBasisAlgebra(_)),
+        "Sq^0 is only implemented in the Adem and Milnor bases"
+    );
+    res.compute_through_stem(Bidegree::n_s(
+        query::with_default("Max n", "30", str::parse),
+        query::with_default("Max s", "7", str::parse),
+    ));
 
     let res = Arc::new(res);
     let doubled = Arc::new(DoubleChainComplex::new(Arc::clone(&res)));
@@ -136,6 +144,8 @@ mod double {
                 match self {
                     SteenrodAlgebra::AdemAlgebra(a) => a.halve(degree, idx),
                     SteenrodAlgebra::MilnorAlgebra(a) => a.halve(degree, idx),
+                    // This is rejected in main
+                    SteenrodAlgebra::ChangeOfBasisAlgebra(_) => unreachable!(),
                 }
             }
         }
@@ -0,0 +1,93 @@
+//! Checks that the json of a finite dimensional module defines a module over the Steenrod
+//! algebra.
+//!
+//! Only the actions of the generators of the algebra, i.e. $Sq^{2^i}$ at the prime 2 and
+//! $\beta$ and $P^{p^i}$ at odd primes, have to be specified. The actions of the other elements
+//! are computed from them, and any that are specified are checked against the computed ones. We
+//! then check every relation of the algebra, as well as $a(bm) = (ab)m$ for all basis elements
+//! $a$, $b$ and $m$, both in the Adem basis and in the Milnor basis. The first failure is reported
+//! together with the element it fails on.
+//!
+//! Optionally, this prints the action of every element of the Adem basis, including those implied
+//! by the actions of the generators.
+//!
+//! # Sample output
+//! ```text
+//! Module (default: S_2): Joker
+//! Print all actions (default: n): y
+//! The module is valid
+//! Sq1 x0 = x1
+//! Sq2 x0 = x2
+//! Sq2 Sq1 x0 = x3
+//! Sq3 Sq1 x0 = x4
+//! Sq2 x1 = x3
+//! Sq3 x1 = x4
+//! Sq2 x2 = x4
+//! Sq1 x3 = x4
+//! ```
+
+use std::sync::Arc;
+
+use algebra::{
+    Algebra, AlgebraType, SteenrodAlgebra,
+    module::{FDModule, Module},
+};
+use anyhow::{Context, anyhow};
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let json = query::with_default("Module", "S_2", ext::utils::parse_module_name);
+    let print_actions = query::with_default("Print all actions", "n", |x| match x {
+        "y" | "yes" => Ok(true),
+        "n" | "no" => Ok(false),
+        _ => Err(format!(
+            "unrecognized response '{x}'. Should be '(y)es' or '(n)o'"
+        )),
+    });
+
+    if json["type"] != "finite dimensional module" {
+        return Err(anyhow!(
+            "Only finite dimensional modules can be validated, but the module has type {}",
+            json["type"]
+        ));
+    }
+
+    let mut modules = Vec::new();
+    for algebra_type in [AlgebraType::Adem, AlgebraType::Milnor] {
+        let algebra = Arc::new(SteenrodAlgebra::from_json(&json, algebra_type, false)?);
+        let module = FDModule::from_json(algebra, &json)
+            .with_context(|| format!("Invalid module over the {algebra_type} algebra"))?;
+        module
+            .check_products()
+            .with_context(|| format!("Invalid module over the {algebra_type} algebra"))?;
+        modules.push(module);
+    }
+    println!("The module is valid");
+
+    if print_actions {
+        let module = &modules[0];
+        let algebra = module.algebra();
+        let min_degree = module.min_degree();
+        let max_degree = module.max_degree().unwrap_or(min_degree - 1);
+        for input_deg in min_degree..=max_degree {
+            for input_idx in 0..module.dimension(input_deg) {
+                for output_deg in input_deg + 1..=max_degree {
+                    let op_deg = output_deg - input_deg;
+                    for op_idx in 0..algebra.dimension(op_deg) {
+                        let value = module.action(op_deg, op_idx, input_deg, input_idx);
+                        if !value.is_zero() {
+                            println!(
+                                "{} {} = {}",
+                                algebra.basis_element_to_string(op_deg, op_idx),
+                                module.basis_element_to_string(input_deg, input_idx),
+                                module.element_to_string(output_deg, value.as_slice())
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,625 @@
+//! Import and export of resolutions in Bruner's format.
+//!
+//! Bruner's programs store a minimal resolution of a module $M$ over the mod 2 Steenrod algebra as
+//! a sequence of files `hDiff.0`, `hDiff.1`, ..., where `hDiff.s` lists the generators of $C_s$
+//! and their differentials. The format is described in <https://arxiv.org/abs/2109.13117>, and
+//! Bruner's resolutions can be found at
+//! <https://archive.sigma2.no/pages/public/datasetDetail.jsf?id=10.11582/2022.00015>.
+//!
+//! Each file starts with the number of generators and the maximum internal degree computed. Each
+//! generator is then listed in increasing order of degree by a block of the form
+//! ```text
+//! $t
+//! $num_terms
+//! $gen_idx $op_deg $op_dim i($p_part)($p_part)...($p_part).
+//! ...
+//! ```
+//! Here each term is an algebra element in the Milnor basis, written as a sum of the listed
+//! Milnor basis elements, acting on the `gen_idx`th generator of $C_{s - 1}$. The generators are
+//! numbered in order of degree, starting from 0. The field `op_dim` is the dimension of the
+//! algebra in degree `op_deg` and is ignored when reading.
+//!
+//! In `hDiff.0`, the terms describe the image of the generator under the augmentation $C_0 \to M$,
+//! and `gen_idx` refers to the `gen_idx`th basis element of $M$, where the basis elements are
+//! numbered in order of degree. When writing, we always express this image as a sum of basis
+//! elements of $M$ acted on by $\mathrm{Sq}^0$.
+//!
+//! The module $M$ itself can be read and written in Bruner's module definition format using
+//! [`read_module`] and [`write_module`]. This consists of the number $n$ of basis elements, then
+//! their degrees, followed by lines of the form
+//! ```text
+//! $i $r $k $j_1 ... $j_k
+//! ```
+//! which says $\mathrm{Sq}^r x_i = x_{j_1} + \cdots + x_{j_k}$.
+//!
+//! Having read a resolution with [`BrunerResolution::read`], one can compare it with our own
+//! resolution via [`BrunerResolution::comparison_map`], which lifts the identity of $M$ to a
+//! chain map from Bruner's resolution to ours. The change-of-basis matrix in bidegree $(s, t)$ is
+//! then given by `hom.get_map(s).hom_k(t)`, whose rows express our generators in terms of Bruner's
+//! generators.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Read, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use algebra::{
+    Algebra, GeneratedAlgebra, MilnorAlgebra, SteenrodAlgebra,
+    milnor_algebra::MilnorBasisElement,
+    module::{
+        FDModule, FreeModule, Module,
+        homomorphism::{FreeModuleHomomorphism, ModuleHomomorphism},
+    },
+};
+use anyhow::{Context, anyhow};
+use bivec::BiVec;
+use fp::{matrix::Matrix, prime::TWO, vector::FpVector};
+use itertools::Itertools;
+use sseq::coordinates::Bidegree;
+
+use crate::{
+    chain_complex::{
+        AugmentedChainComplex, BoundedChainComplex, FiniteChainComplex, FreeChainComplex,
+    },
+    resolution_homomorphism::ResolutionHomomorphism,
+};
+
+/// The chain complex underlying a resolution read from Bruner's files.
+pub type BrunerChainComplex<A> =
+    FiniteChainComplex<FreeModule<A>, FreeModuleHomomorphism<FreeModule<A>>>;
+
+/// Bruner's files describe the algebra elements in the Milnor basis, so the algebra must be either
+/// a [`MilnorAlgebra`] or a [`SteenrodAlgebra`] using the Milnor basis.
+fn milnor_algebra<A: Algebra>(algebra: &A) -> anyhow::Result<&MilnorAlgebra> {
+    let algebra = algebra as &dyn std::any::Any;
+
+    let milnor = if let Some(algebra) = algebra.downcast_ref::<SteenrodAlgebra>() {
+        match algebra {
+            SteenrodAlgebra::MilnorAlgebra(a) => Some(a),
+            SteenrodAlgebra::AdemAlgebra(_) | SteenrodAlgebra::ChangeOfBasisAlgebra(_) => None,
+        }
+    } else {
+        algebra.downcast_ref::<MilnorAlgebra>()
+    };
+    let milnor = milnor.ok_or_else(|| anyhow!("Bruner's format requires the Milnor basis"))?;
+    if milnor.prime() != 2 || milnor.generic() {
+        return Err(anyhow!("Bruner's format only supports the prime 2"));
+    }
+    Ok(milnor)
+}
+
+/// The degree and index of the `n`th element of a graded set with `dim(t)` elements in degree `t`,
+/// where elements are numbered in order of degree.
+fn nth_element(
+    min_degree: i32,
+    max_degree: i32,
+    dim: impl Fn(i32) -> usize,
+    mut n: usize,
+) -> Option<(i32, usize)> {
+    for t in min_degree..=max_degree {
+        let dim = dim(t);
+        if n < dim {
+            return Some((t, n));
+        }
+        n -= dim;
+    }
+    None
+}
+
+/// A stream of whitespace-delimited tokens.
+struct Tokens<'a> {
+    iter: std::str::SplitWhitespace<'a>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            iter: input.split_whitespace(),
+        }
+    }
+
+    fn next_str(&mut self) -> anyhow::Result<&'a str> {
+        self.iter
+            .next()
+            .ok_or_else(|| anyhow!("Unexpected end of file"))
+    }
+
+    fn next<T: std::str::FromStr>(&mut self) -> anyhow::Result<T>
+    where
+        anyhow::Error: From<T::Err>,
+    {
+        let token = self.next_str()?;
+        token
+            .parse()
+            .map_err(anyhow::Error::from)
+            .with_context(|| format!("Invalid token {token}"))
+    }
+
+    /// Read an algebra element of the form `i(..)(..).`, which may be broken across multiple
+    /// tokens, and return the Milnor basis elements in the sum.
+    fn next_algebra_element(
+        &mut self,
+        algebra: &MilnorAlgebra,
+        degree: i32,
+    ) -> anyhow::Result<Vec<usize>> {
+        let mut element = String::from(self.next_str()?);
+        while !element.ends_with('.') {
+            element.push_str(self.next_str()?);
+        }
+        let ops = element
+            .strip_prefix('i')
+            .and_then(|x| x.strip_suffix('.'))
+            .ok_or_else(|| anyhow!("Invalid algebra element {element}"))?;
+
+        ops.split_terminator(')')
+            .map(|op| {
+                let op = op
+                    .strip_prefix('(')
+                    .ok_or_else(|| anyhow!("Invalid algebra element {element}"))?;
+                let mut p_part = op
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<u32>, _>>()
+                    .with_context(|| format!("Invalid algebra element {element}"))?;
+                while p_part.last() == Some(&0) {
+                    p_part.pop();
+                }
+                let elt = MilnorBasisElement {
+                    q_part: 0,
+                    p_part,
+                    degree,
+                };
+                algebra
+                    .try_basis_element_to_index(&elt)
+                    .ok_or_else(|| anyhow!("Invalid Milnor basis element {op} in degree {degree}"))
+            })
+            .collect()
+    }
+}
+
+/// Format the sum of the Milnor basis elements in degree `degree` with the given indices.
+fn format_algebra_element(
+    algebra: &MilnorAlgebra,
+    degree: i32,
+    ops: impl Iterator<Item = usize>,
+) -> String {
+    let mut result = String::from("i");
+    for op in ops {
+        let elt = algebra.basis_element_from_index(degree, op);
+        if elt.p_part.is_empty() {
+            result.push_str("(0)");
+        } else {
+            write!(result, "({})", elt.p_part.iter().format(",")).unwrap();
+        }
+    }
+    result.push('.');
+    result
+}
+
+/// Read a module in Bruner's module definition format. The algebra must use the Milnor basis.
+pub fn read_module<A: GeneratedAlgebra>(
+    algebra: Arc<A>,
+    name: String,
+    mut input: impl Read,
+) -> anyhow::Result<FDModule<A>> {
+    let milnor = milnor_algebra(&*algebra)?;
+
+    let mut contents = String::new();
+    input.read_to_string(&mut contents)?;
+    let mut tokens = Tokens::new(&contents);
+
+    let num_cells: usize = tokens.next()?;
+    let degrees = (0..num_cells)
+        .map(|_| tokens.next::<i32>())
+        .collect::<anyhow::Result<Vec<i32>>>()?;
+    if !degrees.is_sorted() {
+        return Err(anyhow!(
+            "The basis elements must be listed in order of degree"
+        ));
+    }
+    let Some((&min_degree, &max_degree)) = degrees.first().zip(degrees.last()) else {
+        return Err(anyhow!("The module must be non-zero"));
+    };
+
+    let mut graded_dimension =
+        BiVec::from_vec(min_degree, vec![0; (max_degree - min_degree + 1) as usize]);
+    // The degree and index of each cell
+    let cells: Vec<(i32, usize)> = degrees
+        .iter()
+        .map(|&t| {
+            graded_dimension[t] += 1;
+            (t, graded_dimension[t] - 1)
+        })
+        .collect();
+
+    algebra.compute_basis(max_degree - min_degree);
+    let mut module = FDModule::new(Arc::clone(&algebra), name, graded_dimension);
+    for (n, &(t, idx)) in cells.iter().enumerate() {
+        module.set_basis_element_name(t, idx, format!("x{n}"));
+    }
+
+    let cell = |n: usize| -> anyhow::Result<(i32, usize)> {
+        cells
+            .get(n)
+            .copied()
+            .ok_or_else(|| anyhow!("Invalid basis element x{n}"))
+    };
+
+    let mut decomposables = Vec::new();
+    while let Ok(input) = tokens.next_str() {
+        let input: usize = input.parse()?;
+        let r: i32 = tokens.next()?;
+        let k: usize = tokens.next()?;
+        let (input_deg, input_idx) = cell(input)?;
+
+        let mut output = vec![0; module.dimension(input_deg + r)];
+        for _ in 0..k {
+            let (deg, idx) = cell(tokens.next()?)?;
+            if deg != input_deg + r {
+                return Err(anyhow!(
+                    "Sq{r} x{input} has degree {} but x{idx} has degree {deg}",
+                    input_deg + r
+                ));
+            }
+            output[idx] ^= 1;
+        }
+        let op_idx = milnor.basis_element_to_index(&MilnorBasisElement {
+            q_part: 0,
+            p_part: vec![r as u32],
+            degree: r,
+        });
+        if algebra.generators(r).contains(&op_idx) {
+            module.set_action(r, op_idx, input_deg, input_idx, &output);
+        } else {
+            // The actions of the other operations are determined by those of the generators. We
+            // check that they agree after extending the actions.
+            decomposables.push((input, r, op_idx, output));
+        }
+    }
+
+    for input_degree in (min_degree..=max_degree).rev() {
+        for output_degree in input_degree + 1..=max_degree {
+            module.extend_actions(input_degree, output_degree);
+            module.check_validity(input_degree, output_degree)?;
+        }
+    }
+
+    for (input, r, op_idx, output) in decomposables {
+        let (input_deg, input_idx) = cells[input];
+        let actual = module.action(r, op_idx, input_deg, input_idx);
+        if actual.iter().ne(output) {
+            return Err(anyhow!(
+                "Sq{r} x{input} is inconsistent with the actions of Sq^(2^i)"
+            ));
+        }
+    }
+    Ok(module)
+}
+
+/// Write a finite module in Bruner's module definition format. The algebra must use the Milnor
+/// basis.
+pub fn write_module(module: &impl Module, mut output: impl Write) -> anyhow::Result<()> {
+    let algebra = module.algebra();
+    let milnor = milnor_algebra(&*algebra)?;
+    let min_degree = module.min_degree();
+    let max_degree = module
+        .max_degree()
+        .ok_or_else(|| anyhow!("Bruner's format requires a finite module"))?;
+    module.compute_basis(max_degree);
+    algebra.compute_basis(max_degree - min_degree);
+
+    let degrees = (min_degree..=max_degree)
+        .flat_map(|t| std::iter::repeat_n(t, module.dimension(t)))
+        .collect::<Vec<_>>();
+    // The number of basis elements in degrees below t
+    let offset = |t: i32| -> usize { (min_degree..t).map(|t| module.dimension(t)).sum() };
+
+    writeln!(output, "{}", degrees.len())?;
+    writeln!(output, "{}", degrees.iter().format(" "))?;
+
+    let mut result = FpVector::new(TWO, 0);
+    for (n, &t) in degrees.iter().enumerate() {
+        let idx = n - offset(t);
+        for r in 1..=max_degree - t {
+            let op_idx = milnor.basis_element_to_index(&MilnorBasisElement {
+                q_part: 0,
+                p_part: vec![r as u32],
+                degree: r,
+            });
+            result.set_scratch_vector_size(module.dimension(t + r));
+            module.act_on_basis(result.as_slice_mut(), 1, r, op_idx, t, idx);
+            if result.is_zero() {
+                continue;
+            }
+            let outputs = result.iter_nonzero().map(|(j, _)| j + offset(t + r));
+            writeln!(
+                output,
+                "{n} {r} {} {}",
+                result.iter_nonzero().count(),
+                outputs.format(" ")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// A resolution of a module read from Bruner's files.
+pub struct BrunerResolution<M: Module> {
+    module: Arc<M>,
+    complex: Arc<BrunerChainComplex<M::Algebra>>,
+    /// The image of each generator of $C_0$ under the augmentation, indexed by degree.
+    augmentation: BiVec<Vec<FpVector>>,
+}
+
+impl<M: Module> BrunerResolution<M> {
+    /// Read the files `hDiff.0`, `hDiff.1`, ... in `dir` as a resolution of `module`. This reads
+    /// until the first missing file. The algebra of `module` must use the Milnor basis.
+    pub fn read(dir: &Path, module: Arc<M>) -> anyhow::Result<Self> {
+        let algebra = module.algebra();
+        let milnor = milnor_algebra(&*algebra)?;
+
+        let mut modules: Vec<Arc<FreeModule<M::Algebra>>> = Vec::new();
+        let mut differentials = Vec::new();
+        let mut augmentation = BiVec::new(module.min_degree());
+
+        let mut contents = String::new();
+        for s in 0.. {
+            let path = dir.join(format!("hDiff.{s}"));
+            let mut f = match File::open(&path) {
+                Ok(f) => f,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound && s > 0 => break,
+                Err(e) => return Err(e).with_context(|| format!("Failed to open {path:?}")),
+            };
+            contents.clear();
+            f.read_to_string(&mut contents)?;
+            let mut tokens = Tokens::new(&contents);
+
+            let num_gens: usize = tokens.next()?;
+            let max_degree: i32 = tokens.next()?;
+            algebra.compute_basis(max_degree - module.min_degree());
+
+            let m = Arc::new(FreeModule::new(
+                Arc::clone(&algebra),
+                format!("C{s}"),
+                module.min_degree(),
+            ));
+            let target = modules.last().cloned();
+            let d = target
+                .as_ref()
+                .map(|target| FreeModuleHomomorphism::new(Arc::clone(&m), Arc::clone(target), 0));
+
+            let mut cur_degree = module.min_degree();
+            let mut entries: Vec<FpVector> = Vec::new();
+            let mut add_generators = |t: i32, entries: &mut Vec<FpVector>| {
+                m.add_generators(t, entries.len(), None);
+                let entries = std::mem::take(entries);
+                match &d {
+                    Some(d) => d.add_generators_from_rows(t, entries),
+                    None => {
+                        augmentation.extend_with(t, |_| Vec::new());
+                        augmentation[t] = entries;
+                    }
+                }
+            };
+
+            for _ in 0..num_gens {
+                let t: i32 = tokens.next()?;
+                if t < cur_degree || t > max_degree {
+                    return Err(anyhow!(
+                        "hDiff.{s}: generator in degree {t} is out of order"
+                    ));
+                }
+                while cur_degree < t {
+                    add_generators(cur_degree, &mut entries);
+                    cur_degree += 1;
+                }
+                let num_terms: usize = tokens.next()?;
+
+                let mut value = match &target {
+                    Some(target) => {
+                        target.extend_by_zero(t);
+                        FpVector::new(TWO, target.dimension(t))
+                    }
+                    None => {
+                        module.compute_basis(t);
+                        FpVector::new(TWO, module.dimension(t))
+                    }
+                };
+                for _ in 0..num_terms {
+                    let gen_idx: usize = tokens.next()?;
+                    let op_deg: i32 = tokens.next()?;
+                    let _: usize = tokens.next()?;
+                    let ops = tokens.next_algebra_element(milnor, op_deg)?;
+                    let gen_deg = t - op_deg;
+
+                    match &target {
+                        Some(target) => {
+                            let (deg, idx) = nth_element(
+                                target.min_degree(),
+                                gen_deg,
+                                |t| target.number_of_gens_in_degree(t),
+                                gen_idx,
+                            )
+                            .filter(|&(deg, _)| deg == gen_deg)
+                            .ok_or_else(|| {
+                                anyhow!("hDiff.{s}: generator {gen_idx} is not in degree {gen_deg}")
+                            })?;
+                            let offset = target.generator_offset(t, deg, idx);
+                            for op in ops {
+                                value.add_basis_element(offset + op, 1);
+                            }
+                        }
+                        None => {
+                            let (deg, idx) = nth_element(
+                                module.min_degree(),
+                                gen_deg,
+                                |t| module.dimension(t),
+                                gen_idx,
+                            )
+                            .filter(|&(deg, _)| deg == gen_deg)
+                            .ok_or_else(|| {
+                                anyhow!(
+                                    "hDiff.0: basis element {gen_idx} is not in degree {gen_deg}"
+                                )
+                            })?;
+                            for op in ops {
+                                module.act_on_basis(value.as_slice_mut(), 1, op_deg, op, deg, idx);
+                            }
+                        }
+                    }
+                }
+                entries.push(value);
+            }
+            while cur_degree <= max_degree {
+                add_generators(cur_degree, &mut entries);
+                cur_degree += 1;
+            }
+
+            if let Some(d) = d {
+                differentials.push(Arc::new(d));
+            }
+            modules.push(m);
+        }
+
+        Ok(Self {
+            module,
+            complex: Arc::new(FiniteChainComplex::new(modules, differentials)),
+            augmentation,
+        })
+    }
+
+    pub fn module(&self) -> Arc<M> {
+        Arc::clone(&self.module)
+    }
+
+    /// The largest homological degree that was read.
+    pub fn max_s(&self) -> i32 {
+        self.complex.max_s() - 1
+    }
+
+    /// The underlying chain complex of free modules.
+    pub fn complex(&self) -> Arc<BrunerChainComplex<M::Algebra>> {
+        Arc::clone(&self.complex)
+    }
+
+    /// Lift the identity of the module to a chain map from this resolution to `target`, as far as
+    /// both resolutions have been computed. The augmentation of `target` must be a map to the same
+    /// module, i.e. the module must have the same basis.
+    pub fn comparison_map<CC>(
+        &self,
+        target: Arc<CC>,
+    ) -> ResolutionHomomorphism<BrunerChainComplex<M::Algebra>, CC>
+    where
+        CC: AugmentedChainComplex<Algebra = M::Algebra>,
+    {
+        let hom = ResolutionHomomorphism::new(
+            String::new(),
+            Arc::clone(&self.complex),
+            target,
+            Bidegree::zero(),
+        );
+
+        let max_t = std::cmp::min(
+            self.augmentation.max_degree(),
+            hom.target.module(0).max_computed_degree(),
+        );
+        for t in self.augmentation.min_degree()..=max_t {
+            let rows = &self.augmentation[t];
+            let matrix = if rows.is_empty() {
+                Matrix::new(TWO, 0, self.module.dimension(t))
+            } else {
+                Matrix::from_rows(TWO, rows.clone(), self.module.dimension(t))
+            };
+            hom.extend_step(Bidegree::s_t(0, t), Some(&matrix));
+        }
+        hom.extend_all();
+        hom
+    }
+}
+
+/// Write `resolution` in Bruner's format to the files `hDiff.0`, `hDiff.1`, ... in `dir`, one for
+/// each homological degree that has been computed.
+pub fn write_resolution<CC>(resolution: &CC, dir: &Path) -> anyhow::Result<()>
+where
+    CC: FreeChainComplex + AugmentedChainComplex,
+{
+    let algebra = resolution.algebra();
+    let milnor = milnor_algebra(&*algebra)?;
+
+    let mut buffer = String::new();
+    for s in 0..resolution.next_homological_degree() {
+        let path = dir.join(format!("hDiff.{s}"));
+        let mut f = BufWriter::new(
+            File::create(&path).with_context(|| format!("Failed to create {path:?}"))?,
+        );
+        let module = resolution.module(s);
+        let min_degree = module.min_degree();
+        let max_degree = module.max_computed_degree();
+        let num_gens: usize = (min_degree..=max_degree)
+            .map(|t| module.number_of_gens_in_degree(t))
+            .sum();
+
+        writeln!(f, "        {num_gens}        {max_degree}\n")?;
+
+        for t in min_degree..=max_degree {
+            for idx in 0..module.number_of_gens_in_degree(t) {
+                writeln!(f, "{t}\n")?;
+                buffer.clear();
+                let mut row_count = 0;
+
+                if s == 0 {
+                    let chain_map = resolution.chain_map(0);
+                    let target = chain_map.target();
+                    let mut dx = FpVector::new(TWO, target.dimension(t));
+                    chain_map.apply_to_basis_element(
+                        dx.as_slice_mut(),
+                        1,
+                        t,
+                        module.internal_generator_offset(t, idx),
+                    );
+                    let offset: usize = (target.min_degree()..t).map(|t| target.dimension(t)).sum();
+                    for (i, _) in dx.iter_nonzero() {
+                        row_count += 1;
+                        writeln!(buffer, "{} 0 1 i(0).", offset + i).unwrap();
+                    }
+                } else {
+                    let dmodule = resolution.module(s - 1);
+                    let d = resolution.differential(s);
+                    let dx = d.output(t, idx);
+
+                    let mut gen_count = 0;
+                    for gen_deg in min_degree..=std::cmp::min(t, dmodule.max_computed_degree()) {
+                        for gen_idx in 0..dmodule.number_of_gens_in_degree(gen_deg) {
+                            let op_deg = t - gen_deg;
+                            let algebra_dim = algebra.dimension(op_deg);
+                            let start = dmodule.generator_offset(t, gen_deg, gen_idx);
+                            let slice = dx.slice(start, start + algebra_dim);
+                            if !slice.is_zero() {
+                                row_count += 1;
+                                writeln!(
+                                    buffer,
+                                    "{gen_count} {op_deg} {algebra_dim} {}",
+                                    format_algebra_element(
+                                        milnor,
+                                        op_deg,
+                                        slice.iter_nonzero().map(|(i, _)| i)
+                                    )
+                                )
+                                .unwrap();
+                            }
+                            gen_count += 1;
+                        }
+                    }
+                }
+                writeln!(f, "{row_count}")?;
+                // buffer ends with a new line, and writeln adds another one.
+                writeln!(f, "{buffer}")?;
+            }
+        }
+    }
+
+    Ok(())
+}
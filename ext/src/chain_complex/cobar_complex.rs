@@ -0,0 +1,196 @@
+//! The cobar complex of a comodule over the dual Steenrod algebra.
+//!
+//! The cobar complex of a comodule $M$ has $C^s = \bar{A}_*^{\otimes s} \otimes M$, and its
+//! cohomology is $\mathrm{Ext}_{A_*}(\mathbb{F}_p, M)$, which is the $E_2$ page of the Adams
+//! spectral sequence when $M$ is the homology of a spectrum. This is the same as
+//! $\mathrm{Ext}_A(M^*, \mathbb{F}_p)$, which is what a [`Resolution`](crate::resolution::Resolution)
+//! of the dual module computes, so the cobar complex provides an independent check of the
+//! resolution in low degrees.
+//!
+//! Since a [`ChainComplex`] has differentials that lower $s$, we construct the linear dual of the
+//! cobar complex, namely the bar complex $B_s = \bar{A}^{\otimes s} \otimes M^*$ with differential
+//! $$ d[a_1 | \cdots | a_s] m = \sum_{i = 1}^{s - 1} (-1)^{i - 1} [a_1 | \cdots | a_i a_{i + 1} |
+//! \cdots | a_s] m + (-1)^{s - 1} [a_1 | \cdots | a_{s - 1}] a_s m. $$
+//! Its basis is dual to the basis of the cobar complex, and we name the basis elements after the
+//! latter, e.g. `[xi1|xi1^2] x0`. The homology of $B$ is dual to the cohomology of the cobar
+//! complex, so the two have the same dimensions in every bidegree. Each $B_s$ is a module over
+//! $\mathbb{F}_p$, and the complex is only computed up to a fixed internal degree.
+
+use std::sync::Arc;
+
+use algebra::{
+    Algebra, Field, SteenrodAlgebra,
+    comodule::{Comodule, monomial_to_string},
+    module::{
+        FDModule, Module,
+        homomorphism::{FullModuleHomomorphism, ModuleHomomorphism},
+    },
+};
+use bivec::BiVec;
+use fp::{matrix::Matrix, vector::FpVector};
+use sseq::coordinates::Bidegree;
+
+use crate::chain_complex::{ChainComplex, FiniteChainComplex};
+
+pub type CobarComplex = FiniteChainComplex<FDModule<Field>>;
+
+/// The layout of $B_s$ in degree $t$. The basis element $[a | x]$ with $a \in \bar{A}_n$ and $x
+/// \in B_{s - 1}$ has index `offsets[n] + a * dim B_{s - 1}(t - n) + x`.
+struct Layout {
+    dimension: usize,
+    offsets: BiVec<usize>,
+}
+
+/// Constructs the linear dual of the cobar complex of `comodule` in internal degrees at most
+/// `max_t`. The homology is correct for $s \leq$ `max_s`, and the complex has modules up to $s =$
+/// `max_s + 1`.
+pub fn cobar_complex(comodule: &Comodule, max_s: u32, max_t: i32) -> CobarComplex {
+    let algebra = comodule.algebra();
+    let SteenrodAlgebra::MilnorAlgebra(milnor) = &*algebra else {
+        unreachable!("Comodules are defined over the Milnor algebra");
+    };
+    let p = comodule.prime();
+    let field = Arc::new(Field::new(p));
+    let dual = comodule.dual();
+    let min_degree = dual.min_degree();
+    let max_t = std::cmp::max(max_t, min_degree);
+    algebra.compute_basis(max_t - min_degree);
+
+    let mut names: Vec<BiVec<Vec<String>>> = vec![BiVec::new(min_degree)];
+    let mut layouts: Vec<BiVec<Layout>> = vec![BiVec::new(min_degree)];
+    for t in min_degree..=max_t {
+        names[0].push(
+            (0..dual.dimension(t))
+                .map(|i| dual.basis_element_to_string(t, i))
+                .collect(),
+        );
+        layouts[0].push(Layout {
+            dimension: dual.dimension(t),
+            offsets: BiVec::new(1),
+        });
+    }
+
+    let mut differentials = Vec::new();
+    for s in 1..=max_s as usize + 1 {
+        let mut s_names = BiVec::new(min_degree);
+        let mut s_layouts = BiVec::new(min_degree);
+        let mut matrices = BiVec::new(min_degree);
+        for t in min_degree..=max_t {
+            let mut offsets = BiVec::new(1);
+            let mut t_names = Vec::new();
+            let mut dimension = 0;
+            for n in 1..=t - min_degree {
+                offsets.push(dimension);
+                let rest = &names[s - 1][t - n];
+                for a in 0..algebra.dimension(n) {
+                    let a = monomial_to_string(milnor.basis_element_from_index(n, a));
+                    for x in rest {
+                        t_names.push(match x.strip_prefix('[') {
+                            Some(x) => format!("[{a}|{x}"),
+                            None => format!("[{a}] {x}"),
+                        });
+                    }
+                }
+                dimension += algebra.dimension(n) * layouts[s - 1][t - n].dimension;
+            }
+
+            let target_dim = layouts[s - 1][t].dimension;
+            let mut matrix = Matrix::new(p, dimension, target_dim);
+            for n in 1..=t - min_degree {
+                let rest_dim = layouts[s - 1][t - n].dimension;
+                for a in 0..algebra.dimension(n) {
+                    for x in 0..rest_dim {
+                        let row_idx = offsets[n] + a * rest_dim + x;
+                        let mut row = matrix.row_mut(row_idx);
+                        if s == 1 {
+                            // d[a] m = a m
+                            dual.act_on_basis(row, 1, n, a, t - n, x);
+                            continue;
+                        }
+                        // The term [a b | y] m, where x = [b | y] m
+                        let rest = &layouts[s - 1][t - n];
+                        let (m, rest_offset) = rest
+                            .offsets
+                            .iter_enum()
+                            .rfind(|&(_, &offset)| offset <= x)
+                            .map(|(m, &offset)| (m, offset))
+                            .unwrap();
+                        let y_dim = layouts[s - 2][t - n - m].dimension;
+                        let (b, y) = ((x - rest_offset) / y_dim, (x - rest_offset) % y_dim);
+                        let mut product = FpVector::new(p, algebra.dimension(n + m));
+                        algebra.multiply_basis_elements(product.as_slice_mut(), 1, n, a, m, b);
+                        let target = &layouts[s - 1][t];
+                        for (c, v) in product.iter_nonzero() {
+                            row.add_basis_element(target.offsets[n + m] + c * y_dim + y, v);
+                        }
+                        // The term -[a | d(x)]
+                        let d_rest: &BiVec<Matrix> = &differentials[s - 2];
+                        let target_rest_dim = layouts[s - 2][t - n].dimension;
+                        for (c, v) in d_rest[t - n].row(x).iter_nonzero() {
+                            row.add_basis_element(
+                                target.offsets[n] + a * target_rest_dim + c,
+                                p - v,
+                            );
+                        }
+                    }
+                }
+            }
+            matrices.push(matrix);
+            s_names.push(t_names);
+            s_layouts.push(Layout { dimension, offsets });
+        }
+        differentials.push(matrices);
+        names.push(s_names);
+        layouts.push(s_layouts);
+    }
+
+    let modules = names
+        .into_iter()
+        .enumerate()
+        .map(|(s, names)| {
+            let graded_dimension = names.iter().map(Vec::len).collect::<Vec<_>>();
+            let mut module = FDModule::new(
+                Arc::clone(&field),
+                format!("B_{s}"),
+                BiVec::from_vec(min_degree, graded_dimension),
+            );
+            for (t, names) in names.into_iter_enum() {
+                for (i, name) in names.into_iter().enumerate() {
+                    module.set_basis_element_name(t, i, name);
+                }
+            }
+            Arc::new(module)
+        })
+        .collect::<Vec<_>>();
+
+    let differentials = differentials
+        .into_iter()
+        .enumerate()
+        .map(|(s, matrices)| {
+            Arc::new(FullModuleHomomorphism::from_matrices(
+                Arc::clone(&modules[s + 1]),
+                Arc::clone(&modules[s]),
+                0,
+                matrices,
+            ))
+        })
+        .collect();
+
+    FiniteChainComplex::new(modules, differentials)
+}
+
+/// The dimension of the homology of the (dual) cobar complex in bidegree `b`, i.e. the dimension of
+/// $\mathrm{Ext}^{s, t}$.
+pub fn cobar_homology_dimension(cc: &CobarComplex, b: Bidegree) -> usize {
+    let rank = |s: i32| {
+        let d = cc.differential(s);
+        let mut matrix = Matrix::new(
+            cc.prime(),
+            d.source().dimension(b.t()),
+            d.target().dimension(b.t()),
+        );
+        d.get_matrix(matrix.as_slice_mut(), b.t());
+        matrix.row_reduce()
+    };
+    cc.module(b.s()).dimension(b.t()) - rank(b.s()) - rank(b.s() + 1)
+}
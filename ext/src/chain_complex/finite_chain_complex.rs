@@ -163,7 +163,7 @@ where
     }
 
     fn has_computed_bidegree(&self, b: Bidegree) -> bool {
-        b.s() > self.modules.len() as i32 || b.t() < self.module(b.s()).max_computed_degree()
+        b.s() >= self.modules.len() as i32 || b.t() <= self.module(b.s()).max_computed_degree()
     }
 
     fn next_homological_degree(&self) -> i32 {
@@ -1,4 +1,5 @@
 pub(crate) mod chain_homotopy;
+mod cobar_complex;
 mod finite_chain_complex;
 
 use std::sync::Arc;
@@ -12,6 +13,7 @@ use algebra::{
 };
 // pub use hom_complex::HomComplex;
 pub use chain_homotopy::ChainHomotopy;
+pub use cobar_complex::{CobarComplex, cobar_complex, cobar_homology_dimension};
 pub use finite_chain_complex::{FiniteAugmentedChainComplex, FiniteChainComplex};
 use fp::{
     matrix::Matrix,
@@ -0,0 +1,313 @@
+//! Graceful interruption of long computations.
+//!
+//! The `*_with_cancel` variants of the compute functions take a [`CancellationToken`]. Once the
+//! token is cancelled, no new bidegrees are started, but the bidegrees that are being computed are
+//! allowed to finish and their save files are written in full. The functions then return an
+//! [`Interrupted`] error. These functions are
+//!
+//!  - [`MuResolution::compute_through_stem_with_cancel`] and
+//!    [`nassau::Resolution::compute_through_stem_with_cancel`];
+//!  - [`MuResolutionHomomorphism::extend_through_stem_with_cancel`]; and
+//!  - [`SecondaryLift::extend_all_with_cancel`].
+//!
+//! Regardless of whether the computation was interrupted, they write a [`Checkpoint`] to the save
+//! directory if there is one, recording how far the computation got. The computation can then be
+//! resumed by calling the same function again, either on the same object or on a new object in a
+//! different process using the same save directory. The latter simply loads the completed
+//! bidegrees from the save directory as usual.
+//!
+//! The checkpoint itself is not read when resuming, since the save files already record which
+//! bidegrees are complete. It is meant for inspecting the progress of a computation from outside,
+//! e.g. via [`Checkpoint::read`]. In particular, the checkpoint of
+//! [`SecondaryLift::extend_all_with_cancel`] only records the progress of the homotopies, and the
+//! composites and intermediates that were computed before the interruption are reloaded from their
+//! own save files.
+//!
+//! A token that is cancelled by Ctrl-C (or SIGTERM) can be obtained from
+//! [`CancellationToken::ctrlc`]. Without such a token, Ctrl-C deletes the save files that are
+//! being written and terminates the program immediately. With one, the first Ctrl-C cancels the
+//! token instead, and only a second Ctrl-C terminates the program.
+//!
+//! [`MuResolution::compute_through_stem_with_cancel`]: crate::resolution::MuResolution::compute_through_stem_with_cancel
+//! [`nassau::Resolution::compute_through_stem_with_cancel`]: crate::nassau::Resolution::compute_through_stem_with_cancel
+//! [`MuResolutionHomomorphism::extend_through_stem_with_cancel`]: crate::resolution_homomorphism::MuResolutionHomomorphism::extend_through_stem_with_cancel
+//! [`SecondaryLift::extend_all_with_cancel`]: crate::secondary::SecondaryLift::extend_all_with_cancel
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use anyhow::{Context, anyhow};
+use serde_json::{Value, json};
+use sseq::coordinates::Bidegree;
+
+/// A flag that requests a computation to stop at the next opportunity. Clones of a token share
+/// the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+static CTRLC_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+/// The token returned by [`CancellationToken::ctrlc`], if it has been requested.
+pub(crate) fn ctrlc_token() -> Option<&'static CancellationToken> {
+    CTRLC_TOKEN.get()
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a token that is cancelled when the process receives Ctrl-C or SIGTERM. Every call
+    /// returns the same token.
+    pub fn ctrlc() -> Self {
+        let token = CTRLC_TOKEN.get_or_init(Self::new).clone();
+        #[cfg(unix)]
+        crate::save::install_ctrlc_handler();
+        token
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// The error returned when a computation is stopped by a [`CancellationToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Computation interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// A record of how far a computation has progressed, which is written as
+/// `{task}_checkpoint.json` in the save directory.
+///
+/// The computation is organized in rows indexed by `s`, starting at `min_s`. For the `i`th row,
+/// every bidegree `(min_s + i, t)` with `t <= progress[i]` has been computed, and the computation
+/// aims to compute up to `t = target[i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub task: String,
+    pub min_s: i32,
+    pub progress: Vec<i32>,
+    pub target: Vec<i32>,
+}
+
+impl Checkpoint {
+    /// Create a checkpoint from the rows `(progress, target)` starting at `min_s`.
+    pub fn new(task: &str, min_s: i32, rows: impl IntoIterator<Item = (i32, i32)>) -> Self {
+        let (progress, target) = rows.into_iter().unzip();
+        Self {
+            task: task.to_owned(),
+            min_s,
+            progress,
+            target,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        std::iter::zip(&self.progress, &self.target).all(|(p, t)| p >= t)
+    }
+
+    /// The first bidegree in each row that has not been computed yet.
+    pub fn remaining(&self) -> impl Iterator<Item = Bidegree> + '_ {
+        std::iter::zip(&self.progress, &self.target)
+            .enumerate()
+            .filter(|(_, (p, t))| p < t)
+            .map(|(i, (p, _))| Bidegree::s_t(self.min_s + i as i32, p + 1))
+    }
+
+    fn path(dir: &Path, task: &str) -> PathBuf {
+        dir.join(format!("{task}_checkpoint.json"))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "task": self.task,
+            "min_s": self.min_s,
+            "progress": self.progress,
+            "target": self.target,
+            "complete": self.is_complete(),
+        })
+    }
+
+    pub fn from_json(json: &Value) -> anyhow::Result<Self> {
+        let rows = |key: &str| -> anyhow::Result<Vec<i32>> {
+            json[key]
+                .as_array()
+                .ok_or_else(|| anyhow!("Missing field {key}"))?
+                .iter()
+                .map(|x| {
+                    x.as_i64()
+                        .map(|x| x as i32)
+                        .ok_or_else(|| anyhow!("Invalid entry in {key}: {x}"))
+                })
+                .collect()
+        };
+        let checkpoint = Self {
+            task: json["task"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Missing field task"))?
+                .to_owned(),
+            min_s: json["min_s"]
+                .as_i64()
+                .ok_or_else(|| anyhow!("Missing field min_s"))? as i32,
+            progress: rows("progress")?,
+            target: rows("target")?,
+        };
+        if checkpoint.progress.len() != checkpoint.target.len() {
+            return Err(anyhow!("progress and target have different lengths"));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write the checkpoint to `dir`. The file is written to a temporary location and then moved
+    /// into place, so that an existing checkpoint is never left half-written.
+    pub fn write(&self, dir: &Path) -> anyhow::Result<()> {
+        let path = Self::path(dir, &self.task);
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, format!("{:#}\n", self.to_json()))
+            .with_context(|| format!("Failed to write checkpoint {tmp_path:?}"))?;
+        std::fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to write checkpoint {path:?}"))?;
+        tracing::info!(file = ?path, complete = self.is_complete(), "checkpoint written");
+        Ok(())
+    }
+
+    /// Read the checkpoint of `task` from `dir`. Returns `None` if there is no such checkpoint.
+    pub fn read(dir: &Path, task: &str) -> anyhow::Result<Option<Self>> {
+        let path = Self::path(dir, task);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {path:?}")),
+        };
+        let json = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse checkpoint {path:?}"))?;
+        Self::from_json(&json)
+            .with_context(|| format!("Invalid checkpoint {path:?}"))
+            .map(Some)
+    }
+
+    /// Write the checkpoint to `dir` if there is a save directory, and return [`Interrupted`] if
+    /// the computation is not complete.
+    pub(crate) fn finish(self, dir: Option<&PathBuf>) -> anyhow::Result<()> {
+        if let Some(dir) = dir {
+            self.write(dir)?;
+        }
+        if self.is_complete() {
+            Ok(())
+        } else {
+            Err(Interrupted.into())
+        }
+    }
+}
+
+/// Wrap a function passed to [`sseq::coordinates::iter_s_t`] so that it stops computing new
+/// bidegrees once `token` is cancelled. Skipped bidegrees report that nothing new was computed,
+/// so their dependents are skipped as well.
+pub(crate) fn cancellable(
+    token: Option<&CancellationToken>,
+    f: impl Fn(Bidegree) -> std::ops::Range<i32> + Sync,
+) -> impl Fn(Bidegree) -> std::ops::Range<i32> + Sync {
+    move |b| {
+        if token.is_some_and(CancellationToken::is_cancelled) {
+            b.t()..b.t()
+        } else {
+            f(b)
+        }
+    }
+}
+
+/// After [`sseq::coordinates::iter_s_t`] has been interrupted, some bidegrees in a row may have
+/// been computed while an earlier one in the same row was skipped. This computes the skipped
+/// bidegrees with `f`, so that every row is once again computed up to some `t`.
+///
+/// The function `row` returns the first degree not yet computed in order and the degrees
+/// computed out of order in a row. If a bidegree in a row is computed out of order, the
+/// prerequisites of the earlier bidegrees in that row have been computed, so this is always
+/// possible.
+pub(crate) fn fill_gaps(
+    rows: std::ops::Range<i32>,
+    row: impl Fn(i32) -> (i32, Vec<i32>),
+    f: impl Fn(Bidegree),
+) {
+    for s in rows {
+        let (next_degree, ooo) = row(s);
+        let Some(&max) = ooo.iter().max() else {
+            continue;
+        };
+        for t in next_degree..max {
+            if !ooo.contains(&t) {
+                f(Bidegree::s_t(s, t));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        assert_eq!(Checkpoint::read(tempdir.path(), "test").unwrap(), None);
+
+        let checkpoint = Checkpoint::new("test", 1, [(5, 10), (10, 10), (3, 12)]);
+        assert!(!checkpoint.is_complete());
+        assert_eq!(
+            checkpoint.remaining().collect::<Vec<_>>(),
+            [Bidegree::s_t(1, 6), Bidegree::s_t(3, 4)]
+        );
+        checkpoint.write(tempdir.path()).unwrap();
+        assert_eq!(
+            Checkpoint::read(tempdir.path(), "test").unwrap(),
+            Some(checkpoint)
+        );
+
+        let checkpoint = Checkpoint::new("test", 0, [(10, 10)]);
+        assert!(checkpoint.is_complete());
+        checkpoint.write(tempdir.path()).unwrap();
+        assert_eq!(
+            Checkpoint::read(tempdir.path(), "test").unwrap(),
+            Some(checkpoint)
+        );
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let computed = std::sync::Mutex::new(Vec::new());
+        fill_gaps(
+            0..3,
+            |s| match s {
+                0 => (4, vec![]),
+                1 => (2, vec![3, 6]),
+                _ => (0, vec![1]),
+            },
+            |b| computed.lock().unwrap().push(b),
+        );
+        assert_eq!(
+            computed.into_inner().unwrap(),
+            [
+                Bidegree::s_t(1, 2),
+                Bidegree::s_t(1, 4),
+                Bidegree::s_t(1, 5),
+                Bidegree::s_t(2, 0)
+            ]
+        );
+    }
+}
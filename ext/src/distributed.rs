@@ -0,0 +1,648 @@
+//! This module computes a [`MuResolution`] using multiple processes that share a save directory.
+//!
+//! The [`Coordinator`] walks through the bidegrees in the same order as
+//! [`MuResolution::compute_through_stem`], and hands each bidegree whose dependencies are complete
+//! to an idle worker. Workers are started with [`run_worker`] and talk to the coordinator over a
+//! TCP or Unix socket. A worker computes the bidegree it is assigned using
+//! the data other workers have written to the save directory, and then writes its own results
+//! there in the usual format. The save directory is the only way results are shared, so the
+//! coordinator itself does not need to hold the resolution in memory.
+//!
+//! While a worker is computing a bidegree, it holds a [`SaveKind::Lock`] file for that bidegree.
+//! A bidegree is complete if its differential has been saved and it is not locked. If a worker
+//! disconnects before finishing, the coordinator deletes the partially written files, releases the
+//! lock and hands the bidegree to another worker, so only the work on that bidegree is lost. Locks
+//! left over by a coordinator that was itself terminated are cleaned up the same way when the next
+//! coordinator starts, and bidegrees that are already complete are skipped.
+//!
+//! The protocol is line based. A worker sends `ready` when it connects and `done s t` after it
+//! computes a bidegree, and the coordinator replies to each of these with either `compute s t` or
+//! `exit`.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    str::FromStr,
+    sync::mpsc,
+    time::Duration,
+};
+
+use algebra::MuAlgebra;
+use anyhow::{Context, anyhow};
+use sseq::coordinates::Bidegree;
+
+use crate::{
+    chain_complex::ChainComplex,
+    resolution::MuResolution,
+    save::{SaveDirectory, SaveKind},
+};
+
+/// How long the coordinator waits for messages before checking for new connections.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The number of times a bidegree may be handed out before the coordinator gives up on it. If a
+/// bidegree keeps killing the workers computing it, the problem is unlikely to go away by itself.
+const MAX_ATTEMPTS: usize = 3;
+
+/// The address of a coordinator. This is parsed from strings of the form `tcp:host:port` or
+/// `unix:path`. A string without a prefix is interpreted as a TCP address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return Ok(Self::Unix(PathBuf::from(path)));
+            #[cfg(not(unix))]
+            return Err(anyhow!(
+                "Unix sockets are not supported on this platform: {path}"
+            ));
+        }
+        let addr = s.strip_prefix("tcp:").unwrap_or(s);
+        if addr.is_empty() {
+            return Err(anyhow!("Empty address"));
+        }
+        Ok(Self::Tcp(addr.to_owned()))
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp:{addr}"),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+type Reader = BufReader<Box<dyn io::Read + Send>>;
+type Writer = Box<dyn io::Write + Send>;
+
+impl Address {
+    fn connect(&self) -> io::Result<(Reader, Writer)> {
+        match self {
+            Self::Tcp(addr) => {
+                let stream = TcpStream::connect(addr)?;
+                Ok((
+                    BufReader::new(Box::new(stream.try_clone()?)),
+                    Box::new(stream),
+                ))
+            }
+            #[cfg(unix)]
+            Self::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                Ok((
+                    BufReader::new(Box::new(stream.try_clone()?)),
+                    Box::new(stream),
+                ))
+            }
+        }
+    }
+}
+
+/// A message from a worker to the coordinator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Request {
+    Ready,
+    Done(Bidegree),
+}
+
+/// A message from the coordinator to a worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Compute(Bidegree),
+    Exit,
+}
+
+fn parse_bidegree<'a>(mut words: impl Iterator<Item = &'a str>) -> anyhow::Result<Bidegree> {
+    let mut next = || -> anyhow::Result<i32> {
+        Ok(words
+            .next()
+            .ok_or_else(|| anyhow!("Missing coordinate"))?
+            .parse()?)
+    };
+    let s = next()?;
+    let t = next()?;
+    Ok(Bidegree::s_t(s, t))
+}
+
+impl FromStr for Request {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut words = s.split_whitespace();
+        match words.next() {
+            Some("ready") => Ok(Self::Ready),
+            Some("done") => Ok(Self::Done(parse_bidegree(words)?)),
+            _ => Err(anyhow!("Invalid request: {s}")),
+        }
+    }
+}
+
+impl Display for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ready => write!(f, "ready"),
+            Self::Done(b) => write!(f, "done {} {}", b.s(), b.t()),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut words = s.split_whitespace();
+        match words.next() {
+            Some("compute") => Ok(Self::Compute(parse_bidegree(words)?)),
+            Some("exit") => Ok(Self::Exit),
+            _ => Err(anyhow!("Invalid command: {s}")),
+        }
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Compute(b) => write!(f, "compute {} {}", b.s(), b.t()),
+            Self::Exit => write!(f, "exit"),
+        }
+    }
+}
+
+/// Read a single line and parse it. Returns `None` if the connection was closed.
+fn receive<T: FromStr<Err = anyhow::Error>>(reader: &mut Reader) -> anyhow::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().parse()?))
+}
+
+fn send(writer: &mut Writer, msg: impl Display) -> io::Result<()> {
+    writeln!(writer, "{msg}")?;
+    writer.flush()
+}
+
+/// Keeps track of which bidegrees have been completed, and determines which bidegrees are ready to
+/// be computed. This follows the same logic as [`MuResolution::compute_through_stem`].
+struct Staircase {
+    max: Bidegree,
+    /// `progress[s]` is the largest `t` such that `(s, t)` has been completed.
+    progress: Vec<i32>,
+}
+
+impl Staircase {
+    /// Returns the staircase together with the bidegrees that are initially ready.
+    fn new(min_degree: i32, max: Bidegree) -> (Self, Vec<Bidegree>) {
+        let mut staircase = Self {
+            max,
+            progress: vec![min_degree - 1; max.s() as usize + 1],
+        };
+        // As in `compute_through_stem`, we kickstart the process by pretending we have computed
+        // (0, min_degree - 1).
+        staircase.progress[0] = min_degree - 2;
+        let ready = staircase.complete(Bidegree::s_t(0, min_degree - 1));
+        (staircase, ready)
+    }
+
+    /// Mark `b` as completed and return the bidegrees that became ready as a result.
+    fn complete(&mut self, b: Bidegree) -> Vec<Bidegree> {
+        let mut ready = Vec::new();
+        let mut completed = vec![b];
+
+        while let Some(b) = completed.pop() {
+            let s = b.s() as usize;
+            assert_eq!(self.progress[s], b.t() - 1);
+            self.progress[s] = b.t();
+
+            // How far we are from the last one for this s.
+            let distance = self.max.n() - b.n() + 1;
+
+            if b.s() < self.max.s() && self.progress[s + 1] == b.t() - 1 {
+                ready.push(b + Bidegree::s_t(1, 0));
+            }
+
+            if distance > 1 && (b.s() == 0 || self.progress[s - 1] > b.t()) {
+                ready.push(b + Bidegree::s_t(0, 1));
+            } else if distance == 1 && b.s() < self.max.s() {
+                // The bidegree past the edge is never computed. The worker that needs its kernel
+                // computes it itself.
+                completed.push(b + Bidegree::s_t(0, 1));
+            }
+        }
+        ready
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener, PathBuf),
+}
+
+impl Listener {
+    /// Accept a new connection if there is one pending.
+    fn accept(&self) -> io::Result<Option<(Reader, Writer)>> {
+        let result = match self {
+            Self::Tcp(listener) => listener.accept().and_then(|(stream, _)| {
+                stream.set_nonblocking(false)?;
+                Ok((
+                    BufReader::new(Box::new(stream.try_clone()?) as Box<dyn io::Read + Send>),
+                    Box::new(stream) as Writer,
+                ))
+            }),
+            #[cfg(unix)]
+            Self::Unix(listener, _) => listener.accept().and_then(|(stream, _)| {
+                stream.set_nonblocking(false)?;
+                Ok((
+                    BufReader::new(Box::new(stream.try_clone()?) as Box<dyn io::Read + Send>),
+                    Box::new(stream) as Writer,
+                ))
+            }),
+        };
+        match result {
+            Ok(x) => Ok(Some(x)),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Self::Unix(_, path) = self {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
+
+enum Event {
+    Request(usize, Request),
+    Disconnected(usize),
+}
+
+/// Relay messages between a worker and the coordinator's main loop.
+fn handle_connection(
+    id: usize,
+    mut reader: Reader,
+    mut writer: Writer,
+    events: mpsc::Sender<Event>,
+    commands: mpsc::Receiver<Command>,
+) {
+    loop {
+        let request = match receive::<Request>(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!(id, "invalid message from worker: {e:#}");
+                break;
+            }
+        };
+        if events.send(Event::Request(id, request)).is_err() {
+            return;
+        }
+        let Ok(command) = commands.recv() else {
+            return;
+        };
+        if send(&mut writer, command).is_err() || command == Command::Exit {
+            break;
+        }
+    }
+    events.send(Event::Disconnected(id)).ok();
+}
+
+struct Worker {
+    commands: mpsc::Sender<Command>,
+    in_flight: Option<Bidegree>,
+}
+
+/// The coordinator of a distributed computation. See the [module-level documentation](self) for
+/// details.
+pub struct Coordinator {
+    listener: Listener,
+}
+
+impl Coordinator {
+    /// Listen for workers at the given address. For TCP addresses, the port may be 0, in which case
+    /// the actual port can be retrieved via [`Coordinator::address`].
+    pub fn bind(address: &Address) -> anyhow::Result<Self> {
+        let listener = match address {
+            Address::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)
+                    .with_context(|| format!("Failed to listen at {address}"))?;
+                listener.set_nonblocking(true)?;
+                Listener::Tcp(listener)
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let listener = std::os::unix::net::UnixListener::bind(path)
+                    .with_context(|| format!("Failed to listen at {address}"))?;
+                listener.set_nonblocking(true)?;
+                Listener::Unix(listener, path.clone())
+            }
+        };
+        Ok(Self { listener })
+    }
+
+    /// The address workers should connect to.
+    pub fn address(&self) -> anyhow::Result<Address> {
+        Ok(match &self.listener {
+            Listener::Tcp(listener) => Address::Tcp(listener.local_addr()?.to_string()),
+            #[cfg(unix)]
+            Listener::Unix(_, path) => Address::Unix(path.clone()),
+        })
+    }
+
+    /// Resolve through the stem `max` using the workers that connect to this coordinator. This
+    /// returns when every bidegree has been computed and saved, after which the resolution can be
+    /// loaded from the save directory as usual. Workers connected at that point are told to exit.
+    ///
+    /// The resolution `res` is only used to locate the save files, and is not modified. It must
+    /// have a save directory that is used for both reading and writing, and the workers must use
+    /// the same save directory.
+    pub fn run<const U: bool, CC: ChainComplex>(
+        &self,
+        res: &MuResolution<U, CC>,
+        max: Bidegree,
+    ) -> anyhow::Result<()>
+    where
+        CC::Algebra: MuAlgebra<U>,
+    {
+        let dir = shared_save_dir(res.save_dir())?;
+        SaveKind::Lock.create_dir(&dir)?;
+
+        let is_complete = |b: Bidegree| {
+            res.save_file(SaveKind::Differential, b).exists(dir.clone())
+                && !res.save_file(SaveKind::Lock, b).exists(dir.clone())
+        };
+
+        // Delete the partial results of bidegrees that were locked by a previous run.
+        for s in 0..=max.s() {
+            for t in res.min_degree()..=max.n() + s + 1 {
+                release(res, &dir, Bidegree::s_t(s, t))?;
+            }
+        }
+
+        let (mut staircase, ready) = Staircase::new(res.min_degree(), max);
+        let mut queue = VecDeque::new();
+        // Queue the bidegrees in `ready`, skipping the ones that are already complete.
+        let schedule = |mut ready: Vec<Bidegree>,
+                        staircase: &mut Staircase,
+                        queue: &mut VecDeque<Bidegree>| {
+            while let Some(b) = ready.pop() {
+                if is_complete(b) {
+                    ready.extend(staircase.complete(b));
+                } else {
+                    queue.push_back(b);
+                }
+            }
+        };
+        schedule(ready, &mut staircase, &mut queue);
+
+        let (event_sender, events) = mpsc::channel();
+        let mut workers: HashMap<usize, Worker> = HashMap::new();
+        let mut idle: VecDeque<usize> = VecDeque::new();
+        let mut attempts: HashMap<Bidegree, usize> = HashMap::new();
+        let mut next_id = 0;
+
+        loop {
+            while let Some((reader, writer)) = self.listener.accept()? {
+                let (command_sender, commands) = mpsc::channel();
+                let events = event_sender.clone();
+                let id = next_id;
+                next_id += 1;
+                // Register the worker before its first request can arrive.
+                tracing::info!(id, "worker connected");
+                workers.insert(
+                    id,
+                    Worker {
+                        commands: command_sender,
+                        in_flight: None,
+                    },
+                );
+                std::thread::spawn(move || handle_connection(id, reader, writer, events, commands));
+            }
+
+            while !queue.is_empty()
+                && let Some(id) = idle.pop_front()
+            {
+                let b = queue.pop_front().unwrap();
+                let attempt = attempts.entry(b).or_default();
+                *attempt += 1;
+                if *attempt > MAX_ATTEMPTS {
+                    return Err(anyhow!(
+                        "Giving up on {b} after {MAX_ATTEMPTS} failed attempts"
+                    ));
+                }
+                let worker = workers.get_mut(&id).unwrap();
+                if worker.commands.send(Command::Compute(b)).is_ok() {
+                    tracing::info!(id, %b, "assigned");
+                    worker.in_flight = Some(b);
+                } else {
+                    *attempt -= 1;
+                    queue.push_front(b);
+                }
+            }
+
+            if queue.is_empty() && workers.values().all(|w| w.in_flight.is_none()) {
+                break;
+            }
+
+            match events.recv_timeout(POLL_INTERVAL) {
+                Ok(Event::Request(id, Request::Ready)) => idle.push_back(id),
+                Ok(Event::Request(id, Request::Done(b))) => {
+                    let worker = workers.get_mut(&id).unwrap();
+                    if worker.in_flight != Some(b) {
+                        return Err(anyhow!("Worker {id} reported unassigned bidegree {b}"));
+                    }
+                    if !is_complete(b) {
+                        return Err(anyhow!(
+                            "Worker {id} reported {b} as done, but it is not in the save directory"
+                        ));
+                    }
+                    worker.in_flight = None;
+                    tracing::info!(id, %b, "completed");
+                    schedule(staircase.complete(b), &mut staircase, &mut queue);
+                    idle.push_back(id);
+                }
+                Ok(Event::Disconnected(id)) => {
+                    idle.retain(|&x| x != id);
+                    if let Some(Worker {
+                        in_flight: Some(b), ..
+                    }) = workers.remove(&id)
+                    {
+                        if is_complete(b) {
+                            // The worker finished the bidegree but did not get to report it.
+                            schedule(staircase.complete(b), &mut staircase, &mut queue);
+                        } else {
+                            tracing::warn!(id, %b, "worker disconnected during computation");
+                            release(res, &dir, b)?;
+                            queue.push_front(b);
+                        }
+                    } else {
+                        tracing::info!(id, "worker disconnected");
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => (),
+                Err(mpsc::RecvTimeoutError::Disconnected) => unreachable!(),
+            }
+        }
+
+        for id in idle {
+            workers[&id].commands.send(Command::Exit).ok();
+        }
+        Ok(())
+    }
+}
+
+/// Extract the save directory, which must be used for both reading and writing.
+fn shared_save_dir(save_dir: &SaveDirectory) -> anyhow::Result<PathBuf> {
+    match save_dir {
+        SaveDirectory::Combined(_) => Ok(save_dir.write().unwrap().clone()),
+        SaveDirectory::None => Err(anyhow!("Distributed resolution requires a save directory")),
+        SaveDirectory::Split { .. } => Err(anyhow!(
+            "Distributed resolution requires the same save directory for reading and writing"
+        )),
+    }
+}
+
+/// If `b` is locked, delete its partial results and the lock.
+fn release<const U: bool, CC: ChainComplex>(
+    res: &MuResolution<U, CC>,
+    dir: &std::path::Path,
+    b: Bidegree,
+) -> anyhow::Result<()>
+where
+    CC::Algebra: MuAlgebra<U>,
+{
+    let lock = res.save_file(SaveKind::Lock, b);
+    if !lock.exists(dir.to_owned()) {
+        return Ok(());
+    }
+    tracing::warn!(%b, "releasing stale lock");
+    for kind in [
+        SaveKind::Differential,
+        SaveKind::ResQi,
+        SaveKind::AugmentationQi,
+    ] {
+        res.save_file(kind, b)
+            .delete_file(dir.to_owned())
+            .with_context(|| format!("Failed to delete partial {} at {b}", kind.name()))?;
+    }
+    lock.delete_file(dir.to_owned())
+        .with_context(|| format!("Failed to release lock at {b}"))?;
+    Ok(())
+}
+
+/// Connect to the coordinator at `address` and compute the bidegrees it assigns until told to
+/// exit. The resolution must use the same save directory as the coordinator.
+///
+/// The resolution keeps the data loaded from the save directory in memory, so that it only has to
+/// load the data computed by other workers since the last assignment.
+pub fn run_worker<const U: bool, CC: ChainComplex>(
+    res: &MuResolution<U, CC>,
+    address: &Address,
+) -> anyhow::Result<()>
+where
+    CC::Algebra: MuAlgebra<U>,
+{
+    let dir = shared_save_dir(res.save_dir())?;
+    if !res.should_save {
+        return Err(anyhow!(
+            "Distributed resolution requires saving to be enabled"
+        ));
+    }
+
+    let (mut reader, mut writer) = address
+        .connect()
+        .with_context(|| format!("Failed to connect to {address}"))?;
+    send(&mut writer, Request::Ready)?;
+
+    loop {
+        match receive::<Command>(&mut reader)? {
+            Some(Command::Compute(b)) => {
+                let lock = res.save_file(SaveKind::Lock, b);
+                lock.create_lock(dir.clone())
+                    .with_context(|| format!("Failed to lock {b}"))?;
+                res.compute_bidegree_from_save(b)?;
+                lock.delete_file(dir.clone())
+                    .with_context(|| format!("Failed to unlock {b}"))?;
+                send(&mut writer, Request::Done(b))?;
+            }
+            Some(Command::Exit) => return Ok(()),
+            None => return Err(anyhow!("Coordinator closed the connection")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_roundtrip() {
+        let b = Bidegree::s_t(3, 17);
+        for request in [Request::Ready, Request::Done(b)] {
+            assert_eq!(request.to_string().parse::<Request>().unwrap(), request);
+        }
+        for command in [Command::Compute(b), Command::Exit] {
+            assert_eq!(command.to_string().parse::<Command>().unwrap(), command);
+        }
+        assert!("done 3".parse::<Request>().is_err());
+        assert!("compute".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_address() {
+        assert_eq!(
+            "localhost:1234".parse::<Address>().unwrap(),
+            Address::Tcp("localhost:1234".into())
+        );
+        assert_eq!(
+            "tcp:127.0.0.1:0".parse::<Address>().unwrap(),
+            Address::Tcp("127.0.0.1:0".into())
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            "unix:/tmp/sock".parse::<Address>().unwrap(),
+            Address::Unix("/tmp/sock".into())
+        );
+    }
+
+    /// The staircase should hand out every bidegree that `compute_through_stem` computes, each
+    /// exactly once, and only after its dependencies.
+    #[test]
+    fn test_staircase() {
+        let max = Bidegree::n_s(10, 4);
+        let (mut staircase, mut ready) = Staircase::new(0, max);
+        let mut done = std::collections::HashSet::new();
+        while let Some(b) = ready.pop() {
+            assert!(b.n() <= max.n() && b.s() <= max.s());
+            if b.t() > 0 {
+                assert!(done.contains(&(b - Bidegree::s_t(0, 1))));
+            }
+            if b.s() > 0 && b.n() < max.n() {
+                assert!(done.contains(&(b - Bidegree::s_t(1, 0))));
+            }
+            assert!(done.insert(b));
+            ready.extend(staircase.complete(b));
+        }
+        let expected = (0..=max.s())
+            .map(|s| (max.n() + s + 1) as usize)
+            .sum::<usize>();
+        assert_eq!(done.len(), expected);
+    }
+}
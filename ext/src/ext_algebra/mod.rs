@@ -17,9 +17,11 @@
 //! class is assembled at request time as the corresponding linear combination of generator maps.
 //!
 //! The secondary differential ($d_2$) and the $\Mod_{C\lambda^2}$ secondary product live in the
-//! [`secondary`] submodule ([`SecondaryExtAlgebra`]).
+//! [`secondary`] submodule ([`SecondaryExtAlgebra`]). The [`names`] submodule assigns standard
+//! names to the generators by decomposing them into products and Massey products.
 
 pub mod massey;
+pub mod names;
 pub mod secondary;
 
 use std::sync::Arc;
@@ -0,0 +1,903 @@
+//! Standard names for the generators of $\Ext$.
+//!
+//! [`ClassNamer`] assigns names such as $h_0^2$, $h_1 c_0$ or $Ph_1$ to the basis generators of
+//! $\Ext(M, k)$ by decomposing them as products of a configurable list of named indecomposables of
+//! $\Ext(k, k)$. Each indecomposable is given by a [`Definition`]: an explicit class, "the" class
+//! in a bidegree that is not a product of the previous ones, or a Massey product of previously
+//! named classes. When `M != k`, the products are taken with named classes of $\Ext(M, k)$ itself,
+//! which we call *cells*. By default these are the generators of $\Ext^0(M, k)$.
+//!
+//! Bidegrees are processed in order of increasing filtration. At each bidegree we first form every
+//! nonzero product of a named monomial with an indecomposable, and then evaluate the definitions
+//! living in that bidegree. Each basis generator is then
+//!  - [`ClassName::Named`] if it is a multiple of exactly one monomial, or otherwise a combination
+//!    of monomials;
+//!  - [`ClassName::Ambiguous`] if several distinct monomials represent it, e.g. $h_1^3 = h_0^2
+//!    h_2$;
+//!  - [`ClassName::Undecomposable`] if it is not in the span of the monomials, which means the list
+//!    of indecomposables is incomplete in this range.
+//!
+//! Names are written in TeX. They can be placed on charts with [`ClassNames::write_labels`].
+//!
+//! # Configuration format
+//! [`ClassNamer`] can be parsed from a list of definitions, one per line. Empty lines and lines
+//! starting with `#` are ignored.
+//! ```text
+//! h_0 = (0, 1)
+//! x = (5, 3) [1, 0]
+//! c_0 = <h_1, h_0, h_2^2>
+//! cell x_0 = (0, 0)
+//! ```
+//! The right hand side is a [`Definition`]; bidegrees are written as `(n, s)`. Lines starting with
+//! `cell` define cells instead of indecomposables.
+
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use anyhow::{Context, anyhow};
+use fp::{
+    matrix::{Matrix, Subspace},
+    prime::{Prime, ValidPrime, inverse},
+    vector::FpVector,
+};
+use sseq::{
+    charting::Backend,
+    coordinates::{Bidegree, BidegreeElement, BidegreeGenerator},
+};
+
+use super::ExtAlgebra;
+use crate::chain_complex::{AugmentedChainComplex, FreeChainComplex};
+
+/// How a named class is specified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Definition {
+    /// The class with the given coordinates in the generator basis. Written `(n, s) [c_0, c_1, ..]`.
+    Class(Bidegree, Vec<u32>),
+    /// A class in this bidegree that is not a product of previously named classes. This is only
+    /// well-defined modulo decomposables, and if the indecomposables in this bidegree are more than
+    /// one-dimensional, the first basis generator that is not decomposable is used. Written `(n,
+    /// s)`.
+    Indecomposable(Bidegree),
+    /// A representative of the Massey product $\langle a, b, c\rangle$, where each operand is a
+    /// monomial in previously named classes, such as `h_2^2`. The first two operands lie in
+    /// $\Ext(k, k)$, and the last in $\Ext(M, k)$. Written `<a, b, c>`.
+    Massey([String; 3]),
+}
+
+fn parse_bidegree(s: &str) -> anyhow::Result<Bidegree> {
+    let inner = s
+        .trim()
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| anyhow!("Expected bidegree of the form (n, s), found {s}"))?;
+    let (n, s) = inner
+        .split_once(',')
+        .ok_or_else(|| anyhow!("Expected bidegree of the form (n, s), found {s}"))?;
+    Ok(Bidegree::n_s(n.trim().parse()?, s.trim().parse()?))
+}
+
+impl FromStr for Definition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        if let Some(inner) = s.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+            let operands: Vec<String> = inner.split(',').map(|x| x.trim().to_owned()).collect();
+            return Ok(Self::Massey(operands.try_into().map_err(|_| {
+                anyhow!("Massey product {s} should have three operands")
+            })?));
+        }
+        let (b, class) = match s.split_once('[') {
+            Some((b, class)) => (b, Some(class)),
+            None => (s, None),
+        };
+        let b = parse_bidegree(b)?;
+        let Some(class) = class else {
+            return Ok(Self::Indecomposable(b));
+        };
+        let class = class
+            .trim()
+            .strip_suffix(']')
+            .ok_or_else(|| anyhow!("Unterminated class in {s}"))?;
+        let class = class
+            .split(',')
+            .map(|x| x.trim().parse())
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("Invalid class in {s}"))?;
+        Ok(Self::Class(b, class))
+    }
+}
+
+/// The name of a basis generator of $\Ext$. See the [module-level documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassName {
+    Named(String),
+    /// The distinct monomials representing the generator, in order of preference.
+    Ambiguous(Vec<String>),
+    Undecomposable,
+}
+
+impl ClassName {
+    /// The preferred name of the generator, if any.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Named(name) => Some(name),
+            Self::Ambiguous(names) => Some(&names[0]),
+            Self::Undecomposable => None,
+        }
+    }
+}
+
+/// The names of the generators of $\Ext$ produced by [`ClassNamer::name_through_stem`].
+pub struct ClassNames {
+    /// The named bidegrees, in the order they were processed.
+    degrees: Vec<Bidegree>,
+    names: HashMap<Bidegree, Vec<ClassName>>,
+    warnings: Vec<String>,
+}
+
+impl ClassNames {
+    pub fn get(&self, g: BidegreeGenerator) -> Option<&ClassName> {
+        self.names.get(&g.degree())?.get(g.idx())
+    }
+
+    /// The preferred name of a generator, if any.
+    pub fn name(&self, g: BidegreeGenerator) -> Option<&str> {
+        self.get(g)?.name()
+    }
+
+    /// Iterate through all generators in the named range, in order of increasing filtration.
+    pub fn iter(&self) -> impl Iterator<Item = (BidegreeGenerator, &ClassName)> + '_ {
+        self.degrees.iter().flat_map(move |&b| {
+            self.names[&b]
+                .iter()
+                .enumerate()
+                .map(move |(i, name)| (BidegreeGenerator::new(b, i), name))
+        })
+    }
+
+    /// The generators represented by several distinct monomials.
+    pub fn ambiguous(&self) -> impl Iterator<Item = (BidegreeGenerator, &[String])> + '_ {
+        self.iter().filter_map(|(g, name)| match name {
+            ClassName::Ambiguous(names) => Some((g, names.as_slice())),
+            _ => None,
+        })
+    }
+
+    /// The generators that are not in the span of the products of named classes.
+    pub fn undecomposable(&self) -> impl Iterator<Item = BidegreeGenerator> + '_ {
+        self.iter()
+            .filter(|(_, name)| **name == ClassName::Undecomposable)
+            .map(|(g, _)| g)
+    }
+
+    /// Problems encountered when evaluating the definitions of the named classes, e.g. Massey
+    /// products with nonzero indeterminacy.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Label each bidegree of a chart with the names of its generators. `min` is the bidegree
+    /// placed at the origin of the chart, which is `(min_degree, 0)` for a chart of a resolution.
+    pub fn write_labels<B: Backend>(&self, g: &mut B, min: Bidegree) -> Result<(), B::Error> {
+        for &b in &self.degrees {
+            let names: Vec<&str> = self.names[&b].iter().filter_map(ClassName::name).collect();
+            if !names.is_empty() {
+                g.label(b - min, names.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A configurable list of named classes used to name the generators of $\Ext$. See the
+/// [module-level documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct ClassNamer {
+    indecomposables: Vec<(String, Definition)>,
+    cells: Vec<(String, Definition)>,
+}
+
+impl ClassNamer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The standard names of the indecomposables in low degrees. At $p = 2$ these are all the
+    /// indecomposables of $\Ext$ through the 20 stem, together with $h_5$. At odd primes these are
+    /// $a_0$, $h_0$, $b_0$, $h_1$, $b_1$ and $h_2$.
+    pub fn standard(p: ValidPrime) -> Self {
+        let mut namer = Self::new();
+        let mut add = |name: &str, n, s| {
+            namer.add_indecomposable(name, Definition::Indecomposable(Bidegree::n_s(n, s)));
+        };
+
+        if p == 2 {
+            for (name, n, s) in [
+                ("h_0", 0, 1),
+                ("h_1", 1, 1),
+                ("h_2", 3, 1),
+                ("h_3", 7, 1),
+                ("c_0", 8, 3),
+                ("Ph_1", 9, 5),
+                ("Ph_2", 11, 5),
+                ("d_0", 14, 4),
+                ("h_4", 15, 1),
+                ("Pc_0", 16, 7),
+                ("e_0", 17, 4),
+                ("P^2h_1", 17, 9),
+                ("f_0", 18, 4),
+                ("c_1", 19, 3),
+                ("P^2h_2", 19, 9),
+                ("g", 20, 4),
+                ("h_5", 31, 1),
+            ] {
+                add(name, n, s);
+            }
+        } else {
+            let p = p.as_i32();
+            let q = 2 * p - 2;
+            let mut classes = vec![(String::from("a_0"), 0, 1)];
+            for i in 0..3 {
+                classes.push((format!("h_{i}"), q * p.pow(i) - 1, 1));
+                if i < 2 {
+                    classes.push((format!("b_{i}"), q * p.pow(i + 1) - 2, 2));
+                }
+            }
+            classes.sort_by_key(|&(_, n, s)| (n, s));
+            for (name, n, s) in classes {
+                add(&name, n, s);
+            }
+        }
+        namer
+    }
+
+    /// Add a named indecomposable of $\Ext(k, k)$. Definitions may only refer to classes added
+    /// before them.
+    pub fn add_indecomposable(&mut self, name: impl Into<String>, definition: Definition) {
+        self.indecomposables.push((name.into(), definition));
+    }
+
+    /// Add a named class of $\Ext(M, k)$ that is not a product with an indecomposable. If no cells
+    /// are added, the generators of $\Ext^0(M, k)$ are named $x_{n,0}$, or $x_{n,0}^{(i)}$ if there
+    /// are several in degree $n$. This is ignored when `M == k`, where the only cell is $1$.
+    pub fn add_cell(&mut self, name: impl Into<String>, definition: Definition) {
+        self.cells.push((name.into(), definition));
+    }
+
+    /// Name the generators of `ext` in bidegrees with stem at most `max.n()` and filtration at most
+    /// `max.s()`. The resolution and unit must already be computed through this range.
+    ///
+    /// This returns an error if a definition refers to a class that has not been named, and records
+    /// a warning if a definition cannot be evaluated.
+    pub fn name_through_stem<CC>(
+        &self,
+        ext: &ExtAlgebra<CC>,
+        max: Bidegree,
+    ) -> anyhow::Result<ClassNames>
+    where
+        CC: FreeChainComplex + AugmentedChainComplex,
+    {
+        let unit_cells = vec![(String::from("1"), Bidegree::zero())];
+        let mut atoms = Vec::new();
+        let mut unit_pending = vec![Pending {
+            degree: Bidegree::zero(),
+            kind: Kind::Class(vec![1]),
+            target: Target::Cell(0),
+        }];
+        for (name, definition) in &self.indecomposables {
+            let parse = |m: &str| parse_monomial(m, &unit_cells, &atoms);
+            let pending = Pending::new(definition, Target::Atom(atoms.len()), parse, parse)
+                .with_context(|| format!("Invalid definition of {name}"))?;
+            if pending.degree.s() <= 0 {
+                return Err(anyhow!(
+                    "Indecomposable {name} must have positive filtration"
+                ));
+            }
+            atoms.push(Atom {
+                name: name.clone(),
+                degree: pending.degree,
+                class: None,
+            });
+            unit_pending.push(pending);
+        }
+
+        let mut warnings = Vec::new();
+        if ext.is_unit() {
+            let mut sweep = Sweep::new(ext, unit_cells, atoms);
+            sweep.run(max, &unit_pending, None, &mut warnings);
+            return Ok(sweep.into_names(warnings));
+        }
+
+        let unit_ext = ExtAlgebra::new(Arc::clone(ext.unit()), Arc::clone(ext.unit()));
+        let mut unit_sweep = Sweep::new(&unit_ext, unit_cells, atoms);
+        unit_sweep.run(max, &unit_pending, None, &mut warnings);
+
+        let mut cells = Vec::new();
+        let mut pending = Vec::new();
+        if self.cells.is_empty() {
+            let resolution = ext.resolution();
+            for n in resolution.min_degree()..=max.n() {
+                let b = Bidegree::n_s(n, 0);
+                if !resolution.has_computed_bidegree(b) {
+                    break;
+                }
+                let dim = ext.dimension(b);
+                for i in 0..dim {
+                    let name = if dim == 1 {
+                        format!("x_{{{n},0}}")
+                    } else {
+                        format!("x_{{{n},0}}^{{({i})}}")
+                    };
+                    let mut class = vec![0; dim];
+                    class[i] = 1;
+                    pending.push(Pending {
+                        degree: b,
+                        kind: Kind::Class(class),
+                        target: Target::Cell(cells.len()),
+                    });
+                    cells.push((name, b));
+                }
+            }
+        }
+        for (name, definition) in &self.cells {
+            let p = Pending::new(
+                definition,
+                Target::Cell(cells.len()),
+                |m| parse_monomial(m, &unit_sweep.cells, &unit_sweep.atoms),
+                |m| parse_monomial(m, &cells, &unit_sweep.atoms),
+            )
+            .with_context(|| format!("Invalid definition of {name}"))?;
+            cells.push((name.clone(), p.degree));
+            pending.push(p);
+        }
+
+        let mut sweep = Sweep::new(ext, cells, unit_sweep.atoms.clone());
+        sweep.run(max, &pending, Some(&unit_sweep.table), &mut warnings);
+        Ok(sweep.into_names(warnings))
+    }
+}
+
+impl FromStr for ClassNamer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut namer = Self::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, definition) = line.split_once('=').ok_or_else(|| {
+                anyhow!("Expected a definition of the form name = ..., found {line}")
+            })?;
+            let definition = definition.parse()?;
+            match name.trim().strip_prefix("cell ") {
+                Some(name) => namer.add_cell(name.trim(), definition),
+                None => namer.add_indecomposable(name.trim(), definition),
+            }
+        }
+        Ok(namer)
+    }
+}
+
+/// A product `cell · atom_{i_1}^{e_1} ⋯ atom_{i_k}^{e_k}` with `i_1 < ⋯ < i_k`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Monomial {
+    cell: usize,
+    powers: Vec<(usize, u32)>,
+}
+
+impl Monomial {
+    fn cell(cell: usize) -> Self {
+        Self {
+            cell,
+            powers: Vec::new(),
+        }
+    }
+
+    fn last(&self) -> Option<usize> {
+        self.powers.last().map(|&(i, _)| i)
+    }
+
+    /// The product with `atom`, which must be at least [`Monomial::last`].
+    fn times(&self, atom: usize) -> Self {
+        let mut result = self.clone();
+        match result.powers.last_mut() {
+            Some((i, e)) if *i == atom => *e += 1,
+            _ => result.powers.push((atom, 1)),
+        }
+        result
+    }
+
+    fn num_factors(&self) -> u32 {
+        self.powers.iter().map(|&(_, e)| e).sum()
+    }
+}
+
+/// Whether a name can be raised to a power without parentheses.
+fn is_simple(name: &str) -> bool {
+    let head = name.split('_').next().unwrap_or_default();
+    head.chars().count() == 1 && !name.contains(['^', ' ', '\\'])
+}
+
+#[derive(Clone)]
+struct Atom {
+    name: String,
+    degree: Bidegree,
+    /// The class in $\Ext(k, k)$, once it has been evaluated.
+    class: Option<BidegreeElement>,
+}
+
+/// Parse a monomial such as `h_0^2 h_3` in the given cells and atoms, returning the monomial and
+/// its degree.
+fn parse_monomial(
+    s: &str,
+    cells: &[(String, Bidegree)],
+    atoms: &[Atom],
+) -> anyhow::Result<(Monomial, Bidegree)> {
+    let mut cell = None;
+    let mut powers: Vec<(usize, u32)> = Vec::new();
+    for token in s.split_whitespace() {
+        let (name, exponent) = match token.rsplit_once('^').map(|(name, e)| {
+            let e = e.trim_start_matches('{').trim_end_matches('}');
+            (name, e.parse::<u32>())
+        }) {
+            Some((name, Ok(e))) => (name, e),
+            _ => (token, 1),
+        };
+        let name = name
+            .strip_prefix('(')
+            .and_then(|name| name.strip_suffix(')'))
+            .unwrap_or(name);
+
+        if let Some(i) = atoms.iter().position(|a| a.name == name) {
+            powers.push((i, exponent));
+        } else if let Some(i) = cells.iter().position(|(c, _)| c == name) {
+            if cell.is_some() || exponent != 1 {
+                return Err(anyhow!("Monomial {s} contains more than one cell"));
+            }
+            cell = Some(i);
+        } else {
+            return Err(anyhow!("Unknown class {name} in {s}"));
+        }
+    }
+    let cell = match cell {
+        Some(cell) => cell,
+        None if cells.first().is_some_and(|(c, _)| c == "1") => 0,
+        None => return Err(anyhow!("Monomial {s} does not contain a cell")),
+    };
+
+    powers.sort_unstable();
+    let mut monomial = Monomial::cell(cell);
+    let mut degree = cells[cell].1;
+    for (i, e) in powers {
+        for _ in 0..e {
+            monomial = monomial.times(i);
+            degree = degree + atoms[i].degree;
+        }
+    }
+    Ok((monomial, degree))
+}
+
+#[derive(Clone)]
+enum Kind {
+    Class(Vec<u32>),
+    Indecomposable,
+    Massey([Monomial; 3]),
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Cell(usize),
+    Atom(usize),
+}
+
+/// A definition whose degree has been computed, waiting to be evaluated.
+#[derive(Clone)]
+struct Pending {
+    degree: Bidegree,
+    kind: Kind,
+    target: Target,
+}
+
+impl Pending {
+    /// `parse_unit` and `parse` parse monomials in $\Ext(k, k)$ and $\Ext(M, k)$ respectively.
+    fn new(
+        definition: &Definition,
+        target: Target,
+        parse_unit: impl Fn(&str) -> anyhow::Result<(Monomial, Bidegree)>,
+        parse: impl Fn(&str) -> anyhow::Result<(Monomial, Bidegree)>,
+    ) -> anyhow::Result<Self> {
+        let (degree, kind) = match definition {
+            Definition::Class(b, class) => (*b, Kind::Class(class.clone())),
+            Definition::Indecomposable(b) => (*b, Kind::Indecomposable),
+            Definition::Massey([a, b, c]) => {
+                let (a, a_deg) = parse_unit(a)?;
+                let (b, b_deg) = parse_unit(b)?;
+                let (c, c_deg) = parse(c)?;
+                (
+                    a_deg + b_deg + c_deg - Bidegree::s_t(1, 0),
+                    Kind::Massey([a, b, c]),
+                )
+            }
+        };
+        Ok(Self {
+            degree,
+            kind,
+            target,
+        })
+    }
+}
+
+/// The state of the naming procedure over one [`ExtAlgebra`].
+struct Sweep<'a, CC: FreeChainComplex> {
+    ext: &'a ExtAlgebra<CC>,
+    cells: Vec<(String, Bidegree)>,
+    atoms: Vec<Atom>,
+    /// The nonzero monomials in each bidegree, together with their values.
+    table: HashMap<Bidegree, Vec<(Monomial, FpVector)>>,
+    degrees: Vec<Bidegree>,
+    names: HashMap<Bidegree, Vec<ClassName>>,
+}
+
+impl<'a, CC> Sweep<'a, CC>
+where
+    CC: FreeChainComplex + AugmentedChainComplex,
+{
+    fn new(ext: &'a ExtAlgebra<CC>, cells: Vec<(String, Bidegree)>, atoms: Vec<Atom>) -> Self {
+        Self {
+            ext,
+            cells,
+            atoms,
+            table: HashMap::new(),
+            degrees: Vec::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    fn format(&self, m: &Monomial) -> String {
+        let cell = &self.cells[m.cell].0;
+        let mut factors = Vec::new();
+        if cell != "1" || m.powers.is_empty() {
+            factors.push(cell.clone());
+        }
+        for &(i, e) in &m.powers {
+            let name = &self.atoms[i].name;
+            factors.push(match e {
+                1 => name.clone(),
+                2..10 if is_simple(name) => format!("{name}^{e}"),
+                _ if is_simple(name) => format!("{name}^{{{e}}}"),
+                _ => format!("({name})^{{{e}}}"),
+            });
+        }
+        factors.join(" ")
+    }
+
+    /// The value of a monomial, which is `None` if it is zero or out of range.
+    fn evaluate(
+        table: &HashMap<Bidegree, Vec<(Monomial, FpVector)>>,
+        m: &Monomial,
+        degree: Bidegree,
+    ) -> Option<BidegreeElement> {
+        let (_, v) = table.get(&degree)?.iter().find(|(x, _)| x == m)?;
+        Some(BidegreeElement::new(degree, v.clone()))
+    }
+
+    /// Name the generators in the given range. `unit_table` is the table of the sweep over the unit
+    /// when `M != k`, in which the first two operands of Massey products are evaluated.
+    fn run(
+        &mut self,
+        max: Bidegree,
+        pending: &[Pending],
+        unit_table: Option<&HashMap<Bidegree, Vec<(Monomial, FpVector)>>>,
+        warnings: &mut Vec<String>,
+    ) {
+        let p = self.ext.prime();
+        let mut degrees: Vec<Bidegree> = self
+            .ext
+            .resolution()
+            .iter_stem()
+            .filter(|b| b.n() <= max.n() && b.s() <= max.s())
+            .collect();
+        degrees.sort_by_key(|b| (b.s(), b.n()));
+
+        for b in degrees {
+            let dim = self.ext.dimension(b);
+            let mut candidates: Vec<(Monomial, FpVector)> = Vec::new();
+
+            for (i, atom) in self.atoms.iter().enumerate() {
+                let Some(class) = &atom.class else {
+                    continue;
+                };
+                let Some(sources) = self.table.get(&(b - atom.degree)) else {
+                    continue;
+                };
+                for (m, v) in sources {
+                    if m.last().is_some_and(|last| last > i) {
+                        continue;
+                    }
+                    let x = BidegreeElement::new(b - atom.degree, v.clone());
+                    let product = if self.ext.is_unit() {
+                        self.ext.try_multiply(class, &x)
+                    } else {
+                        self.ext.try_multiply(&x, class)
+                    };
+                    if let Some(product) = product
+                        && !product.vec().is_zero()
+                    {
+                        candidates.push((m.times(i), product.into_vec()));
+                    }
+                }
+            }
+
+            for definition in pending.iter().filter(|d| d.degree == b) {
+                let (monomial, name) = match definition.target {
+                    Target::Cell(i) => (Monomial::cell(i), &self.cells[i].0),
+                    Target::Atom(i) => (Monomial::cell(0).times(i), &self.atoms[i].name),
+                };
+                let value = match &definition.kind {
+                    Kind::Class(class) if class.len() == dim => Ok(FpVector::from_slice(p, class)),
+                    Kind::Class(class) => Err(format!(
+                        "{name}: expected a class of length {dim}, found {class:?}"
+                    )),
+                    Kind::Indecomposable => {
+                        let mut span = Subspace::new(p, dim);
+                        for (_, v) in &candidates {
+                            span.add_vector(v.as_slice());
+                        }
+                        if dim - span.dimension() > 1 {
+                            warnings.push(format!(
+                                "{name}: the indecomposables in {b} are {}-dimensional",
+                                dim - span.dimension()
+                            ));
+                        }
+                        (0..dim)
+                            .map(|i| BidegreeGenerator::new(b, i).into_element(p, dim))
+                            .find(|x| !span.contains(x.vec()))
+                            .map(BidegreeElement::into_vec)
+                            .ok_or_else(|| format!("{name}: every class in {b} is decomposable"))
+                    }
+                    Kind::Massey([x, y, z]) => {
+                        let unit_table = unit_table.unwrap_or(&self.table);
+                        let operand = |m: &Monomial, unit: bool| {
+                            let table = if unit { unit_table } else { &self.table };
+                            Self::evaluate(table, m, self.monomial_degree(m, unit))
+                                .ok_or_else(|| format!("{name}: an operand vanishes"))
+                        };
+                        operand(x, true)
+                            .and_then(|x| Ok((x, operand(y, true)?, operand(z, false)?)))
+                            .and_then(|(x, y, z)| {
+                                self.ext.massey(&x, &y, &z).ok_or_else(|| {
+                                    format!("{name}: the Massey product is not defined")
+                                })
+                            })
+                            .and_then(|result| {
+                                if result.contains_zero() {
+                                    return Err(format!(
+                                        "{name}: the Massey product contains zero"
+                                    ));
+                                }
+                                if !result.coset.linear_part().is_empty() {
+                                    warnings.push(format!(
+                                        "{name}: the Massey product has nonzero indeterminacy"
+                                    ));
+                                }
+                                Ok(result.representative().into_vec())
+                            })
+                    }
+                };
+
+                match value {
+                    Ok(v) => {
+                        if let Target::Atom(i) = definition.target {
+                            self.atoms[i].class = Some(BidegreeElement::new(b, v.clone()));
+                        }
+                        candidates.push((monomial, v));
+                    }
+                    Err(warning) => warnings.push(warning),
+                }
+            }
+
+            let names = self.name_generators(p, dim, &candidates);
+            self.table.insert(b, candidates);
+            self.names.insert(b, names);
+            self.degrees.push(b);
+        }
+    }
+
+    fn monomial_degree(&self, m: &Monomial, unit: bool) -> Bidegree {
+        let mut degree = if unit {
+            Bidegree::zero()
+        } else {
+            self.cells[m.cell].1
+        };
+        for &(i, e) in &m.powers {
+            for _ in 0..e {
+                degree = degree + self.atoms[i].degree;
+            }
+        }
+        degree
+    }
+
+    fn name_generators(
+        &self,
+        p: ValidPrime,
+        dim: usize,
+        candidates: &[(Monomial, FpVector)],
+    ) -> Vec<ClassName> {
+        let scaled = |c: u32, name: String| {
+            if c == 1 { name } else { format!("{c} {name}") }
+        };
+
+        // Prefer monomials with fewer factors, and then those found first.
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by_key(|&i| candidates[i].0.num_factors());
+
+        // A maximal linearly independent subset of the candidates, with the change of basis to
+        // the reduced form recorded in the last columns.
+        let mut span = Subspace::new(p, dim);
+        let basis: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&i| {
+                let rank = span.dimension();
+                span.add_vector(candidates[i].1.as_slice()) > rank
+            })
+            .collect();
+        let mut matrix = Matrix::new(p, basis.len(), dim + basis.len());
+        for (row, &i) in basis.iter().enumerate() {
+            matrix
+                .row_mut(row)
+                .slice_mut(0, dim)
+                .add(candidates[i].1.as_slice(), 1);
+            matrix.row_mut(row).set_entry(dim + row, 1);
+        }
+        matrix.row_reduce();
+
+        (0..dim)
+            .map(|idx| {
+                let matches: Vec<String> = order
+                    .iter()
+                    .filter_map(|&i| {
+                        let (m, v) = &candidates[i];
+                        let mut nonzero = v.iter_nonzero();
+                        match (nonzero.next(), nonzero.next()) {
+                            (Some((j, c)), None) if j == idx => {
+                                Some(scaled(inverse(p, c), self.format(m)))
+                            }
+                            _ => None,
+                        }
+                    })
+                    .collect();
+                match matches.len() {
+                    0 => {}
+                    1 => return ClassName::Named(matches.into_iter().next().unwrap()),
+                    _ => return ClassName::Ambiguous(matches),
+                }
+
+                let mut v = FpVector::new(p, dim + basis.len());
+                v.set_entry(idx, 1);
+                for col in 0..dim {
+                    let c = v.entry(col);
+                    if c == 0 {
+                        continue;
+                    }
+                    let Ok(row) = usize::try_from(matrix.pivots()[col]) else {
+                        return ClassName::Undecomposable;
+                    };
+                    v.as_slice_mut().add(matrix.row(row), p - c);
+                }
+                let terms: Vec<String> = (0..basis.len())
+                    .filter_map(|row| {
+                        let c = (p - v.entry(dim + row)) % p;
+                        (c != 0).then(|| scaled(c, self.format(&candidates[basis[row]].0)))
+                    })
+                    .collect();
+                ClassName::Named(terms.join(" + "))
+            })
+            .collect()
+    }
+
+    fn into_names(self, warnings: Vec<String>) -> ClassNames {
+        ClassNames {
+            degrees: self.degrees,
+            names: self.names,
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CCC, resolution::Resolution, utils::construct_standard};
+
+    fn sphere(max: Bidegree) -> ExtAlgebra<Resolution<CCC>> {
+        let res = Arc::new(construct_standard::<false, _, _>("S_2", None).unwrap());
+        res.compute_through_stem(max);
+        ExtAlgebra::new(Arc::clone(&res), res)
+    }
+
+    fn name(names: &ClassNames, n: i32, s: i32, idx: usize) -> Option<&ClassName> {
+        names.get(BidegreeGenerator::new(Bidegree::n_s(n, s), idx))
+    }
+
+    #[test]
+    fn test_standard_names() {
+        let max = Bidegree::n_s(20, 11);
+        let ext = sphere(max);
+        let names = ClassNamer::standard(ext.prime())
+            .name_through_stem(&ext, max)
+            .unwrap();
+
+        assert!(names.warnings().is_empty(), "{:?}", names.warnings());
+        assert_eq!(names.undecomposable().collect::<Vec<_>>(), vec![]);
+
+        let named = |n: &str| ClassName::Named(String::from(n));
+        assert_eq!(name(&names, 0, 0, 0), Some(&named("1")));
+        assert_eq!(name(&names, 0, 2, 0), Some(&named("h_0^2")));
+        assert_eq!(name(&names, 8, 3, 0), Some(&named("c_0")));
+        assert_eq!(name(&names, 9, 5, 0), Some(&named("Ph_1")));
+        assert_eq!(name(&names, 14, 2, 0), Some(&named("h_3^2")));
+        assert_eq!(name(&names, 9, 4, 0), Some(&named("h_1 c_0")));
+        assert_eq!(name(&names, 15, 8, 0), Some(&named("h_0^7 h_4")));
+
+        let Some(ClassName::Ambiguous(h1_cubed)) = name(&names, 3, 3, 0) else {
+            panic!("h_1^3 = h_0^2 h_2 should be ambiguous");
+        };
+        assert_eq!(h1_cubed.len(), 2);
+        assert!(h1_cubed.contains(&String::from("h_1^3")));
+        assert!(h1_cubed.contains(&String::from("h_0^2 h_2")));
+    }
+
+    #[test]
+    fn test_massey_definition() {
+        let max = Bidegree::n_s(10, 5);
+        let ext = sphere(max);
+        let namer: ClassNamer = "h_0 = (0, 1)\nh_1 = (1, 1)\nh_2 = (3, 1)\nc_0 = <h_1, h_0, h_2^2>"
+            .parse()
+            .unwrap();
+        let names = namer.name_through_stem(&ext, max).unwrap();
+
+        assert!(names.warnings().is_empty(), "{:?}", names.warnings());
+        assert_eq!(
+            names.name(BidegreeGenerator::new(Bidegree::n_s(8, 3), 0)),
+            Some("c_0")
+        );
+        assert_eq!(
+            names.name(BidegreeGenerator::new(Bidegree::n_s(9, 4), 0)),
+            Some("h_1 c_0")
+        );
+        // h_3 is not in the list.
+        assert_eq!(
+            names.undecomposable().next(),
+            Some(BidegreeGenerator::new(Bidegree::n_s(7, 1), 0))
+        );
+    }
+
+    #[test]
+    fn test_module_names() {
+        let max = Bidegree::n_s(8, 4);
+        let unit = Arc::new(construct_standard::<false, _, _>("S_2", None).unwrap());
+        unit.compute_through_stem(max);
+        let res = Arc::new(construct_standard::<false, _, _>("C2", None).unwrap());
+        res.compute_through_stem(max);
+        let ext = ExtAlgebra::new(res, unit);
+
+        let names = ClassNamer::standard(ext.prime())
+            .name_through_stem(&ext, max)
+            .unwrap();
+        assert_eq!(
+            names.name(BidegreeGenerator::new(Bidegree::n_s(0, 0), 0)),
+            Some("x_{0,0}")
+        );
+        assert_eq!(
+            names.name(BidegreeGenerator::new(Bidegree::n_s(2, 2), 0)),
+            Some("x_{0,0} h_1^2")
+        );
+        // v_1 is not a product with x_{0,0}
+        assert_eq!(
+            names.get(BidegreeGenerator::new(Bidegree::n_s(2, 1), 0)),
+            Some(&ClassName::Undecomposable)
+        );
+    }
+}
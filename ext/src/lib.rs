@@ -45,7 +45,8 @@
 //! ## Prompts and arguments
 //! Each prompt asks the user for an input, which is submitted by pressing the Enter/Return key. If
 //! the input is invalid, an error message is produced and the user is queried for the same input
-//! again. To exit the program early, one sends a `SIGTERM`, e.g. via `Ctrl-C`.
+//! again. To exit the program early, one sends a `SIGTERM`, e.g. via `Ctrl-C`. Some examples stop
+//! gracefully on the first `Ctrl-C` so that they can be resumed later; see [`checkpoint`].
 //!
 //! Some prompts are optional or have default values. To select the `None` or default option, simply
 //! supply an empty input.
@@ -75,6 +76,23 @@
 //! `Ceta`. It is possible to apply a degree shift to the module without having to define a new one.
 //! For example, to shift `Ceta` by one, we supply `Ceta[1]`.
 //!
+//! Finite dimensional modules can also be combined into new ones. A module specification can be
+//! an expression involving
+//!
+//!  - smash products `C2 ^ Ceta`;
+//!  - direct sums `C2 + Ceta`;
+//!  - Spanier–Whitehead duals `D(Joker)`, computed using the antipode;
+//!  - shifts `S_2[3]`,
+//!
+//! with parentheses for grouping, e.g. `(C2 ^ D(Ceta))[2] + S_2`. Smash products bind more tightly
+//! than direct sums. The operands of smash products, direct sums and duals must be finite
+//! dimensional; for example `D(RP_-4_inf)` is an error.
+//!
+//! As a consequence, the characters `^`, `+`, `(`, `)`, `[`, `]` and whitespace end a module file
+//! name, so module files whose names contain them cannot be loaded. Moreover, a specification
+//! starting with `D(` is always read as a dual, even if there is a module file whose name starts
+//! with `D(`.
+//!
 //! When resolving a module, we have to pick a basis of the Steenrod algebra, which is either the
 //! Adem basis or the Milnor basis. The default choice is the Milnor basis. We can specify the basis
 //! by appending `@basis_name`. For example, if we want to resolve `Ceta[1]` with the Adem basis, we
@@ -119,6 +137,7 @@
 //! | [bruner](../bruner/index.html) | Compare our basis with Bruner's. |
 //! | [define_module](../define_module/index.html) | Interactively define a Steenrod module. |
 //! | [differentials](../differentials/index.html) | Print all differentials in the minimal resolution. |
+//! | [distributed](../distributed/index.html) | Resolve a module to a fixed $(s, n)$ using multiple processes sharing a save directory. |
 //! | [filtration_one](../filtration_one/index.html) | Print all filtration one products. |
 //! | [lift_hom](../lift_hom/index.html) | Compute the map $\Ext(N, k) \to \Ext(M, k)$ induced by an element in $\Ext(M, N)$. |
 //! | [mahowald_invariant](../mahowald_invariant/index.html) | Compute (algebraic) Mahowald invariants. |
@@ -167,8 +186,13 @@
 #![allow(clippy::upper_case_acronyms)]
 #![deny(clippy::use_self, unsafe_op_in_unsafe_fn)]
 
+pub mod bruner;
 pub mod chain_complex;
+pub mod checkpoint;
+pub mod distributed;
 pub mod ext_algebra;
+pub mod may;
+pub mod progress;
 pub mod resolution;
 pub mod resolution_homomorphism;
 pub mod save;
@@ -0,0 +1,722 @@
+//! The May spectral sequence at the prime 2.
+//!
+//! Give the monomial $\xi_1^{r_1} \xi_2^{r_2} \cdots$ of the dual Steenrod algebra the weight
+//! $\sum_i (2i - 1) \alpha(r_i)$, where $\alpha(r)$ is the number of ones in the binary expansion
+//! of $r$. In other words, $\xi_i^{2^j}$ has weight $2i - 1$, and weights are additive. This is the
+//! filtration of the Milnor basis of [Ravenel, Theorem 3.2.5], and its associated graded $E^0
+//! A_*$ is primitively generated. The weight of $[a_1 | \cdots | a_s]$ is the sum of the weights
+//! of the $a_i$, and this makes the cobar complex of $A_*$ a filtered complex whose differential
+//! does not increase the weight. The resulting spectral sequence is the May spectral sequence
+//! $$ E_1 = \mathrm{Ext}_{E^0 A_*}(\mathbb{F}_2, \mathbb{F}_2) = \mathbb{F}_2[h_{ij} : i \geq 1, j
+//! \geq 0] \Rightarrow \mathrm{Ext}_{A_*}(\mathbb{F}_2, \mathbb{F}_2). $$
+//! The class $h_{ij}$ is represented by $[\xi_i^{2^j}]$. It has stem $2^j (2^i - 1) - 1$, Adams
+//! filtration $1$ and May filtration $i$. As usual, a $d_r$ increases the Adams filtration by $1$
+//! and changes the May filtration by $1 - r$, so that for example $d_1(h_{ij}) = \sum_{0 < k < i}
+//! h_{k, i + j - k} h_{i - k, j}$ and $d_2(b_{20}) = h_{11}^3 + h_{10}^2 h_{12}$, where $b_{ij} =
+//! h_{ij}^2$. In terms of the weight, a class with Adams filtration $s$ and May filtration $u$ has
+//! weight $2u - s$, and a $d_r$ lowers the weight by $2r - 1$.
+//!
+//! We compute the spectral sequence of the filtered complex directly from the (dual of the)
+//! [`cobar_complex`], so every differential is determined and there are no choices to make. The
+//! $E_1$ page has the monomials in the $h_{ij}$ as a basis, each represented by the
+//! concatenation of its factors. The result is an [`Sseq`] with the [`May`] profile, whose
+//! coordinates are the stem, the Adams filtration and the May filtration.
+//!
+//! Finally, a permanent class is represented by a cocycle of the cobar complex. By lifting the
+//! identity of $\mathbb{F}_2$ to a chain map from a minimal resolution to the bar resolution, we
+//! can evaluate these cocycles on the generators of a [`MuResolution`](crate::resolution::MuResolution) over
+//! the Milnor algebra. This identifies the classes in $E_\infty$ with elements of $\mathrm{Ext}$,
+//! modulo elements of lower May filtration. In particular, every generator of $\mathrm{Ext}$ is
+//! detected by a class in $E_\infty$, which gives the generator its name in the literature.
+//!
+//! The cobar complex is large, so this is only practical in low degrees.
+
+use std::{collections::HashMap, fmt::Write as _, sync::Arc};
+
+use algebra::{
+    Algebra, MilnorAlgebra, SteenrodAlgebra,
+    comodule::Comodule,
+    milnor_algebra::MilnorBasisElement,
+    module::{FDModule, FreeModule, Module, homomorphism::ModuleHomomorphism},
+};
+use anyhow::{anyhow, ensure};
+use bivec::BiVec;
+use fp::{
+    matrix::{AugmentedMatrix, Matrix, Subspace},
+    prime::TWO,
+    vector::{FpSlice, FpVector},
+};
+use itertools::Itertools;
+use sseq::{
+    May, Sseq, SseqProfile,
+    coordinates::{Bidegree, BidegreeElement, BidegreeGenerator, MultiDegree, MultiDegreeElement},
+};
+
+use crate::chain_complex::{ChainComplex, CobarComplex, FreeChainComplex, cobar_complex};
+
+/// A monomial in the $h_{ij}$, which is a basis element of the $E_1$ page.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MayMonomial {
+    /// The factors $(i, j)$ in increasing order, repeated according to their multiplicity.
+    factors: Vec<(u32, u32)>,
+}
+
+impl MayMonomial {
+    pub fn factors(&self) -> &[(u32, u32)] {
+        &self.factors
+    }
+
+    /// The degree of the monomial in the [`May`] grading, i.e. the stem, Adams filtration and May
+    /// filtration.
+    pub fn degree(&self) -> MultiDegree<3> {
+        self.factors
+            .iter()
+            .fold(MultiDegree::zero(), |acc, &(i, j)| {
+                acc + MultiDegree::new([h_degree(i, j) - 1, 1, i as i32])
+            })
+    }
+}
+
+impl std::fmt::Display for MayMonomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+        let terms = self.factors.iter().dedup_with_count().map(|(k, &(i, j))| {
+            let mut term = if i < 10 && j < 10 {
+                format!("h_{{{i}{j}}}")
+            } else {
+                format!("h_{{{i},{j}}}")
+            };
+            if k > 1 {
+                write!(term, "^{k}").unwrap();
+            }
+            term
+        });
+        write!(f, "{}", terms.format(" "))
+    }
+}
+
+/// The internal degree of $h_{ij}$.
+fn h_degree(i: u32, j: u32) -> i32 {
+    (1 << j) * ((1 << i) - 1)
+}
+
+/// The weight of a cochain in the cobar complex whose class in $E_1$ lies in degree `b`.
+fn cobar_weight(b: MultiDegree<3>) -> i32 {
+    2 * b.coords()[2] - b.s()
+}
+
+/// The weight of the element of $A_*$ dual to a Milnor basis element.
+fn weight(elt: &MilnorBasisElement) -> i32 {
+    elt.p_part
+        .iter()
+        .enumerate()
+        .map(|(i, &r)| (2 * i as i32 + 1) * r.count_ones() as i32)
+        .sum()
+}
+
+/// The data needed to express a cocycle of $E_0$ in terms of the monomial basis of $E_1$ in a
+/// fixed degree.
+struct E1Reducer {
+    /// The basis elements of the cobar complex of the given weight.
+    coords: Vec<usize>,
+    /// The span of the coboundaries and the representatives of the monomials. Each vector is
+    /// written in the basis `coords`, followed by the coefficients of the monomials.
+    subspace: Subspace,
+}
+
+impl E1Reducer {
+    /// The class of the leading term of `cocycle` in $E_1$.
+    fn reduce(&self, cocycle: FpSlice) -> FpVector {
+        let len = self.coords.len();
+        let mut v = FpVector::new(TWO, self.subspace.ambient_dimension());
+        for (k, &i) in self.coords.iter().enumerate() {
+            v.set_entry(k, cocycle.entry(i));
+        }
+        self.subspace.reduce(v.as_slice_mut());
+        assert!(
+            v.slice(0, len).is_zero(),
+            "The monomials in the h_ij do not span E_1"
+        );
+        v.slice(len, v.len()).to_owned()
+    }
+}
+
+/// The May spectral sequence, computed in a range. See the [module level documentation](self)
+/// for details.
+pub struct MaySpectralSequence {
+    max: Bidegree,
+    algebra: Arc<SteenrodAlgebra>,
+    cobar: CobarComplex,
+    /// `weights[s][t]` lists the weights of the basis elements of $C^{s, t}$.
+    weights: Vec<Vec<Vec<i32>>>,
+    /// `coboundaries[s][t]` is the differential $C^{s, t} \to C^{s + 1, t}$ of the cobar complex.
+    /// This is the transpose of the differential of the bar complex.
+    coboundaries: Vec<Vec<Matrix>>,
+    e1: HashMap<MultiDegree<3>, Vec<MayMonomial>>,
+    /// A basis of the permanent classes in each degree, with cocycles representing them.
+    permanent: HashMap<MultiDegree<3>, Vec<(FpVector, FpVector)>>,
+    sseq: Sseq<3, May>,
+}
+
+impl MaySpectralSequence {
+    /// Computes the May spectral sequence in all bidegrees $(s, t)$ with $s \leq$ `max.s()` and $t
+    /// \leq$ `max.t()`. The $E_1$ page is also computed with $s =$ `max.s() + 1`, since it receives
+    /// differentials from the range.
+    pub fn new(max: Bidegree) -> Self {
+        let max_s = max.s() as usize;
+        let max_t = max.t();
+
+        let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+            TWO, false,
+        )));
+        let sphere = FDModule::new(
+            Arc::clone(&algebra),
+            String::from("F_2"),
+            BiVec::from_vec(0, vec![1]),
+        );
+        let comodule = Comodule::from_dual(sphere).unwrap();
+        let cobar = cobar_complex(&comodule, max_s as u32, max_t);
+
+        let mut may = Self {
+            max,
+            algebra,
+            cobar,
+            weights: Vec::new(),
+            coboundaries: Vec::new(),
+            e1: HashMap::new(),
+            permanent: HashMap::new(),
+            sseq: Sseq::new(TWO),
+        };
+        may.compute_weights();
+        may.compute_coboundaries();
+        may.compute_e1();
+
+        for s in 0..=max_s as i32 + 1 {
+            for t in 0..=max_t {
+                for u in 0..=t {
+                    let b = MultiDegree::new([t - s, s, u]);
+                    may.sseq
+                        .set_dimension(b, may.e1.get(&b).map_or(0, Vec::len));
+                }
+            }
+        }
+
+        let mut reducers = HashMap::new();
+        let mut degrees = may.e1.keys().copied().collect::<Vec<_>>();
+        degrees.sort_unstable_by_key(|b| (b.s(), b.t(), b.coords()[2]));
+        for b in degrees {
+            if b.s() <= max_s as i32 {
+                may.compute_differentials(b, &mut reducers);
+            }
+        }
+        may.sseq.update();
+        may
+    }
+
+    pub fn max(&self) -> Bidegree {
+        self.max
+    }
+
+    pub fn sseq(&self) -> &Sseq<3, May> {
+        &self.sseq
+    }
+
+    pub fn into_sseq(self) -> Sseq<3, May> {
+        self.sseq
+    }
+
+    /// The basis of the $E_1$ page in degree `b`.
+    pub fn e1_basis(&self, b: MultiDegree<3>) -> &[MayMonomial] {
+        self.e1.get(&b).map_or(&[], Vec::as_slice)
+    }
+
+    /// Writes an element of the $E_1$ page as a sum of monomials.
+    pub fn element_to_string(&self, b: MultiDegree<3>, v: FpSlice) -> String {
+        let basis = self.e1_basis(b);
+        if v.is_zero() {
+            return String::from("0");
+        }
+        v.iter_nonzero()
+            .map(|(i, _)| basis[i].to_string())
+            .join(" + ")
+    }
+
+    fn milnor(&self) -> &MilnorAlgebra {
+        let SteenrodAlgebra::MilnorAlgebra(milnor) = &*self.algebra else {
+            unreachable!()
+        };
+        milnor
+    }
+
+    fn bar_dimension(&self, s: usize, t: i32) -> usize {
+        if t < 0 {
+            return 0;
+        }
+        self.cobar.module(s as i32).dimension(t)
+    }
+
+    /// The index of $[a | x]$ in $B_{s, t}$, where $a$ has degree $n$. This follows the layout
+    /// used by [`cobar_complex`].
+    fn bar_index(&self, s: usize, t: i32, n: i32, a: usize, x: usize) -> usize {
+        let offset: usize = (1..n)
+            .map(|m| self.algebra.dimension(m) * self.bar_dimension(s - 1, t - m))
+            .sum();
+        offset + a * self.bar_dimension(s - 1, t - n) + x
+    }
+
+    fn compute_weights(&mut self) {
+        let max_t = self.max.t();
+        let mut weights = vec![(0..=max_t).map(|t| vec![0; (t == 0) as usize]).collect()];
+        for s in 1..=self.max.s() as usize + 1 {
+            let previous: &Vec<Vec<i32>> = &weights[s - 1];
+            let row = (0..=max_t)
+                .map(|t| {
+                    let mut result = Vec::with_capacity(self.bar_dimension(s, t));
+                    for n in 1..=t {
+                        for a in 0..self.algebra.dimension(n) {
+                            let w = weight(self.milnor().basis_element_from_index(n, a));
+                            result.extend(previous[(t - n) as usize].iter().map(|x| w + x));
+                        }
+                    }
+                    assert_eq!(result.len(), self.bar_dimension(s, t));
+                    result
+                })
+                .collect();
+            weights.push(row);
+        }
+        self.weights = weights;
+    }
+
+    fn compute_coboundaries(&mut self) {
+        self.coboundaries = (0..=self.max.s() as usize)
+            .map(|s| {
+                let d = self.cobar.differential(s as i32 + 1);
+                (0..=self.max.t())
+                    .map(|t| {
+                        let source_dim = self.bar_dimension(s + 1, t);
+                        let target_dim = self.bar_dimension(s, t);
+                        let mut matrix = Matrix::new(TWO, source_dim, target_dim);
+                        d.get_matrix(matrix.as_slice_mut(), t);
+
+                        let mut transpose = Matrix::new(TWO, target_dim, source_dim);
+                        for (i, row) in matrix.iter().enumerate() {
+                            for (j, c) in row.iter_nonzero() {
+                                transpose.row_mut(j).set_entry(i, c);
+                            }
+                        }
+                        transpose
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    fn compute_e1(&mut self) {
+        let max_s = self.max.s() as usize + 1;
+        let max_t = self.max.t();
+        let generators = (1..)
+            .take_while(|&i| h_degree(i, 0) <= max_t)
+            .flat_map(|i| {
+                (0..)
+                    .take_while(move |&j| h_degree(i, j) <= max_t)
+                    .map(move |j| (i, j))
+            })
+            .sorted()
+            .collect::<Vec<_>>();
+
+        // Enumerate the monomials as non-decreasing sequences of generators.
+        let mut stack = vec![(0, MayMonomial { factors: vec![] })];
+        while let Some((start, monomial)) = stack.pop() {
+            let degree = monomial.degree();
+            for (k, &(i, j)) in generators.iter().enumerate().skip(start) {
+                if (degree.s() as usize) < max_s && degree.t() + h_degree(i, j) <= max_t {
+                    let mut factors = monomial.factors.clone();
+                    factors.push((i, j));
+                    stack.push((k, MayMonomial { factors }));
+                }
+            }
+            self.e1.entry(degree).or_default().push(monomial);
+        }
+        for basis in self.e1.values_mut() {
+            basis.sort_unstable();
+        }
+    }
+
+    /// The cocycle of the cobar complex representing a monomial.
+    fn monomial_representative(&self, monomial: &MayMonomial) -> FpVector {
+        let t = monomial.degree().t();
+        let mut index = 0;
+        let mut degree = 0;
+        let len = monomial.factors.len();
+        for (k, &(i, j)) in monomial.factors.iter().enumerate().rev() {
+            let n = h_degree(i, j);
+            let mut p_part = vec![0; i as usize];
+            p_part[i as usize - 1] = 1 << j;
+            let a = self.milnor().basis_element_to_index(&MilnorBasisElement {
+                q_part: 0,
+                p_part,
+                degree: n,
+            });
+            degree += n;
+            index = self.bar_index(len - k, degree, n, a, index);
+        }
+        let mut result = FpVector::new(TWO, self.bar_dimension(len, t));
+        result.set_entry(index, 1);
+        result
+    }
+
+    fn e1_reducer(&self, b: MultiDegree<3>) -> E1Reducer {
+        let (s, t, w) = (b.s() as usize, b.t(), cobar_weight(b));
+        let basis = self.e1_basis(b);
+        let coords = self.weights[s][t as usize]
+            .iter()
+            .positions(|&x| x == w)
+            .collect::<Vec<_>>();
+        let len = coords.len();
+
+        let mut rows = Vec::new();
+        if s > 0 {
+            let coboundary = &self.coboundaries[s - 1][t as usize];
+            for (i, &x) in self.weights[s - 1][t as usize].iter().enumerate() {
+                if x == w {
+                    let mut row = FpVector::new(TWO, len + basis.len());
+                    for (k, &j) in coords.iter().enumerate() {
+                        row.set_entry(k, coboundary.row(i).entry(j));
+                    }
+                    rows.push(row);
+                }
+            }
+        }
+        for (m, monomial) in basis.iter().enumerate() {
+            let representative = self.monomial_representative(monomial);
+            let mut row = FpVector::new(TWO, len + basis.len());
+            for (k, &j) in coords.iter().enumerate() {
+                row.set_entry(k, representative.entry(j));
+            }
+            row.set_entry(len + m, 1);
+            rows.push(row);
+        }
+        let subspace = Subspace::from_matrix(Matrix::from_rows(TWO, rows, len + basis.len()));
+        assert!(
+            subspace.pivots()[len..].iter().all(|&x| x < 0),
+            "The monomials in the h_ij are linearly dependent in E_1 at {b}"
+        );
+        E1Reducer { coords, subspace }
+    }
+
+    /// Computes all differentials originating from `b`.
+    ///
+    /// Let $w$ be the weight of `b`, and write $Z_k = \{c \in F_w C^{s, t} : dc \in F_{w - k}\}$.
+    /// We row reduce the differential on $F_w C^{s, t}$, with the columns ordered by decreasing
+    /// weight. If the first nonzero entry of the image of a row has weight $w - k$, we say the row
+    /// has level $k$. Then $Z_k$ is spanned by the rows of level at least $k$, and its image in
+    /// $E_1$ consists of the classes that survive until the differential that lowers the weight by
+    /// $k$, namely $d_r$ with $k = 2r - 1$. The rows of level exactly $k$ whose leading terms are
+    /// not in the image of $Z_{k + 1}$ support these differentials.
+    fn compute_differentials(
+        &mut self,
+        b: MultiDegree<3>,
+        reducers: &mut HashMap<MultiDegree<3>, E1Reducer>,
+    ) {
+        let (s, t, w) = (b.s() as usize, b.t() as usize, cobar_weight(b));
+        let dim = self.e1_basis(b).len();
+        let reducer = reducers.remove(&b).unwrap_or_else(|| self.e1_reducer(b));
+
+        let source = self.weights[s][t]
+            .iter()
+            .positions(|&x| x <= w)
+            .collect::<Vec<_>>();
+        let target_weights = &self.weights[s + 1][t];
+        let columns = (0..target_weights.len())
+            .sorted_by_key(|&j| std::cmp::Reverse(target_weights[j]))
+            .collect::<Vec<_>>();
+        let mut column_position = vec![0; columns.len()];
+        for (k, &j) in columns.iter().enumerate() {
+            column_position[j] = k;
+        }
+
+        let coboundary = &self.coboundaries[s][t];
+        let mut matrix =
+            AugmentedMatrix::<2>::new(TWO, source.len(), [columns.len(), source.len()]);
+        for (k, &i) in source.iter().enumerate() {
+            let mut row = matrix.row_segment_mut(k, 0, 0);
+            for (j, c) in coboundary.row(i).iter_nonzero() {
+                row.set_entry(column_position[j], c);
+            }
+        }
+        matrix.segment(1, 1).add_identity();
+        matrix.row_reduce();
+
+        // The rows of positive level, sorted by decreasing level. The level of a cocycle is
+        // `i32::MAX`.
+        let mut rows = Vec::new();
+        for k in 0..source.len() {
+            let level = match matrix.row_segment(k, 0, 0).first_nonzero() {
+                Some((j, _)) => w - target_weights[columns[j]],
+                None => i32::MAX,
+            };
+            if level <= 0 {
+                continue;
+            }
+            let mut cochain = FpVector::new(TWO, self.weights[s][t].len());
+            for (i, c) in matrix.row_segment(k, 1, 1).iter_nonzero() {
+                cochain.set_entry(source[i], c);
+            }
+            rows.push((level, cochain));
+        }
+        rows.sort_by_key(|&(level, _)| std::cmp::Reverse(level));
+
+        let mut span = Subspace::new(TWO, dim);
+        let mut permanent = Vec::new();
+        for (level, cochain) in rows {
+            let v = reducer.reduce(cochain.as_slice());
+            if span.contains(v.as_slice()) {
+                continue;
+            }
+            span.add_vector(v.as_slice());
+            let elem = MultiDegreeElement::new(b, v.clone());
+            if level == i32::MAX {
+                self.sseq.add_permanent_class(&elem);
+                permanent.push((v, cochain));
+                continue;
+            }
+
+            // A d_r lowers the weight by 2r - 1.
+            assert_eq!(level % 2, 1, "Differential of even length at {b}");
+            let r = (level + 1) / 2;
+            let target_b = May::profile(r, b);
+            let target_reducer = reducers
+                .entry(target_b)
+                .or_insert_with(|| self.e1_reducer(target_b));
+            let mut dc = FpVector::new(TWO, target_weights.len());
+            for (i, c) in cochain.iter_nonzero() {
+                dc.as_slice_mut().add(coboundary.row(i), c);
+            }
+            let target = target_reducer.reduce(dc.as_slice());
+            debug_assert!(!target.is_zero());
+            self.sseq.add_differential(r, &elem, target.as_slice());
+        }
+        assert_eq!(
+            span.dimension(),
+            dim,
+            "Z_1 does not surject onto E_1 at {b}"
+        );
+
+        self.permanent.insert(b, permanent);
+        reducers.insert(b, reducer);
+    }
+
+    /// A cocycle of the cobar complex representing a permanent class.
+    fn cocycle(&self, elem: &MultiDegreeElement<3>) -> Option<FpVector> {
+        let permanent = self.permanent.get(&elem.degree())?;
+        let dim = elem.vec().len();
+        let rows = permanent
+            .iter()
+            .enumerate()
+            .map(|(k, (v, _))| {
+                let mut row = FpVector::new(TWO, dim + permanent.len());
+                row.slice_mut(0, dim).assign(v.as_slice());
+                row.set_entry(dim + k, 1);
+                row
+            })
+            .collect();
+        let span = Subspace::from_matrix(Matrix::from_rows(TWO, rows, dim + permanent.len()));
+
+        let mut v = FpVector::new(TWO, dim + permanent.len());
+        v.slice_mut(0, dim).assign(elem.vec());
+        span.reduce(v.as_slice_mut());
+        if !v.slice(0, dim).is_zero() {
+            return None;
+        }
+        let b = elem.degree();
+        let mut result = FpVector::new(TWO, self.weights[b.s() as usize][b.t() as usize].len());
+        for (k, c) in v.slice(dim, dim + permanent.len()).iter_nonzero() {
+            result.add(&permanent[k].1, c);
+        }
+        Some(result)
+    }
+
+    /// For each generator of the resolution in bidegree $(s, t)$, the element $\phi(g) \in B_{s,
+    /// t}$ of the bar complex, where $\phi$ is a chain map lifting the identity. Cocycles of the
+    /// cobar complex are evaluated on these to get the corresponding elements of $\mathrm{Ext}$.
+    fn bar_representatives<CC: FreeChainComplex<Algebra = SteenrodAlgebra>>(
+        &self,
+        resolution: &CC,
+    ) -> anyhow::Result<Vec<Vec<Vec<FpVector>>>> {
+        match &*resolution.algebra() {
+            SteenrodAlgebra::MilnorAlgebra(milnor)
+                if milnor.prime() == 2 && milnor.profile().is_trivial() => {}
+            _ => {
+                return Err(anyhow!(
+                    "The May spectral sequence requires a resolution over the mod 2 Milnor algebra"
+                ));
+            }
+        }
+        ensure!(
+            resolution.has_computed_bidegree(self.max),
+            "The resolution has not been computed through {}",
+            self.max
+        );
+        for t in 0..=self.max.t() {
+            ensure!(
+                resolution.number_of_gens_in_bidegree(Bidegree::s_t(0, t)) == (t == 0) as usize,
+                "The May spectral sequence requires a resolution of F_2"
+            );
+        }
+
+        let mut result: Vec<Vec<Vec<FpVector>>> = Vec::new();
+        for s in 0..=self.max.s() {
+            let mut row = Vec::new();
+            for t in 0..=self.max.t() {
+                let b = Bidegree::s_t(s, t);
+                let num_gens = resolution.number_of_gens_in_bidegree(b);
+                let mut gens = Vec::with_capacity(num_gens);
+                for idx in 0..num_gens {
+                    let mut phi = FpVector::new(TWO, self.bar_dimension(s as usize, t));
+                    if s == 0 {
+                        phi.set_entry(0, 1);
+                    } else {
+                        let module: Arc<FreeModule<SteenrodAlgebra>> = resolution.module(s - 1);
+                        let d = resolution.differential(s);
+                        let dx = d.output(t, idx);
+                        for (i, c) in dx.iter_nonzero() {
+                            let opgen = module.index_to_op_gen(t, i);
+                            let n = opgen.operation_degree;
+                            assert!(n > 0, "The resolution is not minimal");
+                            let previous = &result[s as usize - 1][opgen.generator_degree as usize]
+                                [opgen.generator_index];
+                            for (x, d) in previous.iter_nonzero() {
+                                phi.add_basis_element(
+                                    self.bar_index(s as usize, t, n, opgen.operation_index, x),
+                                    c * d,
+                                );
+                            }
+                        }
+                    }
+                    gens.push(phi);
+                }
+                row.push(gens);
+            }
+            result.push(row);
+        }
+        Ok(result)
+    }
+
+    fn evaluate(
+        &self,
+        bar_representatives: &[Vec<Vec<FpVector>>],
+        b: Bidegree,
+        cocycle: &FpVector,
+    ) -> BidegreeElement {
+        let gens = &bar_representatives[b.s() as usize][b.t() as usize];
+        let mut result = FpVector::new(TWO, gens.len());
+        for (g, phi) in gens.iter().enumerate() {
+            let value = phi
+                .iter_nonzero()
+                .map(|(i, c)| c * cocycle.entry(i))
+                .sum::<u32>();
+            result.set_entry(g, value % 2);
+        }
+        BidegreeElement::new(b, result)
+    }
+
+    /// The element of $\mathrm{Ext}$ represented by a permanent class, written in terms of the
+    /// generators of `resolution`. This is well-defined modulo elements of lower May filtration.
+    ///
+    /// Returns an error if `elem` is not a permanent class, or if `resolution` is not a minimal
+    /// resolution of $\mathbb{F}_2$ over the Milnor algebra computed through [`Self::max`].
+    pub fn ext_class<CC: FreeChainComplex<Algebra = SteenrodAlgebra>>(
+        &self,
+        resolution: &CC,
+        elem: &MultiDegreeElement<3>,
+    ) -> anyhow::Result<BidegreeElement> {
+        let b = elem.degree();
+        let cocycle = self
+            .cocycle(elem)
+            .ok_or_else(|| anyhow!("The class at {b} is not known to be permanent"))?;
+        let bar_representatives = self.bar_representatives(resolution)?;
+        Ok(self.evaluate(&bar_representatives, Bidegree::n_s(b.n(), b.s()), &cocycle))
+    }
+
+    /// For every generator of `resolution` in the range, the class in $E_\infty$ detecting it.
+    /// This is the image of the generator in $E_\infty$ in its May filtration, and is written in
+    /// terms of the $E_1$ basis.
+    ///
+    /// Returns an error if `resolution` is not a minimal resolution of $\mathbb{F}_2$ over the
+    /// Milnor algebra computed through [`Self::max`].
+    pub fn detect_generators<CC: FreeChainComplex<Algebra = SteenrodAlgebra>>(
+        &self,
+        resolution: &CC,
+    ) -> anyhow::Result<Vec<(BidegreeGenerator, MultiDegreeElement<3>)>> {
+        let bar_representatives = self.bar_representatives(resolution)?;
+        let mut result = Vec::new();
+        for s in 0..=self.max.s() {
+            for t in s..=self.max.t() {
+                let b = Bidegree::s_t(s, t);
+                let dim = resolution.number_of_gens_in_bidegree(b);
+
+                // The basis of E_∞ in each May filtration, together with the Ext classes they
+                // represent.
+                let mut classes = Vec::new();
+                for u in 0..=t {
+                    let may_b = MultiDegree::new([b.n(), s, u]);
+                    for v in self.sseq.page_data(may_b).last().unwrap().gens() {
+                        let elem = MultiDegreeElement::new(may_b, v.to_owned());
+                        let cocycle = self.cocycle(&elem).unwrap();
+                        let x = self.evaluate(&bar_representatives, b, &cocycle);
+                        classes.push((elem, x));
+                    }
+                }
+                ensure!(
+                    classes.len() == dim,
+                    "E_∞ has dimension {} at {b} but Ext has dimension {dim}",
+                    classes.len()
+                );
+                if dim == 0 {
+                    continue;
+                }
+
+                let mut matrix = AugmentedMatrix::<2>::new(TWO, dim, [dim, dim]);
+                for (k, (_, x)) in classes.iter().enumerate() {
+                    matrix.row_segment_mut(k, 0, 0).assign(x.vec());
+                }
+                matrix.segment(1, 1).add_identity();
+                matrix.row_reduce();
+                ensure!(
+                    matrix.pivots()[..dim].iter().all(|&x| x >= 0),
+                    "The classes of E_∞ do not span Ext at {b}"
+                );
+                let inverse = matrix.into_tail_segment(0, dim, 1);
+
+                for g in 0..dim {
+                    let coefficients = inverse.row(g);
+                    let (_, u) = coefficients
+                        .iter_nonzero()
+                        .map(|(k, _)| classes[k].0.degree().coords()[2])
+                        .minmax()
+                        .into_option()
+                        .unwrap();
+                    let may_b = MultiDegree::new([b.n(), s, u]);
+                    let mut v = FpVector::new(TWO, self.e1_basis(may_b).len());
+                    for (k, c) in coefficients.iter_nonzero() {
+                        if classes[k].0.degree() == may_b {
+                            v.as_slice_mut().add(classes[k].0.vec(), c);
+                        }
+                    }
+                    self.sseq
+                        .page_data(may_b)
+                        .last()
+                        .unwrap()
+                        .reduce_by_quotient(v.as_slice_mut());
+                    result.push((
+                        BidegreeGenerator::new(b, g),
+                        MultiDegreeElement::new(may_b, v),
+                    ));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
@@ -16,6 +16,7 @@ use std::{
     fmt::Display,
     io,
     sync::{Arc, Mutex, mpsc},
+    time::{Duration, Instant},
 };
 
 use algebra::{
@@ -39,22 +40,26 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator};
 
 use crate::{
     chain_complex::{AugmentedChainComplex, ChainComplex, FiniteChainComplex, FreeChainComplex},
+    checkpoint::{CancellationToken, Checkpoint},
+    progress::Progress,
     save::{SaveDirectory, SaveKind},
     utils::{LogWriter, parallel::ParallelGuard},
 };
 
-/// See [`resolution::SenderData`](../resolution/struct.SenderData.html). This differs by not having the `new` field.
+/// See [`resolution::SenderData`](../resolution/struct.SenderData.html).
 struct SenderData {
     b: Bidegree,
+    new: Option<Duration>,
     retry: bool,
     sender: mpsc::Sender<Self>,
 }
 
 impl SenderData {
-    pub(crate) fn send(b: Bidegree, sender: mpsc::Sender<Self>) {
+    pub(crate) fn send(b: Bidegree, new: Option<Duration>, sender: mpsc::Sender<Self>) {
         sender
             .send(Self {
                 b,
+                new,
                 retry: false,
                 sender: sender.clone(),
             })
@@ -66,6 +71,7 @@ impl SenderData {
         sender
             .send(Self {
                 b,
+                new: None,
                 retry: true,
                 sender: sender.clone(),
             })
@@ -927,6 +933,54 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
     /// This function resolves up till a fixed stem instead of a fixed t.
     #[tracing::instrument(skip(self), fields(self = self.name, %max))]
     pub fn compute_through_stem(&self, max: Bidegree) {
+        self.compute_through_stem_timed(max, None, |_, _| ());
+    }
+
+    /// Resolve through the stem as in [`Resolution::compute_through_stem`], stopping early if
+    /// `token` is cancelled. See
+    /// [`MuResolution::compute_through_stem_with_cancel`](crate::resolution::MuResolution::compute_through_stem_with_cancel).
+    #[tracing::instrument(skip(self, token), fields(self = self.name, %max))]
+    pub fn compute_through_stem_with_cancel(
+        &self,
+        max: Bidegree,
+        token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        self.compute_through_stem_timed(max, Some(token), |_, _| ());
+        Checkpoint::new(
+            "resolution",
+            0,
+            (0..=max.s()).map(|s| (self.differential(s).next_degree() - 1, max.n() + s)),
+        )
+        .finish(self.save_dir.write())
+    }
+
+    /// Resolve through the stem as in [`Resolution::compute_through_stem`], while reporting the
+    /// progress to `progress`.
+    pub fn compute_through_stem_with_progress(&self, max: Bidegree, progress: &Progress) {
+        progress.begin(
+            &format!("Resolving {}", self.name),
+            (0..=max.s())
+                .flat_map(|s| (0..=max.n() + s).map(move |t| Bidegree::s_t(s, t)))
+                .filter(|&b| !self.has_computed_bidegree(b)),
+        );
+        self.compute_through_stem_timed(max, None, |b, elapsed| {
+            progress.record(
+                b,
+                elapsed,
+                self.module(b.s()).dimension(b.t()),
+                self.differential(b.s()).target().dimension(b.t()),
+            )
+        });
+        progress.finish();
+    }
+
+    fn compute_through_stem_timed(
+        &self,
+        max: Bidegree,
+        token: Option<&CancellationToken>,
+        mut cb: impl FnMut(Bidegree, Duration),
+    ) {
+        let is_cancelled = || token.is_some_and(CancellationToken::is_cancelled);
         let _lock = self.lock.lock();
 
         self.extend_through_degree(max.s());
@@ -948,11 +1002,13 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
             progress[0] = -2;
 
             let (sender, receiver) = mpsc::channel();
-            SenderData::send(Bidegree::s_t(0, -1), sender);
+            SenderData::send(Bidegree::s_t(0, -1), None, sender);
 
             let f = |b, sender| {
                 if self.has_computed_bidegree(b) {
-                    SenderData::send(b, sender);
+                    SenderData::send(b, None, sender);
+                } else if is_cancelled() {
+                    // Drop the sender without computing anything.
                 } else {
                     let tracing_span = tracing_span.clone();
                     scope.spawn(move |_| {
@@ -961,13 +1017,20 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
                             SenderData::send_retry(b, sender);
                             return;
                         }
+                        let start = Instant::now();
                         self.step_resolution(b);
-                        SenderData::send(b, sender);
+                        SenderData::send(b, Some(start.elapsed()), sender);
                     });
                 }
             };
 
-            while let Ok(SenderData { b, retry, sender }) = receiver.recv() {
+            while let Ok(SenderData {
+                b,
+                new,
+                retry,
+                sender,
+            }) = receiver.recv()
+            {
                 if retry {
                     f(b, sender);
                     continue;
@@ -986,7 +1049,10 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
                     // We are computing a normal step
                     f(b + Bidegree::s_t(0, 1), sender);
                 } else if distance == 1 && b.s() < max.s() {
-                    SenderData::send(b + Bidegree::s_t(0, 1), sender);
+                    SenderData::send(b + Bidegree::s_t(0, 1), None, sender);
+                }
+                if let Some(elapsed) = new {
+                    cb(b, elapsed);
                 }
             }
         });
@@ -0,0 +1,324 @@
+//! Structured progress reporting for long computations.
+//!
+//! A [`Progress`] object is passed to the `*_with_progress` variants of the compute functions,
+//! namely
+//!
+//!  - [`MuResolution::compute_through_stem_with_progress`] and
+//!    [`MuResolution::compute_through_bidegree_with_progress`];
+//!  - [`nassau::Resolution::compute_through_stem_with_progress`];
+//!  - [`MuResolutionHomomorphism::extend_with_progress`] and
+//!    [`MuResolutionHomomorphism::extend_through_stem_with_progress`]; and
+//!  - [`SecondaryLift::extend_all_with_progress`], which covers [`SecondaryResolution`] and
+//!    [`SecondaryResolutionHomomorphism`].
+//!
+//! At the start of a computation, the [`Progress`] object is told which bidegrees are yet to be
+//! computed. Afterwards, every time a bidegree is computed, it sends a [`ProgressEvent`] to its
+//! [`ProgressSink`], containing the time spent on the bidegree, the size of the map computed in
+//! that bidegree and an estimate of the remaining time.
+//!
+//! The estimate is based on the shape of the region to be computed. Each bidegree is weighted by
+//! its internal degree, as a crude model of how the matrices grow, and the time spent so far is
+//! extrapolated to the remaining weight. This is quite inaccurate at the beginning of a
+//! computation, but tends to improve as it proceeds.
+//!
+//! The sinks provided are [`TerminalProgress`], which draws a progress bar on stderr,
+//! [`JsonLinesProgress`], which writes each event as a line of JSON, and
+//! [`mpsc::Sender<ProgressEvent>`], which forwards the events to a channel.
+//!
+//! [`MuResolution::compute_through_stem_with_progress`]: crate::resolution::MuResolution::compute_through_stem_with_progress
+//! [`MuResolution::compute_through_bidegree_with_progress`]: crate::resolution::MuResolution::compute_through_bidegree_with_progress
+//! [`nassau::Resolution::compute_through_stem_with_progress`]: crate::nassau::Resolution::compute_through_stem_with_progress
+//! [`MuResolutionHomomorphism::extend_with_progress`]: crate::resolution_homomorphism::MuResolutionHomomorphism::extend_with_progress
+//! [`MuResolutionHomomorphism::extend_through_stem_with_progress`]: crate::resolution_homomorphism::MuResolutionHomomorphism::extend_through_stem_with_progress
+//! [`SecondaryLift::extend_all_with_progress`]: crate::secondary::SecondaryLift::extend_all_with_progress
+//! [`SecondaryResolution`]: crate::secondary::SecondaryResolution
+//! [`SecondaryResolutionHomomorphism`]: crate::secondary::SecondaryResolutionHomomorphism
+
+use std::{
+    io::{self, Write},
+    sync::{Mutex, mpsc},
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+use sseq::coordinates::Bidegree;
+
+/// The data reported after a bidegree has been computed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    /// The name of the computation, as passed to [`Progress::begin`].
+    pub task: String,
+    /// The bidegree that was computed.
+    pub b: Bidegree,
+    /// The time spent computing this bidegree.
+    pub elapsed: Duration,
+    /// The dimension of the source of the map computed in this bidegree.
+    pub source_dimension: usize,
+    /// The dimension of the target of the map computed in this bidegree.
+    pub target_dimension: usize,
+    /// The number of bidegrees computed so far, including this one.
+    pub completed: usize,
+    /// The total number of bidegrees to be computed.
+    pub total: usize,
+    /// The estimated time until the computation finishes.
+    pub eta: Duration,
+}
+
+impl ProgressEvent {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "task": self.task,
+            "s": self.b.s(),
+            "t": self.b.t(),
+            "elapsed": self.elapsed.as_secs_f64(),
+            "source_dimension": self.source_dimension,
+            "target_dimension": self.target_dimension,
+            "completed": self.completed,
+            "total": self.total,
+            "eta": self.eta.as_secs_f64(),
+        })
+    }
+}
+
+/// A destination for [`ProgressEvent`]s.
+pub trait ProgressSink: Send + Sync {
+    /// Called after each bidegree is computed. This may be called from multiple threads
+    /// concurrently.
+    fn report(&self, event: &ProgressEvent);
+
+    /// Called when the computation named `task` has finished.
+    #[allow(unused_variables)]
+    fn finish(&self, task: &str) {}
+}
+
+impl ProgressSink for mpsc::Sender<ProgressEvent> {
+    fn report(&self, event: &ProgressEvent) {
+        // The receiver may have stopped listening, which should not abort the computation.
+        self.send(event.clone()).ok();
+    }
+}
+
+/// Writes each event as a line of JSON, as produced by [`ProgressEvent::to_json`].
+pub struct JsonLinesProgress<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesProgress<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap()
+    }
+}
+
+impl<W: Write + Send> ProgressSink for JsonLinesProgress<W> {
+    fn report(&self, event: &ProgressEvent) {
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", event.to_json())
+            .and_then(|()| writer.flush())
+            .unwrap_or_else(|e| tracing::warn!("Failed to write progress: {e}"));
+    }
+}
+
+/// Draws a progress bar on stderr, which is redrawn after every bidegree.
+pub struct TerminalProgress {
+    width: usize,
+}
+
+impl TerminalProgress {
+    pub fn new() -> Self {
+        Self { width: 30 }
+    }
+}
+
+impl Default for TerminalProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+impl ProgressSink for TerminalProgress {
+    fn report(&self, event: &ProgressEvent) {
+        let filled = (self.width * event.completed)
+            .checked_div(event.total)
+            .unwrap_or(self.width)
+            .min(self.width);
+        let mut stderr = io::stderr().lock();
+        write!(
+            stderr,
+            "\r\x1b[K{task} [{bar}{space}] {completed}/{total} {b} ({source} x {target}) ETA {eta}",
+            task = event.task,
+            bar = "#".repeat(filled),
+            space = " ".repeat(self.width - filled),
+            completed = event.completed,
+            total = event.total,
+            b = event.b,
+            source = event.source_dimension,
+            target = event.target_dimension,
+            eta = format_duration(event.eta),
+        )
+        .and_then(|()| stderr.flush())
+        .ok();
+    }
+
+    fn finish(&self, _task: &str) {
+        eprintln!();
+    }
+}
+
+struct State {
+    task: String,
+    start: Instant,
+    min_t: i32,
+    completed: usize,
+    total: usize,
+    completed_weight: f64,
+    total_weight: f64,
+}
+
+impl State {
+    fn weight(&self, b: Bidegree) -> f64 {
+        (b.t() - self.min_t + 1).max(1) as f64
+    }
+}
+
+/// Keeps track of the progress of a computation and reports it to a [`ProgressSink`]. See the
+/// [module-level documentation](self) for details.
+pub struct Progress {
+    sink: Box<dyn ProgressSink>,
+    state: Mutex<State>,
+}
+
+impl Progress {
+    pub fn new(sink: impl ProgressSink + 'static) -> Self {
+        Self {
+            sink: Box::new(sink),
+            state: Mutex::new(State {
+                task: String::new(),
+                start: Instant::now(),
+                min_t: 0,
+                completed: 0,
+                total: 0,
+                completed_weight: 0.0,
+                total_weight: 0.0,
+            }),
+        }
+    }
+
+    /// Start tracking a new computation, where `work` are the bidegrees to be computed. This
+    /// resets the previous state, so the same [`Progress`] can be used for several computations
+    /// in sequence.
+    pub fn begin(&self, task: &str, work: impl IntoIterator<Item = Bidegree>) {
+        let work: Vec<Bidegree> = work.into_iter().collect();
+        let mut state = self.state.lock().unwrap();
+        state.task = task.to_owned();
+        state.start = Instant::now();
+        state.min_t = work.iter().map(|b| b.t()).min().unwrap_or(0);
+        state.completed = 0;
+        state.total = work.len();
+        state.completed_weight = 0.0;
+        state.total_weight = work.iter().map(|&b| state.weight(b)).sum();
+    }
+
+    /// Record that the bidegree `b` has been computed in time `elapsed`, where the map computed
+    /// has the given source and target dimensions.
+    pub fn record(
+        &self,
+        b: Bidegree,
+        elapsed: Duration,
+        source_dimension: usize,
+        target_dimension: usize,
+    ) {
+        let event = {
+            let mut state = self.state.lock().unwrap();
+            state.completed += 1;
+            state.completed_weight += state.weight(b);
+            // Bidegrees outside of the declared work still count towards the progress, so the
+            // remaining weight may be negative.
+            let remaining = (state.total_weight - state.completed_weight).max(0.0);
+            let eta = state
+                .start
+                .elapsed()
+                .mul_f64(remaining / state.completed_weight);
+            ProgressEvent {
+                task: state.task.clone(),
+                b,
+                elapsed,
+                source_dimension,
+                target_dimension,
+                completed: state.completed,
+                total: state.total.max(state.completed),
+                eta,
+            }
+        };
+        self.sink.report(&event);
+    }
+
+    /// Signal that the current computation has finished.
+    pub fn finish(&self) {
+        let task = self.state.lock().unwrap().task.clone();
+        self.sink.finish(&task);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eta() {
+        let (sender, receiver) = mpsc::channel();
+        let progress = Progress::new(sender);
+
+        // The weights are 1, 2 and 3.
+        progress.begin("test", (0..3).map(|t| Bidegree::s_t(0, t)));
+        progress.record(Bidegree::s_t(0, 0), Duration::ZERO, 1, 0);
+        progress.record(Bidegree::s_t(0, 1), Duration::ZERO, 1, 1);
+        progress.record(Bidegree::s_t(0, 2), Duration::ZERO, 1, 1);
+        progress.finish();
+
+        let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events.iter().map(|e| e.completed).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert!(events.iter().all(|e| e.total == 3 && e.task == "test"));
+        assert_eq!(events[2].eta, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let progress = JsonLinesProgress::new(Vec::new());
+        let event = ProgressEvent {
+            task: "test".to_owned(),
+            b: Bidegree::s_t(2, 5),
+            elapsed: Duration::from_millis(1500),
+            source_dimension: 4,
+            target_dimension: 7,
+            completed: 1,
+            total: 10,
+            eta: Duration::from_secs(3),
+        };
+        progress.report(&event);
+        progress.report(&event);
+
+        let output = String::from_utf8(progress.into_inner()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, [event.to_json(), event.to_json()]);
+        assert_eq!(lines[0]["elapsed"], 1.5);
+        assert_eq!(lines[0]["s"], 2);
+    }
+}
@@ -1,6 +1,9 @@
 //! This module exports the [`Resolution`] object, which is a chain complex resolving a module. In
 //! particular, this contains the core logic that compute minimal resolutions.
-use std::sync::{Arc, Mutex, mpsc};
+use std::{
+    sync::{Arc, Mutex, mpsc},
+    time::{Duration, Instant},
+};
 
 use algebra::{
     Algebra, MuAlgebra,
@@ -22,6 +25,8 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator};
 
 use crate::{
     chain_complex::{AugmentedChainComplex, ChainComplex},
+    checkpoint::{CancellationToken, Checkpoint},
+    progress::Progress,
     save::{SaveDirectory, SaveKind},
     utils::parallel::ParallelGuard,
 };
@@ -31,8 +36,9 @@ use crate::{
 /// explicit struct instead of a tuple to avoid an infinite type problem.
 struct SenderData {
     b: Bidegree,
-    /// Whether this bidegree was newly calculated or have already been calculated.
-    new: bool,
+    /// If this bidegree was newly calculated, the time it took. This is `None` if it has already
+    /// been calculated.
+    new: Option<Duration>,
     /// Whether this job should be retried due to priority inversion avoidance.
     retry: bool,
     /// The sender object used to send the `SenderData`. We put this in the struct and pass it
@@ -43,7 +49,7 @@ struct SenderData {
 }
 
 impl SenderData {
-    fn send(b: Bidegree, new: bool, sender: mpsc::Sender<Self>) {
+    fn send(b: Bidegree, new: Option<Duration>, sender: mpsc::Sender<Self>) {
         sender
             .send(Self {
                 b,
@@ -58,7 +64,7 @@ impl SenderData {
         sender
             .send(Self {
                 b,
-                new: false,
+                new: None,
                 retry: true,
                 sender: sender.clone(),
             })
@@ -116,8 +122,11 @@ impl<const U: bool, CC: ChainComplex> MuResolution<U, CC>
 where
     CC::Algebra: MuAlgebra<U>,
 {
+    /// # Panics
+    ///
+    /// Panics if the resolution is unstable but the algebra has no unstable operations.
     pub fn new(complex: Arc<CC>) -> Self {
-        // It doesn't error if the save file is None
+        // Otherwise it doesn't error if the save file is None
         Self::new_with_save(complex, None).unwrap()
     }
 
@@ -127,6 +136,11 @@ where
     ) -> anyhow::Result<Self> {
         let save_dir = save_dir.into();
         let algebra = complex.algebra();
+        anyhow::ensure!(
+            algebra.has_unstable_operations(),
+            "Unstable resolutions are not supported over the {} algebra",
+            algebra.prefix()
+        );
         let min_degree = complex.min_degree();
         let zero_module = Arc::new(MuFreeModule::new(algebra, "F_{-1}".to_string(), min_degree));
 
@@ -733,6 +747,37 @@ where
         &self,
         max: Bidegree,
         mut cb: impl FnMut(Bidegree),
+    ) {
+        self.compute_through_bidegree_timed(max, |b, _| cb(b));
+    }
+
+    /// Resolve through the bidegree `max` as in [`ChainComplex::compute_through_bidegree`], while
+    /// reporting the progress to `progress`.
+    pub fn compute_through_bidegree_with_progress(&self, max: Bidegree, progress: &Progress) {
+        progress.begin(
+            &format!("Resolving {}", self.name),
+            (0..=max.s())
+                .flat_map(|s| (self.min_degree()..=max.t()).map(move |t| Bidegree::s_t(s, t)))
+                .filter(|&b| !self.has_computed_bidegree(b)),
+        );
+        self.compute_through_bidegree_timed(max, |b, elapsed| {
+            self.record_progress(progress, b, elapsed)
+        });
+        progress.finish();
+    }
+
+    /// Report the bidegree `b` to `progress`. The map computed is the differential together with
+    /// the augmentation.
+    fn record_progress(&self, progress: &Progress, b: Bidegree, elapsed: Duration) {
+        let target = self.differential(b.s()).target().dimension(b.t())
+            + self.target().module(b.s()).dimension(b.t());
+        progress.record(b, elapsed, self.module(b.s()).dimension(b.t()), target);
+    }
+
+    fn compute_through_bidegree_timed(
+        &self,
+        max: Bidegree,
+        mut cb: impl FnMut(Bidegree, Duration),
     ) {
         let min_degree = self.min_degree();
         let _lock = self.lock.lock();
@@ -752,11 +797,11 @@ where
             progress[0] = min_degree - 2;
 
             let (sender, receiver) = mpsc::channel();
-            SenderData::send(Bidegree::s_t(0, min_degree - 1), false, sender);
+            SenderData::send(Bidegree::s_t(0, min_degree - 1), None, sender);
 
             let f = |b, sender| {
                 if self.has_computed_bidegree(b) {
-                    SenderData::send(b, false, sender);
+                    SenderData::send(b, None, sender);
                 } else {
                     let tracing_span = tracing_span.clone();
                     scope.spawn(move |_| {
@@ -765,8 +810,9 @@ where
                             SenderData::send_retry(b, sender);
                             return;
                         }
+                        let start = Instant::now();
                         self.step_resolution(b);
-                        SenderData::send(b, true, sender);
+                        SenderData::send(b, Some(start.elapsed()), sender);
                     });
                 }
             };
@@ -792,8 +838,8 @@ where
                 if b.s() < max.s() && progress[b.s() as usize + 1] == b.t() - 1 {
                     f(b + Bidegree::s_t(1, 0), sender);
                 }
-                if new {
-                    cb(b);
+                if let Some(elapsed) = new {
+                    cb(b, elapsed);
                 }
             }
         });
@@ -806,6 +852,65 @@ where
     }
 
     pub fn compute_through_stem_with_callback(&self, max: Bidegree, mut cb: impl FnMut(Bidegree)) {
+        self.compute_through_stem_timed(max, None, |b, _| cb(b));
+    }
+
+    /// Resolve through the stem as in [`MuResolution::compute_through_stem`], stopping early if
+    /// `token` is cancelled. This returns an
+    /// [`Interrupted`](crate::checkpoint::Interrupted) error if the computation was stopped
+    /// before it finished. In either case, a checkpoint is written to the save directory. See
+    /// [`checkpoint`](crate::checkpoint) for details.
+    #[tracing::instrument(skip(self, token), fields(self = self.name, %max))]
+    pub fn compute_through_stem_with_cancel(
+        &self,
+        max: Bidegree,
+        token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        self.compute_through_stem_timed(max, Some(token), |_, _| ());
+        self.checkpoint(max).finish(self.save_dir.write())
+    }
+
+    /// The progress of resolving through the stem `max`. The bidegrees at the edge whose kernels
+    /// are computed in advance are not part of the checkpoint.
+    fn checkpoint(&self, max: Bidegree) -> Checkpoint {
+        Checkpoint::new(
+            "resolution",
+            0,
+            (0..=max.s()).map(|s| {
+                let progress = if s < self.next_homological_degree() {
+                    self.differential(s).next_degree() - 1
+                } else {
+                    self.min_degree() - 1
+                };
+                (progress, max.n() + s)
+            }),
+        )
+    }
+
+    /// Resolve through the stem as in [`MuResolution::compute_through_stem`], while reporting the
+    /// progress to `progress`.
+    pub fn compute_through_stem_with_progress(&self, max: Bidegree, progress: &Progress) {
+        progress.begin(
+            &format!("Resolving {}", self.name),
+            (0..=max.s())
+                .flat_map(|s| (self.min_degree()..=max.n() + s).map(move |t| Bidegree::s_t(s, t)))
+                .filter(|&b| !self.has_computed_bidegree(b)),
+        );
+        self.compute_through_stem_timed(max, None, |b, elapsed| {
+            self.record_progress(progress, b, elapsed)
+        });
+        progress.finish();
+    }
+
+    /// Once `token` is cancelled, no new bidegrees are started. The bidegrees that have already
+    /// started are allowed to finish, after which all senders are dropped and the loop ends.
+    fn compute_through_stem_timed(
+        &self,
+        max: Bidegree,
+        token: Option<&CancellationToken>,
+        mut cb: impl FnMut(Bidegree, Duration),
+    ) {
+        let is_cancelled = || token.is_some_and(CancellationToken::is_cancelled);
         let min_degree = self.min_degree();
         let _lock = self.lock.lock();
 
@@ -824,11 +929,13 @@ where
             progress[0] = min_degree - 2;
 
             let (sender, receiver) = mpsc::channel();
-            SenderData::send(Bidegree::s_t(0, min_degree - 1), false, sender);
+            SenderData::send(Bidegree::s_t(0, min_degree - 1), None, sender);
 
             let f = |b, sender| {
                 if self.has_computed_bidegree(b) {
-                    SenderData::send(b, false, sender);
+                    SenderData::send(b, None, sender);
+                } else if is_cancelled() {
+                    // Drop the sender without computing anything.
                 } else {
                     let tracing_span = tracing_span.clone();
                     scope.spawn(move |_| {
@@ -837,8 +944,9 @@ where
                             SenderData::send_retry(b, sender);
                             return;
                         }
+                        let start = Instant::now();
                         self.step_resolution(b);
-                        SenderData::send(b, true, sender);
+                        SenderData::send(b, Some(start.elapsed()), sender);
                     });
                 }
             };
@@ -870,7 +978,9 @@ where
                 } else if distance == 1 && b.s() < max.s() {
                     // We compute the kernel at the edge if necessary
                     let next_b = b + Bidegree::s_t(0, 1);
-                    if !self.has_computed_bidegree(b + Bidegree::s_t(1, 1))
+                    if is_cancelled() {
+                        // Drop the sender without computing anything.
+                    } else if !self.has_computed_bidegree(b + Bidegree::s_t(1, 1))
                         && (self.save_dir.is_none()
                             || !self
                                 .save_file(SaveKind::Differential, b + Bidegree::s_t(1, 1))
@@ -878,18 +988,59 @@ where
                     {
                         scope.spawn(move |_| {
                             self.kernels.insert(next_b, self.get_kernel(next_b));
-                            SenderData::send(next_b, false, sender);
+                            SenderData::send(next_b, None, sender);
                         });
                     } else {
-                        SenderData::send(next_b, false, sender);
+                        SenderData::send(next_b, None, sender);
                     }
                 }
-                if new {
-                    cb(b);
+                if let Some(elapsed) = new {
+                    cb(b, elapsed);
                 }
             }
         });
     }
+
+    /// Compute the bidegree `b`, assuming every bidegree it depends on has already been written to
+    /// the save directory, possibly by a different process. These are the bidegrees $(s', t')$
+    /// with $s' \leq s$ and $t' - s' \leq t - s$. Those that are not yet in memory are loaded
+    /// first, and an error is returned if any of them is missing from the save directory.
+    ///
+    /// This is the unit of work in a [`distributed`](crate::distributed) computation, where the
+    /// scheduling is performed by the coordinator rather than by
+    /// [`MuResolution::compute_through_stem`].
+    pub(crate) fn compute_bidegree_from_save(&self, b: Bidegree) -> anyhow::Result<()> {
+        let min_degree = self.min_degree();
+        let _lock = self.lock.lock();
+
+        let dir = self
+            .save_dir
+            .read()
+            .cloned()
+            .context("Computing from save requires a save directory")?;
+
+        self.target().compute_through_bidegree(b);
+        self.extend_through_degree(b.s());
+        self.algebra().compute_basis(b.t() - min_degree);
+
+        for s in 0..=b.s() {
+            let max_t = b.t() - (b.s() - s);
+            for t in self.differential(s).next_degree()..=max_t {
+                let dep = Bidegree::s_t(s, t);
+                if dep != b
+                    && !self
+                        .save_file(SaveKind::Differential, dep)
+                        .exists(dir.clone())
+                {
+                    return Err(anyhow::anyhow!(
+                        "Cannot compute {b}: {dep} is missing from the save directory"
+                    ));
+                }
+                self.step_resolution(dep);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<const U: bool, CC: ChainComplex> ChainComplex for MuResolution<U, CC>
@@ -1,6 +1,6 @@
 //! This module defines [`MuResolutionHomomorphism`], which is a chain map from a
 //! [`FreeChainComplex`].
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, sync::Arc, time::Instant};
 
 use algebra::{
     MuAlgebra,
@@ -20,6 +20,8 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator, BidegreeRange};
 
 use crate::{
     chain_complex::{AugmentedChainComplex, BoundedChainComplex, ChainComplex, FreeChainComplex},
+    checkpoint::{self, CancellationToken, Checkpoint},
+    progress::Progress,
     save::{SaveDirectory, SaveKind},
 };
 
@@ -174,10 +176,94 @@ where
     /// decomposables (e.g. it is trivial). More precisely, we assume
     /// [`MuResolutionHomomorphism::extend_step_raw`] can be called with `extra_images = None`.
     pub fn extend_profile<AUX: Sync>(&self, max: BidegreeRange<AUX>) {
+        self.extend_profile_with(max, None, None);
+    }
+
+    /// Extend the resolution homomorphism as in [`MuResolutionHomomorphism::extend`], while
+    /// reporting the progress to `progress`.
+    pub fn extend_with_progress(&self, max: Bidegree, progress: &Progress) {
+        self.extend_profile_with(
+            BidegreeRange::new(&(), max.s() + 1, &|_, _| max.t() + 1),
+            Some(progress),
+            None,
+        )
+    }
+
+    /// Extend the resolution homomorphism as in [`MuResolutionHomomorphism::extend_through_stem`],
+    /// while reporting the progress to `progress`.
+    pub fn extend_through_stem_with_progress(&self, max: Bidegree, progress: &Progress) {
+        self.extend_profile_with(
+            BidegreeRange::new(&(), max.s() + 1, &|_, s| max.n() + s + 1),
+            Some(progress),
+            None,
+        )
+    }
+
+    /// Extend the resolution homomorphism as in [`MuResolutionHomomorphism::extend_through_stem`],
+    /// stopping early if `token` is cancelled. This returns an
+    /// [`Interrupted`](crate::checkpoint::Interrupted) error if the computation was stopped before
+    /// it finished. In either case, a checkpoint is written to the save directory. See
+    /// [`checkpoint`](crate::checkpoint) for details.
+    #[tracing::instrument(skip(self, token), fields(self = self.name, %max))]
+    pub fn extend_through_stem_with_cancel(
+        &self,
+        max: Bidegree,
+        token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        self.extend_profile_with(
+            BidegreeRange::new(&(), max.s() + 1, &|_, s| max.n() + s + 1),
+            None,
+            Some(token),
+        );
+        Checkpoint::new(
+            "homomorphism",
+            self.shift.s(),
+            (self.shift.s()..=max.s()).map(|s| (self.maps[s].next_degree() - 1, max.n() + s)),
+        )
+        .finish(self.save_dir.write())
+    }
+
+    /// Extend the resolution homomorphism up to `max`. If `progress` is given, the newly computed
+    /// bidegrees are reported to it. If `token` is cancelled, no new bidegrees are started, and
+    /// the bidegrees needed to make the maps contiguous again are computed before returning.
+    fn extend_profile_with<AUX: Sync>(
+        &self,
+        max: BidegreeRange<AUX>,
+        progress: Option<&Progress>,
+        token: Option<&CancellationToken>,
+    ) {
         self.get_map_ensure_length(max.s() - 1);
 
+        if let Some(progress) = progress {
+            progress.begin(
+                &format!("Extending {}", self.name),
+                (self.shift.s()..max.s()).flat_map(|s| {
+                    (self.maps[s].next_degree()..max.t(s)).map(move |t| Bidegree::s_t(s, t))
+                }),
+            );
+        }
+
+        let step = |b: Bidegree| {
+            let Some(progress) = progress else {
+                return self.extend_step_raw(b, None);
+            };
+            let is_new = b.t() >= self.maps[b.s()].next_degree();
+            let start = Instant::now();
+            let result = self.extend_step_raw(b, None);
+            if is_new {
+                let output = b - self.shift;
+                progress.record(
+                    b,
+                    start.elapsed(),
+                    self.source.module(b.s()).dimension(b.t()),
+                    self.target.module(output.s()).dimension(output.t()),
+                );
+            }
+            result
+        };
+
         sseq::coordinates::iter_s_t(
-            &|b| self.extend_step_raw(b, None),
+            &checkpoint::cancellable(token, step),
             Bidegree::s_t(
                 self.shift.s(),
                 self.get_map_ensure_length(self.shift.s()).min_degree(),
@@ -185,6 +271,20 @@ where
             max,
         );
 
+        if token.is_some_and(CancellationToken::is_cancelled) {
+            checkpoint::fill_gaps(
+                self.shift.s()..max.s(),
+                |s| (self.maps[s].next_degree(), self.maps[s].ooo_outputs()),
+                |b| {
+                    step(b);
+                },
+            );
+        }
+
+        if let Some(progress) = progress {
+            progress.finish();
+        }
+
         for s in self.shift.s()..max.s() {
             assert_eq!(
                 Vec::<i32>::new(),
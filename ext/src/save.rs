@@ -76,6 +76,15 @@ fn open_files() -> &'static Mutex<HashSet<PathBuf>> {
     static OPEN_FILES: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| {
         #[cfg(unix)]
         ctrlc::set_handler(move || {
+            if let Some(token) = crate::checkpoint::ctrlc_token()
+                && !token.is_cancelled()
+            {
+                tracing::warn!(
+                    "Ctrl-C detected. Finishing current computations. Press Ctrl-C again to abort."
+                );
+                token.cancel();
+                return;
+            }
             tracing::warn!("Ctrl-C detected. Deleting open files and exiting.");
             let files = open_files().lock().unwrap();
             for file in &*files {
@@ -91,6 +100,14 @@ fn open_files() -> &'static Mutex<HashSet<PathBuf>> {
     &OPEN_FILES
 }
 
+/// Make sure the ctrlc handler is set. See [`CancellationToken::ctrlc`].
+///
+/// [`CancellationToken::ctrlc`]: crate::checkpoint::CancellationToken::ctrlc
+#[cfg(unix)]
+pub(crate) fn install_ctrlc_handler() {
+    open_files();
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum SaveKind {
@@ -127,6 +144,10 @@ pub enum SaveKind {
 
     /// The quasi-inverse data in Nassau's algorithm
     NassauQi,
+
+    /// A marker that a bidegree is being computed by a worker in a
+    /// [`distributed`](crate::distributed) computation. This carries no data.
+    Lock,
 }
 
 impl SaveKind {
@@ -143,6 +164,7 @@ impl SaveKind {
             Self::ChainHomotopy => 0x11110000,
             Self::NassauDifferential => 0xD1FF0001,
             Self::NassauQi => 0x0100D1FE,
+            Self::Lock => 0x4C4F434B,
         }
     }
 
@@ -159,6 +181,7 @@ impl SaveKind {
             Self::ChainHomotopy => "chain_homotopy",
             Self::NassauDifferential => "nassau_differential",
             Self::NassauQi => "nassau_qi",
+            Self::Lock => "lock",
         }
     }
 
@@ -457,6 +480,20 @@ impl<A: Algebra> SaveFile<A> {
         }
     }
 
+    /// Atomically create an empty marker file, failing if it already exists. The file contains the
+    /// id of the process holding it and has no header or checksum. This is meant to be used with
+    /// [`SaveKind::Lock`].
+    pub fn create_lock(&self, dir: PathBuf) -> io::Result<()> {
+        use io::Write;
+
+        let p = self.get_save_path(dir);
+        let mut f = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&p)?;
+        writeln!(f, "{}", std::process::id())
+    }
+
     /// # Arguments
     ///  - `overwrite`: Whether to overwrite a file if it already exists.
     pub fn create_file(&self, dir: PathBuf, overwrite: bool) -> impl io::Write + use<A> {
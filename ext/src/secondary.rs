@@ -1,4 +1,4 @@
-use std::{io, sync::Arc};
+use std::{io, sync::Arc, time::Instant};
 
 use algebra::{
     Algebra,
@@ -34,6 +34,8 @@ pub use crate::{
 };
 use crate::{
     chain_complex::{ChainComplex, FreeChainComplex},
+    checkpoint::{self, CancellationToken, Checkpoint},
+    progress::Progress,
     save::{SaveDirectory, SaveFile, SaveKind},
 };
 
@@ -407,18 +409,7 @@ pub trait SecondaryLift: Sync + Sized {
 
     #[tracing::instrument(skip(self))]
     fn compute_composites(&self) {
-        let tracing_span = tracing::Span::current();
-        let f = |s| {
-            let _tracing_guard = tracing_span.enter();
-            self.homotopies()[s].add_composite(
-                s,
-                self.max().t(s) - 1,
-                self.composite(s),
-                self.save_dir(),
-            );
-        };
-
-        self.homotopies().range().into_maybe_par_iter().for_each(f);
+        compute_composites(self, None);
     }
 
     #[tracing::instrument(skip(self), ret(Display, level = Level::DEBUG), fields(%g))]
@@ -485,44 +476,7 @@ pub trait SecondaryLift: Sync + Sized {
 
     #[tracing::instrument(skip(self))]
     fn compute_intermediates(&self) {
-        let tracing_span = tracing::Span::current();
-        let f = |g: BidegreeGenerator| {
-            let _tracing_guard = tracing_span.enter();
-
-            // If we already have homotopies, we don't need to compute intermediate
-            if self.homotopies()[g.s()].homotopies.next_degree() >= g.t() {
-                return;
-            }
-            // Check if we have a saved homotopy
-            if let Some(dir) = self.save_dir().read() {
-                let save_file = SaveFile {
-                    algebra: self.algebra(),
-                    kind: SaveKind::SecondaryHomotopy,
-                    b: g.degree(),
-                    idx: None,
-                };
-
-                if save_file.exists(dir.to_owned()) {
-                    return;
-                }
-            }
-            self.intermediates().insert(g, self.get_intermediate(g));
-        };
-
-        self.homotopies()
-            .maybe_par_iter()
-            .skip(1)
-            .for_each(|(s, homotopy)| {
-                homotopy
-                    .composites
-                    .range()
-                    .into_maybe_par_iter()
-                    .for_each(|t| {
-                        (0..homotopy.source.number_of_gens_in_degree(t))
-                            .into_maybe_par_iter()
-                            .for_each(|i| f(BidegreeGenerator::s_t(s, t, i)))
-                    })
-            })
+        compute_intermediates(self, None);
     }
 
     fn compute_homotopy_step(&self, b: Bidegree) -> std::ops::Range<i32> {
@@ -644,19 +598,7 @@ pub trait SecondaryLift: Sync + Sized {
 
     #[tracing::instrument(skip(self))]
     fn compute_homotopies(&self) {
-        let shift = self.shift();
-
-        // When s = shift_s, the homotopies are just zero
-        {
-            let h = &self.homotopies()[shift.s()];
-            h.homotopies.extend_by_zero(h.composites.max_degree());
-        }
-
-        let min_t = self.homotopies()[shift.s()].homotopies.min_degree();
-        let s_range = self.homotopies().range();
-        let min = Bidegree::s_t(s_range.start + 1, min_t);
-        let max = self.max().restrict(s_range.end);
-        sseq::coordinates::iter_s_t(&|b| self.compute_homotopy_step(b), min, max);
+        compute_homotopies(self, None, None);
     }
 
     #[tracing::instrument(skip(self))]
@@ -666,6 +608,184 @@ pub trait SecondaryLift: Sync + Sized {
         self.compute_intermediates();
         self.compute_homotopies();
     }
+
+    /// Same as [`SecondaryLift::extend_all`], but reports the progress of computing the
+    /// homotopies, which is the final and most expensive step.
+    #[tracing::instrument(skip(self, progress))]
+    fn extend_all_with_progress(&self, progress: &Progress) {
+        self.initialize_homotopies();
+        self.compute_composites();
+        self.compute_intermediates();
+        compute_homotopies(self, Some(progress), None);
+    }
+
+    /// Same as [`SecondaryLift::extend_all`], but stops early if `token` is cancelled. The token
+    /// is checked before each bidegree of the composites, each generator of the intermediates and
+    /// each bidegree of the homotopies. This returns an
+    /// [`Interrupted`](crate::checkpoint::Interrupted) error if the computation was stopped before
+    /// it finished. In either case, a checkpoint of the homotopies is written to the save
+    /// directory. See [`checkpoint`](crate::checkpoint) for details.
+    #[tracing::instrument(skip(self, token))]
+    fn extend_all_with_cancel(&self, token: &CancellationToken) -> anyhow::Result<()> {
+        self.initialize_homotopies();
+        compute_composites(self, Some(token));
+        if !token.is_cancelled() {
+            compute_intermediates(self, Some(token));
+        }
+        if !token.is_cancelled() {
+            compute_homotopies(self, None, Some(token));
+        }
+
+        let homotopies = self.homotopies();
+        let max = self.max();
+        Checkpoint::new(
+            "secondary",
+            homotopies.min_degree() + 1,
+            (homotopies.min_degree() + 1..homotopies.len())
+                .map(|s| (homotopies[s].homotopies.next_degree() - 1, max.t(s) - 1)),
+        )
+        .finish(self.save_dir().write())
+    }
+}
+
+/// The implementation of [`SecondaryLift::compute_composites`], stopping early when `token` is
+/// cancelled. With a token, the composites of each `s` are computed one degree at a time so that
+/// the token can be checked in between.
+fn compute_composites<T: SecondaryLift>(lift: &T, token: Option<&CancellationToken>) {
+    let tracing_span = tracing::Span::current();
+    let f = |s| {
+        let _tracing_guard = tracing_span.enter();
+        let homotopy = &lift.homotopies()[s];
+        let max_t = lift.max().t(s) - 1;
+        let Some(token) = token else {
+            homotopy.add_composite(s, max_t, lift.composite(s), lift.save_dir());
+            return;
+        };
+        for t in homotopy.composites.max_degree() + 1..=max_t {
+            if token.is_cancelled() {
+                return;
+            }
+            homotopy.add_composite(s, t, lift.composite(s), lift.save_dir());
+        }
+    };
+
+    lift.homotopies().range().into_maybe_par_iter().for_each(f);
+}
+
+/// The implementation of [`SecondaryLift::compute_intermediates`], stopping early when `token` is
+/// cancelled. The intermediates that are skipped are computed on demand by
+/// [`SecondaryLift::compute_homotopy_step`] when the computation is resumed.
+fn compute_intermediates<T: SecondaryLift>(lift: &T, token: Option<&CancellationToken>) {
+    let tracing_span = tracing::Span::current();
+    let f = |g: BidegreeGenerator| {
+        let _tracing_guard = tracing_span.enter();
+
+        if token.is_some_and(CancellationToken::is_cancelled) {
+            return;
+        }
+        // If we already have homotopies, we don't need to compute intermediate
+        if lift.homotopies()[g.s()].homotopies.next_degree() >= g.t() {
+            return;
+        }
+        // Check if we have a saved homotopy
+        if let Some(dir) = lift.save_dir().read() {
+            let save_file = SaveFile {
+                algebra: lift.algebra(),
+                kind: SaveKind::SecondaryHomotopy,
+                b: g.degree(),
+                idx: None,
+            };
+
+            if save_file.exists(dir.to_owned()) {
+                return;
+            }
+        }
+        lift.intermediates().insert(g, lift.get_intermediate(g));
+    };
+
+    lift.homotopies()
+        .maybe_par_iter()
+        .skip(1)
+        .for_each(|(s, homotopy)| {
+            homotopy
+                .composites
+                .range()
+                .into_maybe_par_iter()
+                .for_each(|t| {
+                    (0..homotopy.source.number_of_gens_in_degree(t))
+                        .into_maybe_par_iter()
+                        .for_each(|i| f(BidegreeGenerator::s_t(s, t, i)))
+                })
+        })
+}
+
+/// The implementation of [`SecondaryLift::compute_homotopies`], optionally reporting the progress
+/// and stopping early when `token` is cancelled.
+fn compute_homotopies<T: SecondaryLift>(
+    lift: &T,
+    progress: Option<&Progress>,
+    token: Option<&CancellationToken>,
+) {
+    let shift = lift.shift();
+
+    // When s = shift_s, the homotopies are just zero
+    {
+        let h = &lift.homotopies()[shift.s()];
+        h.homotopies.extend_by_zero(h.composites.max_degree());
+    }
+
+    let min_t = lift.homotopies()[shift.s()].homotopies.min_degree();
+    let s_range = lift.homotopies().range();
+    let min = Bidegree::s_t(s_range.start + 1, min_t);
+    let max = lift.max().restrict(s_range.end);
+
+    if let Some(progress) = progress {
+        progress.begin(
+            "Computing secondary homotopies",
+            (min.s()..max.s()).flat_map(|s| {
+                let start = lift.homotopies()[s].homotopies.next_degree();
+                (start..max.t(s)).map(move |t| Bidegree::s_t(s, t))
+            }),
+        );
+    }
+
+    let step = |b: Bidegree| {
+        let Some(progress) = progress else {
+            return lift.compute_homotopy_step(b);
+        };
+        let homotopy = &lift.homotopies()[b.s()];
+        let is_new = b.t() >= homotopy.homotopies.next_degree();
+        let start = Instant::now();
+        let result = lift.compute_homotopy_step(b);
+        if is_new {
+            progress.record(
+                b,
+                start.elapsed(),
+                homotopy.source.dimension(b.t()),
+                homotopy.target.dimension(b.t() - homotopy.shift_t),
+            );
+        }
+        result
+    };
+
+    sseq::coordinates::iter_s_t(&checkpoint::cancellable(token, step), min, max);
+
+    if token.is_some_and(CancellationToken::is_cancelled) {
+        checkpoint::fill_gaps(
+            min.s()..max.s(),
+            |s| {
+                let homotopies = &lift.homotopies()[s].homotopies;
+                (homotopies.next_degree(), homotopies.ooo_outputs())
+            },
+            |b| {
+                step(b);
+            },
+        );
+    }
+
+    if let Some(progress) = progress {
+        progress.finish();
+    }
 }
 
 #[cfg(test)]
@@ -3,10 +3,10 @@ use std::{path::PathBuf, sync::Arc};
 
 use algebra::{
     AlgebraType, MilnorAlgebra, SteenrodAlgebra,
-    module::{FDModule, Module, SteenrodModule, steenrod_module},
+    module::{FDModule, Module, SteenrodModule, TensorModule, steenrod_module},
 };
 use anyhow::{Context, anyhow};
-use serde_json::Value;
+use serde_json::{Value, json};
 use sseq::coordinates::{Bidegree, BidegreeGenerator};
 
 use crate::{
@@ -40,28 +40,199 @@ pub struct Config {
 
 /// Given a module specification string, load a json description of the module as described
 /// [here](../index.html#module-specification).
+///
+/// Besides the name of a module file, the specification can be an expression built out of smash
+/// products `M ^ N`, direct sums `M + N`, Spanier–Whitehead duals `D(M)` and shifts `M[n]`, with
+/// parentheses for grouping. Smash products bind more tightly than direct sums. The operands of
+/// smash products, direct sums and duals must be finite dimensional, and the result is a finite
+/// dimensional module.
 pub fn parse_module_name(module_name: &str) -> anyhow::Result<Value> {
-    let mut args = module_name.split('[');
-    let module_file = args.next().unwrap();
-    let mut module = load_module_json(module_file)
-        .with_context(|| format!("Failed to load module file {module_file}"))?;
-    if let Some(shift) = args.next() {
-        let shift: i64 = match shift.strip_suffix(']') {
-            None => return Err(anyhow!("Unterminated shift [")),
-            Some(x) => x
-                .parse()
-                .with_context(|| format!("Cannot parse shift value ({x}) as an integer"))?,
-        };
-        if let Some(spec_shift) = module.get_mut("shift") {
-            let existing = spec_shift
-                .as_i64()
-                .with_context(|| format!("Module shift field is not an integer: {spec_shift}"))?;
-            *spec_shift = Value::from(existing + shift);
+    let mut parser = ModuleExprParser {
+        input: module_name,
+        pos: 0,
+    };
+    let (module, _) = parser.sum()?;
+    parser.skip_whitespace();
+    if parser.pos < module_name.len() {
+        return Err(anyhow!(
+            "Unexpected character at position {} of module specification {module_name}",
+            parser.pos
+        ));
+    }
+    Ok(module)
+}
+
+/// A recursive descent parser for module specifications. Each rule returns the json of the
+/// module, together with a name for it that is used as the name of composite modules.
+struct ModuleExprParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl ModuleExprParser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
         } else {
-            module["shift"] = Value::from(shift);
+            false
         }
     }
-    Ok(module)
+
+    fn expect(&mut self, c: char) -> anyhow::Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Expected '{c}' at position {} of module specification {}",
+                self.pos,
+                self.input
+            ))
+        }
+    }
+
+    /// `sum := smash ('+' smash)*`
+    fn sum(&mut self) -> anyhow::Result<(Value, String)> {
+        let (mut module, mut name) = self.smash()?;
+        while self.eat('+') {
+            let (other, other_name) = self.smash()?;
+            name = format!("{name} + {other_name}");
+            let sum = to_fd_module(&module)?.direct_sum(&to_fd_module(&other)?);
+            module = fd_module_json(&sum, &module, &other, &name)?;
+        }
+        Ok((module, name))
+    }
+
+    /// `smash := shifted ('^' shifted)*`
+    fn smash(&mut self) -> anyhow::Result<(Value, String)> {
+        let (mut module, mut name) = self.shifted()?;
+        while self.eat('^') {
+            let (other, other_name) = self.shifted()?;
+            name = format!("{name} ^ {other_name}");
+            let left = to_fd_module(&module)?;
+            let right = to_fd_module(&other)?;
+            let smash = FDModule::from(&TensorModule::new(Arc::new(left), Arc::new(right)));
+            module = fd_module_json(&smash, &module, &other, &name)?;
+        }
+        Ok((module, name))
+    }
+
+    /// `shifted := atom ('[' integer ']')*`
+    fn shifted(&mut self) -> anyhow::Result<(Value, String)> {
+        let (mut module, mut name) = self.atom()?;
+        while self.eat('[') {
+            let end = self
+                .rest()
+                .find(']')
+                .ok_or_else(|| anyhow!("Unterminated shift ["))?;
+            let x = self.rest()[..end].trim();
+            let shift: i64 = x
+                .parse()
+                .with_context(|| format!("Cannot parse shift value ({x}) as an integer"))?;
+            name = format!("{name}[{shift}]");
+            self.pos += end + 1;
+
+            if let Some(spec_shift) = module.get_mut("shift") {
+                let existing = spec_shift.as_i64().with_context(|| {
+                    format!("Module shift field is not an integer: {spec_shift}")
+                })?;
+                *spec_shift = Value::from(existing + shift);
+            } else {
+                module["shift"] = Value::from(shift);
+            }
+        }
+        Ok((module, name))
+    }
+
+    /// `atom := 'D(' sum ')' | '(' sum ')' | module_file`
+    fn atom(&mut self) -> anyhow::Result<(Value, String)> {
+        self.skip_whitespace();
+        if self.rest().starts_with("D(") {
+            self.pos += 1;
+            self.expect('(')?;
+            let (module, name) = self.sum()?;
+            self.expect(')')?;
+            let name = format!("D({name})");
+            let dual = to_fd_module(&module)?.dual()?;
+            return Ok((fd_module_json(&dual, &module, &module, &name)?, name));
+        }
+        if self.eat('(') {
+            let (module, name) = self.sum()?;
+            self.expect(')')?;
+            return Ok((module, format!("({name})")));
+        }
+
+        let end = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || "^+()[]".contains(c))
+            .unwrap_or(self.rest().len());
+        if end == 0 {
+            return Err(anyhow!(
+                "Expected module name at position {} of module specification {}",
+                self.pos,
+                self.input
+            ));
+        }
+        let module_file = &self.rest()[..end];
+        let module = load_module_json(module_file)
+            .with_context(|| format!("Failed to load module file {module_file}"))?;
+        let name = module_file.to_owned();
+        self.pos += end;
+        Ok((module, name))
+    }
+}
+
+/// Construct a finite dimensional module from its json specification, over the Milnor basis so
+/// that profiles are supported.
+fn to_fd_module(json: &Value) -> anyhow::Result<FDModule<SteenrodAlgebra>> {
+    let algebra = Arc::new(SteenrodAlgebra::from_json(
+        json,
+        AlgebraType::Milnor,
+        false,
+    )?);
+    let module = steenrod_module::from_json(algebra, json)?;
+    if module.max_degree().is_none() {
+        return Err(anyhow!("Module {module} is not finite dimensional"));
+    }
+    Ok(FDModule::from(&module))
+}
+
+/// The json of a module built out of modules with json `left` and `right`, which must be over the
+/// same algebra.
+fn fd_module_json(
+    module: &FDModule<SteenrodAlgebra>,
+    left: &Value,
+    right: &Value,
+    name: &str,
+) -> anyhow::Result<Value> {
+    for key in ["p", "profile"] {
+        if left[key] != right[key] {
+            return Err(anyhow!(
+                "Modules must have the same {key}, but they are {} and {}",
+                left[key],
+                right[key]
+            ));
+        }
+    }
+    let mut json = json!({ "p": left["p"] });
+    for key in ["algebra", "profile"] {
+        if !left[key].is_null() {
+            json[key] = left[key].clone();
+        }
+    }
+    module.to_json(&mut json);
+    json["name"] = Value::from(name);
+    Ok(json)
 }
 
 impl TryFrom<&str> for Config {
@@ -126,11 +297,13 @@ impl<T: TryInto<AlgebraType>> TryFrom<(Value, T)> for Config {
 ///    [`TryInto<Config>`] (with appropriate error bounds). In practice, we can supply
 ///    - A [`Config`] object itself
 ///    - `(json, algebra)`: The first argument is a [`serde_json::Value`] that specifies the
-///      module; the second argument is either a string (`"milnor"` or `"adem"`) or an
-///      [`algebra::AlgebraType`] object.
+///      module; the second argument is either a string (`"milnor"` or `"adem"`, or at the prime 2
+///      one of the bases `"pst"`, `"arnona"`, `"arnonc"` and `"wall"` of
+///      [`algebra::ChangeOfBasisAlgebra`]) or an [`algebra::AlgebraType`] object.
 ///    - `(module_name, algebra)`: The first argument is the name of the module and the second is
 ///      as above. Modules are searched in the current directory, `$CWD/steenrod_modules` and
-///      `ext/steenrod_modules`. The modules can be shifted by appending e.g. `S_2[2]`.
+///      `ext/steenrod_modules`. The modules can be shifted by appending e.g. `S_2[2]`, and
+///      combined with smash products, direct sums and duals as in `D(C2 ^ Ceta) + S_2`.
 ///    - `module_spec`, a single `&str` of the form `module_name@algebra`, where `module_name` and
 ///      `algebra` are as above.
 ///  - `save_file`: The save file for the module. If it points to an invalid save file, an error is
@@ -171,7 +344,7 @@ where
         algebra,
     } = module_spec.try_into()?;
 
-    if algebra == AlgebraType::Adem {
+    if algebra != AlgebraType::Milnor {
         return Err(anyhow!("Nassau's algorithm requires Milnor's basis"));
     }
     if !json["profile"].is_null() {
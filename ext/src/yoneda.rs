@@ -45,6 +45,7 @@ fn rate_operation<A: Algebra>(algebra: &Arc<A>, op_deg: i32, op_idx: usize) -> i
         match algebra {
             SteenrodAlgebra::AdemAlgebra(a) => rate_adem_operation(a, op_deg, op_idx),
             SteenrodAlgebra::MilnorAlgebra(a) => rate_milnor_operation(a, op_deg, op_idx),
+            SteenrodAlgebra::ChangeOfBasisAlgebra(_) => 0,
         }
     } else if let Some(algebra) = algebra.downcast_ref::<MilnorAlgebra>() {
         rate_milnor_operation(algebra, op_deg, op_idx)
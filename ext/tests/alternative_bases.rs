@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use algebra::{SteenrodAlgebra, module::steenrod_module};
+use ext::{
+    CCC,
+    chain_complex::{ChainComplex, FreeChainComplex},
+    resolution::UnstableResolution,
+    utils::{construct, construct_standard, load_module_json},
+};
+use rstest::rstest;
+use sseq::coordinates::Bidegree;
+
+#[rstest]
+#[trace]
+fn compare(
+    #[values("S_2", "C2", "Joker", "RP4", "Csigma", "RP_inf")] module_name: &str,
+    #[values("pst", "arnona", "arnonc", "wall")] algebra: &str,
+) {
+    let max = Bidegree::s_t(20, 20);
+    let a = construct((module_name, algebra), None).unwrap();
+    let b = construct((module_name, "milnor"), None).unwrap();
+
+    a.compute_through_bidegree(max);
+    b.compute_through_bidegree(max);
+
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+}
+
+#[test]
+fn unstable() {
+    // Unstable modules are only defined over the Adem and Milnor bases
+    for algebra in ["pst", "arnona", "arnonc", "wall"] {
+        assert!(construct_standard::<true, _, _>(("S_2", algebra), None).is_err());
+    }
+
+    // A stable algebra can still be used for an unstable resolution, which has to refuse it
+    let json = load_module_json("S_2").unwrap();
+    let algebra =
+        Arc::new(SteenrodAlgebra::from_json(&json, "wall".parse().unwrap(), false).unwrap());
+    let module = Arc::new(steenrod_module::from_json(algebra, &json).unwrap());
+    let cc = Arc::new(CCC::ccdz(module));
+    assert!(UnstableResolution::new_with_save(cc, None).is_err());
+}
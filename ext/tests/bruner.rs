@@ -0,0 +1,95 @@
+use std::{path::Path, sync::Arc};
+
+use algebra::module::{FDModule, Module};
+use ext::{
+    bruner::{BrunerResolution, read_module, write_module, write_resolution},
+    chain_complex::{AugmentedChainComplex, ChainComplex, FreeChainComplex},
+    utils::construct_standard,
+};
+use sseq::coordinates::Bidegree;
+
+/// Write our resolution of `module` in Bruner's format, read it back, and check that the
+/// comparison map is the identity.
+fn check_roundtrip(module: &str, max: Bidegree) {
+    let tempdir = tempfile::TempDir::new().unwrap();
+
+    let res = Arc::new(construct_standard::<false, _, _>(module, None).unwrap());
+    res.compute_through_stem(max);
+    write_resolution(&*res, tempdir.path()).unwrap();
+
+    let bruner = BrunerResolution::read(tempdir.path(), res.target().module(0)).unwrap();
+    assert_eq!(bruner.max_s(), max.s());
+
+    let hom = bruner.comparison_map(Arc::clone(&res));
+    for b in res.iter_stem() {
+        let num_gens = res.number_of_gens_in_bidegree(b);
+        assert_eq!(
+            bruner
+                .complex()
+                .module(b.s())
+                .number_of_gens_in_degree(b.t()),
+            num_gens
+        );
+        let matrix = hom.get_map(b.s()).hom_k(b.t());
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, &v) in row.iter().enumerate() {
+                assert_eq!(v, u32::from(i == j), "Not the identity at {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn roundtrip_sphere() {
+    check_roundtrip("S_2@milnor", Bidegree::n_s(15, 6));
+}
+
+#[test]
+fn roundtrip_c2() {
+    check_roundtrip("C2@milnor", Bidegree::n_s(15, 6));
+}
+
+#[test]
+fn roundtrip_joker() {
+    check_roundtrip("Joker@milnor", Bidegree::n_s(12, 5));
+}
+
+#[test]
+fn read_bruner_sphere() {
+    let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/bruner_data");
+    let res = Arc::new(construct_standard::<false, _, _>("S_2@milnor", None).unwrap());
+
+    let bruner = BrunerResolution::read(&data_dir, res.target().module(0)).unwrap();
+    res.compute_through_stem(Bidegree::n_s(20, bruner.max_s()));
+
+    let hom = bruner.comparison_map(Arc::clone(&res));
+    assert_eq!(
+        hom.get_map(5).hom_k(20),
+        vec![vec![0, 1], vec![1, 0]],
+        "Change of basis at (15, 5)"
+    );
+}
+
+#[test]
+fn module_roundtrip() {
+    for name in ["Joker@milnor", "RP4@milnor", "C2@milnor"] {
+        let res = construct_standard::<false, _, _>(name, None).unwrap();
+        let module = FDModule::from(&*res.target().module(0));
+
+        let mut buffer = Vec::new();
+        write_module(&module, &mut buffer).unwrap();
+        let read = read_module(module.algebra(), String::new(), buffer.as_slice()).unwrap();
+
+        module.test_equal(&read).unwrap();
+    }
+}
+
+#[test]
+fn invalid_module() {
+    let res = construct_standard::<false, _, _>("S_2@milnor", None).unwrap();
+    let algebra = res.algebra();
+    // Sq^2 Sq^2 = Sq^3 Sq^1 fails since Sq^1 acts trivially
+    let input = "3\n0 2 4\n0 2 1 1\n1 2 1 2\n";
+    assert!(read_module(Arc::clone(&algebra), String::new(), input.as_bytes()).is_err());
+    assert!(read_module(algebra, String::new(), "2\n0 1\n0 1 1 5\n".as_bytes()).is_err());
+}
@@ -0,0 +1,289 @@
+use std::{sync::Arc, time::Duration};
+
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    checkpoint::{CancellationToken, Checkpoint, Interrupted},
+    resolution_homomorphism::ResolutionHomomorphism,
+    secondary::{SecondaryLift, SecondaryResolution},
+    utils::{construct_nassau, construct_standard},
+};
+use sseq::coordinates::Bidegree;
+
+fn cancelled() -> CancellationToken {
+    let token = CancellationToken::new();
+    token.cancel();
+    token
+}
+
+fn is_interrupted(result: anyhow::Result<()>) -> bool {
+    match result {
+        Ok(()) => false,
+        Err(e) => {
+            assert!(e.is::<Interrupted>(), "Unexpected error: {e}");
+            true
+        }
+    }
+}
+
+#[test]
+fn resolution_cancel_before_start() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(10, 4);
+
+    let res = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    assert!(is_interrupted(
+        res.compute_through_stem_with_cancel(max, &cancelled())
+    ));
+    assert!(!res.has_computed_bidegree(Bidegree::s_t(0, 0)));
+
+    let checkpoint = Checkpoint::read(tempdir.path(), "resolution")
+        .unwrap()
+        .unwrap();
+    assert!(!checkpoint.is_complete());
+    assert_eq!(checkpoint.remaining().next(), Some(Bidegree::s_t(0, 0)));
+
+    res.compute_through_stem_with_cancel(max, &CancellationToken::new())
+        .unwrap();
+    let checkpoint = Checkpoint::read(tempdir.path(), "resolution")
+        .unwrap()
+        .unwrap();
+    assert!(checkpoint.is_complete());
+    assert_eq!(checkpoint.target.len(), max.s() as usize + 1);
+}
+
+/// Cancel the computation from a different thread while it is running, and resume it in a new
+/// resolution loaded from the save directory.
+#[test]
+fn resolution_cancel_and_resume() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(30, 10);
+
+    let res = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    let token = CancellationToken::new();
+    let interrupted = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        });
+        is_interrupted(res.compute_through_stem_with_cancel(max, &token))
+    });
+
+    let checkpoint = Checkpoint::read(tempdir.path(), "resolution")
+        .unwrap()
+        .unwrap();
+    assert_eq!(checkpoint.is_complete(), !interrupted);
+    // The checkpoint agrees with what is in memory, and all rows are contiguous.
+    for (s, &t) in checkpoint.progress.iter().enumerate() {
+        let s = s as i32;
+        if t >= 0 {
+            assert!(res.has_computed_bidegree(Bidegree::s_t(s, t)));
+        }
+        assert!(!res.has_computed_bidegree(Bidegree::s_t(s, t + 1)) || t >= max.n() + s);
+    }
+    drop(res);
+
+    let resumed = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    resumed
+        .compute_through_stem_with_cancel(max, &CancellationToken::new())
+        .unwrap();
+
+    let local = construct_standard::<false, _, _>("S_2", None).unwrap();
+    local.compute_through_stem(max);
+    assert_eq!(
+        resumed.graded_dimension_string(),
+        local.graded_dimension_string()
+    );
+}
+
+#[test]
+fn nassau_cancel() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(10, 4);
+
+    let res = construct_nassau("S_2", Some(tempdir.path().into())).unwrap();
+    assert!(is_interrupted(
+        res.compute_through_stem_with_cancel(max, &cancelled())
+    ));
+    res.compute_through_stem_with_cancel(max, &CancellationToken::new())
+        .unwrap();
+    assert!(
+        Checkpoint::read(tempdir.path(), "resolution")
+            .unwrap()
+            .unwrap()
+            .is_complete()
+    );
+}
+
+#[test]
+fn homomorphism_cancel_and_resume() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(20, 8);
+
+    let res =
+        Arc::new(construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap());
+    res.compute_through_stem(max);
+
+    let h0 = |res: &Arc<_>| {
+        ResolutionHomomorphism::from_class(
+            String::from("h0"),
+            Arc::clone(res),
+            Arc::clone(res),
+            Bidegree::s_t(1, 1),
+            &[1],
+        )
+    };
+
+    let hom = h0(&res);
+    assert!(is_interrupted(
+        hom.extend_through_stem_with_cancel(max, &cancelled())
+    ));
+    let dir = tempdir.path().join("products/h0");
+    assert!(
+        !Checkpoint::read(&dir, "homomorphism")
+            .unwrap()
+            .unwrap()
+            .is_complete()
+    );
+
+    let token = CancellationToken::new();
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            token.cancel();
+        });
+        let _ = hom.extend_through_stem_with_cancel(max, &token);
+    });
+    // Resume in the same object.
+    hom.extend_through_stem_with_cancel(max, &CancellationToken::new())
+        .unwrap();
+    assert!(
+        Checkpoint::read(&dir, "homomorphism")
+            .unwrap()
+            .unwrap()
+            .is_complete()
+    );
+
+    let local_res = Arc::new(construct_standard::<false, _, _>("S_2", None).unwrap());
+    local_res.compute_through_stem(max);
+    let local = h0(&local_res);
+    local.extend_through_stem(max);
+
+    for s in 1..=max.s() {
+        let (f, g) = (hom.get_map(s), local.get_map(s));
+        for t in s..=max.n() + s {
+            for i in 0..res.number_of_gens_in_bidegree(Bidegree::s_t(s, t)) {
+                assert_eq!(
+                    f.output(t, i),
+                    g.output(t, i),
+                    "Mismatch at ({s}, {t}, {i})"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn secondary_cancel() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(14, 5);
+
+    let res = Arc::new(
+        construct_standard::<false, _, _>("S_2@milnor", Some(tempdir.path().into())).unwrap(),
+    );
+    res.compute_through_stem(max);
+
+    let lift = SecondaryResolution::new(Arc::clone(&res));
+    assert!(is_interrupted(lift.extend_all_with_cancel(&cancelled())));
+    assert!(
+        !Checkpoint::read(tempdir.path(), "secondary")
+            .unwrap()
+            .unwrap()
+            .is_complete()
+    );
+
+    lift.extend_all_with_cancel(&CancellationToken::new())
+        .unwrap();
+    assert!(
+        Checkpoint::read(tempdir.path(), "secondary")
+            .unwrap()
+            .unwrap()
+            .is_complete()
+    );
+
+    let local_res = Arc::new(construct_standard::<false, _, _>("S_2@milnor", None).unwrap());
+    local_res.compute_through_stem(max);
+    let local = SecondaryResolution::new(Arc::clone(&local_res));
+    local.extend_all();
+
+    for s in 3..=max.s() {
+        let (f, g) = (&lift.homotopy(s).homotopies, &local.homotopy(s).homotopies);
+        assert_eq!(f.next_degree(), g.next_degree());
+        for t in s..f.next_degree() {
+            for i in 0..res.number_of_gens_in_bidegree(Bidegree::s_t(s, t)) {
+                assert_eq!(
+                    f.output(t, i),
+                    g.output(t, i),
+                    "Mismatch at ({s}, {t}, {i})"
+                );
+            }
+        }
+    }
+}
+
+/// Cancel the secondary lift at an arbitrary point, which may be in the middle of the composites
+/// or intermediates, and resume it with a new lift on the same save directory.
+#[test]
+fn secondary_cancel_and_resume() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(20, 6);
+
+    let res = Arc::new(
+        construct_standard::<false, _, _>("S_2@milnor", Some(tempdir.path().into())).unwrap(),
+    );
+    res.compute_through_stem(max);
+
+    let lift = SecondaryResolution::new(Arc::clone(&res));
+    let token = CancellationToken::new();
+    let interrupted = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            token.cancel();
+        });
+        is_interrupted(lift.extend_all_with_cancel(&token))
+    });
+    assert_eq!(
+        Checkpoint::read(tempdir.path(), "secondary")
+            .unwrap()
+            .unwrap()
+            .is_complete(),
+        !interrupted
+    );
+    drop(lift);
+
+    let resumed = SecondaryResolution::new(Arc::clone(&res));
+    resumed
+        .extend_all_with_cancel(&CancellationToken::new())
+        .unwrap();
+
+    let local_res = Arc::new(construct_standard::<false, _, _>("S_2@milnor", None).unwrap());
+    local_res.compute_through_stem(max);
+    let local = SecondaryResolution::new(Arc::clone(&local_res));
+    local.extend_all();
+
+    for s in 3..=max.s() {
+        let (f, g) = (
+            &resumed.homotopy(s).homotopies,
+            &local.homotopy(s).homotopies,
+        );
+        assert_eq!(f.next_degree(), g.next_degree());
+        for t in s..f.next_degree() {
+            for i in 0..res.number_of_gens_in_bidegree(Bidegree::s_t(s, t)) {
+                assert_eq!(
+                    f.output(t, i),
+                    g.output(t, i),
+                    "Mismatch at ({s}, {t}, {i})"
+                );
+            }
+        }
+    }
+}
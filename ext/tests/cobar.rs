@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use algebra::{AlgebraType, SteenrodAlgebra, comodule::Comodule, module::FDModule};
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex, cobar_complex, cobar_homology_dimension},
+    utils::{construct, parse_module_name},
+};
+use rstest::rstest;
+use sseq::coordinates::Bidegree;
+
+/// Checks that the cobar complex of the dual comodule has the same homology as the resolution.
+#[rstest]
+#[trace]
+#[case("S_2", 4, 10)]
+#[case("C2", 3, 10)]
+#[case("Joker", 3, 12)]
+#[case("S_3", 3, 16)]
+#[case("Calpha", 2, 16)]
+fn cobar_vs_resolution(#[case] module_name: &str, #[case] max_s: u32, #[case] max_t: i32) {
+    let json = parse_module_name(module_name).unwrap();
+    let algebra = Arc::new(SteenrodAlgebra::from_json(&json, AlgebraType::Milnor, false).unwrap());
+    let comodule = Comodule::from_dual(FDModule::from_json(algebra, &json).unwrap()).unwrap();
+    let cobar = cobar_complex(&comodule, max_s, max_t);
+
+    let resolution = construct((module_name, "milnor"), None).unwrap();
+    resolution.compute_through_bidegree(Bidegree::s_t(max_s as i32, max_t));
+
+    for s in 0..=max_s as i32 {
+        for t in resolution.min_degree() + s..=max_t {
+            let b = Bidegree::s_t(s, t);
+            assert_eq!(
+                cobar_homology_dimension(&cobar, b),
+                resolution.number_of_gens_in_bidegree(b),
+                "at {b}"
+            );
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use ext::{
+    chain_complex::FreeChainComplex,
+    distributed::{Address, Coordinator, run_worker},
+    save::SaveKind,
+    utils::construct_standard,
+};
+use sseq::coordinates::Bidegree;
+
+/// Run a coordinator with `num_workers` workers, each in its own thread.
+fn run_distributed(module: &str, dir: &Path, address: &Address, num_workers: usize, max: Bidegree) {
+    let coordinator = Coordinator::bind(address).unwrap();
+    let address = coordinator.address().unwrap();
+
+    std::thread::scope(|s| {
+        for _ in 0..num_workers {
+            let address = address.clone();
+            s.spawn(move || {
+                let res = construct_standard::<false, _, _>(module, Some(dir.into())).unwrap();
+                run_worker(&res, &address).unwrap();
+            });
+        }
+        let res = construct_standard::<false, _, _>(module, Some(dir.into())).unwrap();
+        coordinator.run(&res, max).unwrap();
+    });
+}
+
+fn check_against_local(module: &str, dir: &Path, max: Bidegree) {
+    let mut loaded = construct_standard::<false, _, _>(module, Some(dir.into())).unwrap();
+    // Everything should be loaded from the save directory.
+    loaded.should_save = false;
+    loaded.compute_through_stem(max);
+
+    let local = construct_standard::<false, _, _>(module, None).unwrap();
+    local.compute_through_stem(max);
+
+    assert_eq!(
+        loaded.graded_dimension_string(),
+        local.graded_dimension_string()
+    );
+}
+
+#[test]
+fn distributed_tcp() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(20, 8);
+    run_distributed(
+        "S_2",
+        tempdir.path(),
+        &"tcp:127.0.0.1:0".parse().unwrap(),
+        3,
+        max,
+    );
+    check_against_local("S_2", tempdir.path(), max);
+}
+
+#[cfg(unix)]
+#[test]
+fn distributed_unix() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let socket = tempdir.path().join("socket");
+    let max = Bidegree::n_s(12, 5);
+    run_distributed("C2", tempdir.path(), &Address::Unix(socket.clone()), 2, max);
+    check_against_local("C2", tempdir.path(), max);
+    assert!(!socket.exists());
+}
+
+/// Simulate a worker that crashed while writing a bidegree, and check that the bidegree is
+/// recomputed while the rest is reused.
+#[test]
+fn distributed_stale_lock() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let max = Bidegree::n_s(15, 6);
+    let b = Bidegree::n_s(10, 3);
+
+    let res = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    res.compute_through_stem(max);
+    drop(res);
+
+    // Truncate the differential and lock the bidegree.
+    let differential =
+        tempdir
+            .path()
+            .join(format!("differentials/{}_{}_differential", b.s(), b.t()));
+    std::fs::write(&differential, b"garbage").unwrap();
+    SaveKind::Lock.create_dir(tempdir.path()).unwrap();
+    let lock = tempdir
+        .path()
+        .join(format!("locks/{}_{}_lock", b.s(), b.t()));
+    std::fs::write(&lock, b"0\n").unwrap();
+
+    run_distributed(
+        "S_2",
+        tempdir.path(),
+        &"127.0.0.1:0".parse().unwrap(),
+        1,
+        max,
+    );
+    assert!(!lock.exists());
+    check_against_local("S_2", tempdir.path(), max);
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use algebra::{
+    Algebra, MilnorAlgebra, SteenrodAlgebra,
+    module::{FreeModule, Module, homomorphism::FreeModuleHomomorphism},
+};
+use ext::chain_complex::{ChainComplex, FiniteChainComplex};
+use fp::prime::ValidPrime;
+use sseq::coordinates::Bidegree;
+
+#[test]
+fn has_computed_bidegree() {
+    let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+        ValidPrime::new(2),
+        false,
+    )));
+    algebra.compute_basis(10);
+    let module = FreeModule::new(algebra, String::from("F"), 0);
+    module.add_generators(0, 1, None);
+    module.extend_by_zero(5);
+    assert_eq!(module.max_computed_degree(), 5);
+
+    let cc: FiniteChainComplex<_, FreeModuleHomomorphism<_>> =
+        FiniteChainComplex::ccdz(Arc::new(module));
+
+    // The top computed degree counts as computed
+    assert!(cc.has_computed_bidegree(Bidegree::s_t(0, 5)));
+    assert!(!cc.has_computed_bidegree(Bidegree::s_t(0, 6)));
+    // So does everything past the last module, which is zero
+    assert!(cc.has_computed_bidegree(Bidegree::s_t(1, 0)));
+    assert!(cc.has_computed_bidegree(Bidegree::s_t(1, 20)));
+}
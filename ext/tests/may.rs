@@ -0,0 +1,84 @@
+use expect_test::expect;
+use ext::{chain_complex::ChainComplex, may::MaySpectralSequence, utils::construct};
+use fp::{prime::TWO, vector::FpVector};
+use itertools::Itertools;
+use sseq::{
+    May, SseqProfile,
+    coordinates::{Bidegree, MultiDegree},
+};
+
+fn differential(may: &MaySpectralSequence, r: i32, b: MultiDegree<3>, idx: usize) -> String {
+    let target_b = May::profile(r, b);
+    let mut source = FpVector::new(TWO, may.e1_basis(b).len());
+    source.set_entry(idx, 1);
+    let mut target = FpVector::new(TWO, may.e1_basis(target_b).len());
+    may.sseq().differentials(b)[r].evaluate(source.as_slice(), target.as_slice_mut());
+    format!(
+        "d_{r}({}) = {}",
+        may.e1_basis(b)[idx],
+        may.element_to_string(target_b, target.as_slice())
+    )
+}
+
+#[test]
+fn may_differentials() {
+    let may = MaySpectralSequence::new(Bidegree::s_t(3, 12));
+
+    let result = [
+        // h_20, h_21 and h_30
+        differential(&may, 1, MultiDegree::new([2, 1, 2]), 0),
+        differential(&may, 1, MultiDegree::new([5, 1, 2]), 0),
+        differential(&may, 1, MultiDegree::new([6, 1, 3]), 0),
+        // b_20
+        differential(&may, 2, MultiDegree::new([4, 2, 4]), 0),
+    ];
+    expect![[r#"
+        d_1(h_{20}) = h_{10} h_{11}
+        d_1(h_{21}) = h_{11} h_{12}
+        d_1(h_{30}) = h_{10} h_{21} + h_{12} h_{20}
+        d_2(h_{20}^2) = h_{10}^2 h_{12} + h_{11}^3"#]]
+    .assert_eq(&result.join("\n"));
+}
+
+#[test]
+fn may_detects_ext() {
+    let max = Bidegree::s_t(4, 15);
+    let may = MaySpectralSequence::new(max);
+    let resolution = construct("S_2@milnor", None).unwrap();
+    resolution.compute_through_bidegree(max);
+
+    let names = may
+        .detect_generators(&resolution)
+        .unwrap()
+        .into_iter()
+        .map(|(g, elem)| format!("{g}: {}", may.element_to_string(elem.degree(), elem.vec())))
+        .join("\n");
+    expect![[r#"
+        (0, 0, 0): 1
+        (0, 1, 0): h_{10}
+        (1, 1, 0): h_{11}
+        (3, 1, 0): h_{12}
+        (7, 1, 0): h_{13}
+        (0, 2, 0): h_{10}^2
+        (2, 2, 0): h_{11}^2
+        (3, 2, 0): h_{10} h_{12}
+        (6, 2, 0): h_{12}^2
+        (7, 2, 0): h_{10} h_{13}
+        (8, 2, 0): h_{11} h_{13}
+        (0, 3, 0): h_{10}^3
+        (3, 3, 0): h_{11}^3
+        (7, 3, 0): h_{10}^2 h_{13}
+        (8, 3, 0): h_{11}^2 h_{30} + h_{11} h_{20} h_{21}
+        (9, 3, 0): h_{12}^3
+        (0, 4, 0): h_{10}^4
+        (7, 4, 0): h_{10}^3 h_{13}
+        (9, 4, 0): h_{11}^3 h_{30} + h_{11}^2 h_{20} h_{21}"#]]
+    .assert_eq(&names);
+
+    // The E_∞ classes detecting the generators represent them
+    for (g, elem) in may.detect_generators(&resolution).unwrap() {
+        let x = may.ext_class(&resolution, &elem).unwrap();
+        assert_eq!(x.degree(), g.degree());
+        assert!(!x.vec().is_zero());
+    }
+}
@@ -1,4 +1,9 @@
-use ext::utils::{LoadModuleError, load_module_json, parse_module_name};
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    utils::{LoadModuleError, construct, load_module_json, parse_module_name},
+};
+use rstest::rstest;
+use sseq::coordinates::Bidegree;
 
 #[test]
 fn valid_module_name_parses_ok() {
@@ -28,3 +33,66 @@ fn non_integer_shift_suffix_is_err_not_panic() {
 fn unterminated_shift_bracket_is_err() {
     assert!(parse_module_name("S_2[5").is_err());
 }
+
+#[test]
+fn shift_adds_to_existing_shift() {
+    let module = parse_module_name("S_2[2][3]").unwrap();
+    assert_eq!(module["shift"], 5);
+}
+
+/// Each expression should have the same Ext as the module it evaluates to.
+#[rstest]
+#[trace]
+#[case("C2 ^ Ceta", "C2_sm_Ceta")]
+#[case("D(Joker)[4]", "Joker")]
+#[case("D(D(Csigma))", "Csigma")]
+#[case("D(C3)", "C3")]
+#[case("D(C3v1)[6]", "C3v1")]
+#[case("(C2 ^ S_2[1])[-1]", "C2")]
+fn expression(#[case] expr: &str, #[case] expected: &str) {
+    let max = Bidegree::s_t(10, 25);
+    let a = construct(expr, None).unwrap();
+    let b = construct(expected, None).unwrap();
+
+    a.compute_through_bidegree(max);
+    b.compute_through_bidegree(max);
+
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+}
+
+#[test]
+fn direct_sum() {
+    let max = Bidegree::s_t(10, 25);
+    let sum = construct("C2 + Ceta[1] + C2", None).unwrap();
+    let c2 = construct("C2", None).unwrap();
+    let ceta = construct("Ceta[1]", None).unwrap();
+
+    sum.compute_through_bidegree(max);
+    c2.compute_through_bidegree(max);
+    ceta.compute_through_bidegree(max);
+
+    for b in sum.iter_stem() {
+        assert_eq!(
+            sum.number_of_gens_in_bidegree(b),
+            2 * c2.number_of_gens_in_bidegree(b) + ceta.number_of_gens_in_bidegree(b),
+            "at {b}"
+        );
+    }
+}
+
+#[rstest]
+#[case("C2 ^ C3")]
+#[case("C2 ^ RP_inf")]
+#[case("D(C2")]
+#[case("C2 ^")]
+#[case("C2 + + C2")]
+#[case("C2 C2")]
+fn invalid_expression_is_err(#[case] expr: &str) {
+    assert!(parse_module_name(expr).is_err());
+}
+
+#[test]
+fn dual_of_infinite_module_is_err_not_panic() {
+    assert!(parse_module_name("D(RP_-4_inf)").is_err());
+    assert!(parse_module_name("C2 ^ RP_-4_inf").is_err());
+}
@@ -0,0 +1,106 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, mpsc},
+};
+
+use ext::{
+    chain_complex::ChainComplex,
+    progress::{Progress, ProgressEvent},
+    resolution_homomorphism::ResolutionHomomorphism,
+    secondary::{SecondaryLift, SecondaryResolution},
+    utils::{construct_nassau, construct_standard},
+};
+use sseq::coordinates::Bidegree;
+
+/// Check that every bidegree is reported exactly once and that the counts are consistent.
+fn check_events(receiver: &mpsc::Receiver<ProgressEvent>) -> Vec<ProgressEvent> {
+    let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+    assert!(!events.is_empty());
+
+    let total = events[0].total;
+    assert_eq!(events.len(), total);
+    for (i, event) in events.iter().enumerate() {
+        assert_eq!(event.completed, i + 1);
+        assert_eq!(event.total, total);
+    }
+    assert_eq!(events.last().unwrap().eta, std::time::Duration::ZERO);
+
+    let bidegrees: HashSet<Bidegree> = events.iter().map(|e| e.b).collect();
+    assert_eq!(bidegrees.len(), events.len());
+    events
+}
+
+#[test]
+fn resolution_progress() {
+    let (sender, receiver) = mpsc::channel();
+    let progress = Progress::new(sender);
+    let max = Bidegree::n_s(12, 5);
+
+    let res = construct_standard::<false, _, _>("S_2", None).unwrap();
+    res.compute_through_stem_with_progress(max, &progress);
+    let events = check_events(&receiver);
+    assert_eq!(
+        events.len(),
+        (0..=max.s())
+            .map(|s| (max.n() + s + 1) as usize)
+            .sum::<usize>()
+    );
+    let h0 = events.iter().find(|e| e.b == Bidegree::s_t(1, 1)).unwrap();
+    assert_eq!((h0.source_dimension, h0.target_dimension), (1, 1));
+
+    // Already computed bidegrees are not reported.
+    res.compute_through_stem_with_progress(max + Bidegree::n_s(2, 0), &progress);
+    assert_eq!(check_events(&receiver).len(), 2 * (max.s() as usize + 1));
+
+    res.compute_through_bidegree_with_progress(Bidegree::s_t(7, 20), &progress);
+    check_events(&receiver);
+}
+
+#[test]
+fn nassau_progress() {
+    let (sender, receiver) = mpsc::channel();
+    let progress = Progress::new(sender);
+
+    let res = construct_nassau("S_2", None).unwrap();
+    res.compute_through_stem_with_progress(Bidegree::n_s(12, 5), &progress);
+    check_events(&receiver);
+}
+
+#[test]
+fn homomorphism_progress() {
+    let (sender, receiver) = mpsc::channel();
+    let progress = Progress::new(sender);
+    let max = Bidegree::n_s(12, 5);
+
+    let res = Arc::new(construct_standard::<false, _, _>("S_2", None).unwrap());
+    res.compute_through_stem(max);
+
+    let hom = ResolutionHomomorphism::from_class(
+        String::from("h0"),
+        Arc::clone(&res),
+        Arc::clone(&res),
+        Bidegree::s_t(1, 1),
+        &[1],
+    );
+    hom.extend_through_stem_with_progress(max, &progress);
+    let events = check_events(&receiver);
+    assert!(events.iter().all(|e| e.task == "Extending h0"));
+
+    hom.extend_with_progress(Bidegree::s_t(3, 8), &progress);
+    // Everything has already been computed.
+    assert_eq!(receiver.try_iter().count(), 0);
+}
+
+#[test]
+fn secondary_progress() {
+    let (sender, receiver) = mpsc::channel();
+    let progress = Progress::new(sender);
+
+    let res = Arc::new(construct_standard::<false, _, _>("S_2@milnor", None).unwrap());
+    res.compute_through_stem(Bidegree::n_s(14, 5));
+
+    let lift = SecondaryResolution::new(Arc::clone(&res));
+    lift.extend_all_with_progress(&progress);
+    check_events(&receiver);
+    assert_eq!(res.next_homological_degree(), 6);
+}
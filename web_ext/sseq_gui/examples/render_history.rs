@@ -0,0 +1,110 @@
+//! Replays a history file saved by the GUI and renders the resulting spectral sequence.
+//!
+//! The chart is written to stdout in the requested format. By default all steps of the history are
+//! replayed; a smaller number of steps renders the chart as it was partway through the
+//! calculation. All structlines are drawn.
+//!
+//! Answering `all` for the page renders every page from $E_2$ to $E_\infty$, with the classes that
+//! have died faded out. In svg format, page $E_r$ is written to `[prefix]_E[r].svg`, where the
+//! last page is `[prefix]_Einf.svg`. In tikz format, the pages are written to stdout as a single
+//! LaTeX document with one page each.
+//!
+//! # Usage
+//! ```text
+//! cargo run --example render_history -- tests/benchmarks/s_2.save svg 3 > S_2_e3.svg
+//! cargo run --example render_history -- tests/benchmarks/s_2.save svg all 10 S_2
+//! ```
+
+use std::{fs::File, io::Write};
+
+use anyhow::{Context, anyhow};
+use sseq::charting::{SvgBackend, TikzBackend};
+use sseq_gui::{actions::SseqChoice, history::History, sseq::SseqWrapper};
+
+fn main() -> anyhow::Result<()> {
+    let history = query::raw("History file", |path| {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {path}"))?
+            .parse::<History>()
+    });
+    let format = query::with_default("Output format (svg or tikz)", "svg", |x| match x {
+        "svg" | "tikz" => Ok(x.to_owned()),
+        _ => Err(format!("Unknown format {x}")),
+    });
+    let page = query::with_default("Page (or all)", "2", |x| match x {
+        "all" => Ok(None),
+        _ => match x.parse::<i32>() {
+            Ok(r) if r >= 2 => Ok(Some(r)),
+            _ => Err(format!("Invalid page {x}")),
+        },
+    });
+    let steps = query::with_default("Steps", &history.steps().to_string(), |x| {
+        x.parse::<usize>()
+            .ok()
+            .filter(|&n| n <= history.steps())
+            .ok_or_else(|| format!("Number of steps must be at most {}", history.steps()))
+    });
+
+    let replay = history.replay(steps)?;
+    let sseq = replay
+        .sseq(SseqChoice::Main)
+        .ok_or_else(|| anyhow!("History does not resolve a module"))?;
+
+    let Some(page) = page else {
+        return render_pages(sseq, &format);
+    };
+
+    let out = std::io::stdout().lock();
+    match &*format {
+        "svg" => sseq.write_to_graph(SvgBackend::new(out), page, true, |_| true)?,
+        "tikz" => sseq.write_to_graph(TikzBackend::new(out), page, true, |_| true)?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn render_pages(sseq: &SseqWrapper, format: &str) -> anyhow::Result<()> {
+    let last_page = sseq.inner.last_page();
+
+    match format {
+        "svg" => {
+            let prefix = query::raw("Output prefix", |x| Ok::<_, String>(x.to_owned()));
+            sseq.write_pages(
+                |r| {
+                    let path = if r == last_page {
+                        format!("{prefix}_Einf.svg")
+                    } else {
+                        format!("{prefix}_E{r}.svg")
+                    };
+                    Ok(SvgBackend::new(File::create(path)?))
+                },
+                |_| true,
+            )?;
+        }
+        "tikz" => {
+            let mut out = std::io::stdout();
+            writeln!(out, r"\documentclass{{article}}")?;
+            writeln!(out, r"\usepackage{{tikz}}")?;
+            writeln!(out, r"\begin{{document}}")?;
+            sseq.write_pages(
+                |r| {
+                    let mut out = std::io::stdout();
+                    if r > 2 {
+                        writeln!(out, r"\newpage")?;
+                    }
+                    let name = if r == last_page {
+                        String::from(r"\infty")
+                    } else {
+                        r.to_string()
+                    };
+                    writeln!(out, r"\section*{{$E_{{{name}}}$}}")?;
+                    Ok(TikzBackend::new(out))
+                },
+                |_| true,
+            )?;
+            writeln!(out, r"\end{{document}}")?;
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
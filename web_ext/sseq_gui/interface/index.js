@@ -59,6 +59,8 @@ for (const [k, v] of url.searchParams.entries()) {
 if (params.module || params.module_json) {
     const maxDegree = parseInt(params.degree ? params.degree : 40);
     const algebra = params.algebra ? params.algebra : 'milnor';
+    // Whether to compute d2 differentials with the secondary resolution
+    window.computeSecondary = params.secondary === 'true';
 
     const action = params.module
         ? {
@@ -73,6 +75,10 @@ if (params.module || params.module_json) {
                 data: params.module_json,
             },
         };
+    // Load precomputed data from a save directory on the server
+    if (params.save_dir) {
+        Object.values(action)[0].save_dir = params.save_dir;
+    }
 
     // Record this for the save functionality, since the wasm version modifies it
     window.constructCommand = {
@@ -91,6 +97,7 @@ if (params.module || params.module_json) {
                 action: {
                     Resolve: {
                         max_degree: maxDegree,
+                        secondary: window.computeSecondary,
                     },
                 },
             },
@@ -156,6 +163,7 @@ function generateHistory() {
         action: {
             Resolve: {
                 max_degree: window.mainSseq.maxDegree,
+                secondary: window.computeSecondary,
             },
         },
     });
@@ -200,6 +208,7 @@ function loadHistory(hist) {
 
     // First command is construct and second command is resolve
     window.constructCommand = JSON.parse(lines[0]);
+    window.computeSecondary = !!JSON.parse(lines[1]).action.Resolve.secondary;
     window.sendSocket = openSocket(
         lines.splice(0, 2).map(JSON.parse),
         onMessage,
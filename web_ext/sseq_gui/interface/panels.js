@@ -150,6 +150,10 @@ export const ACTION_TO_DISPLAY = {
         ];
     },
 
+    NameClasses: () => {
+        return ['Name classes', []];
+    },
+
     SetClassName: (details, sseq) => {
         const x = details.x;
         const y = details.y;
@@ -416,6 +420,7 @@ function* overviewPanel(sseq) {
     yield intercept;
 
     yield createButton('Resolve further', () => sseq.resolveFurther());
+    yield createButton('Name classes', () => sseq.nameClasses());
 }
 
 function* structlinePanel(sseq) {
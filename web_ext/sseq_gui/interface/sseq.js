@@ -44,6 +44,7 @@ const KEEP_LOG = new Set([
     'AddProductDifferential',
     'AddPermanentClass',
     'SetClassName',
+    'NameClasses',
 ]);
 
 function bidegreeToCoordinates({ coords }) {
@@ -271,6 +272,13 @@ export class ExtSseq {
         });
     }
 
+    nameClasses() {
+        this.send({
+            recipients: ['Resolver'],
+            action: { NameClasses: {} },
+        });
+    }
+
     // addProductInteractive takes in the number of classes in bidegree (x, y), because this should be the number of classes in the *unit* spectral sequence, not the main spectral sequence
     addProductInteractive(x, y, num) {
         const b = coordinatesToBidegree(x, y);
@@ -438,6 +446,8 @@ export class ExtSseq {
                 action: {
                     Resolve: {
                         max_degree: newmax,
+                        secondary:
+                            this.name == 'Main' && window.computeSecondary,
                     },
                 },
             });
@@ -463,6 +473,8 @@ export class ExtSseq {
                     action: {
                         Resolve: {
                             max_degree: newmax,
+                            secondary:
+                                this.name == 'Main' && window.computeSecondary,
                         },
                     },
                 });
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use algebra::module::Module;
 use bivec::BiVec;
 use enum_dispatch::enum_dispatch;
@@ -8,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use sseq::coordinates::{Bidegree, BidegreeElement, BidegreeGenerator};
 
 use crate::{
+    history_tree::BranchState,
     resolution_wrapper::Resolution,
     sseq::{ClassState, ProductItem, SseqWrapper},
 };
@@ -48,12 +51,21 @@ pub enum Action {
     AddPermanentClass,
     AddDifferential,
     SetClassName,
+    NameClasses,
     Clear,
     BlockRefresh,
+    Undo,
+    Redo,
+    Branch,
+    Checkout,
+    DeleteBranch,
+    QueryHistory,
 
     // Resolver -> Sseq
     AddProduct,
     AddClass,
+    SetClassNames,
+    AddComputedDifferential,
 
     // Resolver -> JS
     Resolving,
@@ -69,6 +81,7 @@ pub enum Action {
     SetDifferential,
     SetClass,
     SetPageList,
+    HistoryState,
 
     // Queries
     QueryTable,
@@ -177,15 +190,150 @@ impl ActionT for SetClassName {
     }
 }
 
+/// Names all computed classes by their standard names, as computed by
+/// [`ext::ext_algebra::names`]. The resolver replies with a [`SetClassNames`] message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameClasses {}
+
+impl ActionT for NameClasses {
+    fn act_resolution(&self, resolution: &mut Resolution<CCC>) -> Option<Message> {
+        let action = match resolution.class_names() {
+            Ok(names) => Action::from(SetClassNames { names }),
+            Err(e) => Action::from(Error {
+                message: format!("{e:?}"),
+            }),
+        };
+        Some(Message {
+            recipients: vec![],
+            sseq: SseqChoice::Main, // This will be overwritten
+            action,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetClassNames {
+    pub names: Vec<SetClassName>,
+}
+
+impl ActionT for SetClassNames {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        for name in &self.names {
+            name.act_sseq(sseq);
+        }
+        None
+    }
+}
+
+/// A differential computed by the resolver, as opposed to one entered by the user. These are
+/// kept when the user actions are cleared. See [`Resolve::secondary`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddComputedDifferential {
+    pub b: Bidegree,
+    pub r: i32,
+    pub source: Vec<u32>,
+    pub target: Vec<u32>,
+}
+
+impl ActionT for AddComputedDifferential {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        let source = BidegreeElement::new(self.b, FpVector::from_slice(sseq.p, &self.source));
+        let target = FpVector::from_slice(sseq.p, &self.target);
+        sseq.add_computed_differential(self.r, source, target);
+        None
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Clear {}
 impl ActionT for Clear {
     fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
-        sseq.clear();
+        sseq.reset();
         None
     }
 }
 
+/// Replies with the [`HistoryState`] of `sseq` if `result` is `Ok`, and an [`Error`] otherwise.
+fn history_reply(sseq: &SseqWrapper, result: anyhow::Result<()>) -> Option<Message> {
+    let action = match result {
+        Ok(()) => Action::from(sseq.history_state()),
+        Err(e) => Action::from(Error {
+            message: format!("{e:?}"),
+        }),
+    };
+    Some(Message {
+        recipients: vec![],
+        sseq: SseqChoice::Main, // This will be overwritten
+        action,
+    })
+}
+
+/// Undoes the last user action of the current branch. Unlike the undo of the frontend, which
+/// clears the spectral sequence and resends its own history, this uses the history tree kept by
+/// the backend. See [`crate::history_tree`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Undo {}
+impl ActionT for Undo {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        sseq.undo();
+        history_reply(sseq, Ok(()))
+    }
+}
+
+/// Redoes the most recently undone user action of the current branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Redo {}
+impl ActionT for Redo {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        sseq.redo();
+        history_reply(sseq, Ok(()))
+    }
+}
+
+/// Creates a new branch at the current state and switches to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Branch {
+    pub name: String,
+}
+impl ActionT for Branch {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        let result = sseq.branch(&self.name);
+        history_reply(sseq, result)
+    }
+}
+
+/// Switches to an existing branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkout {
+    pub name: String,
+}
+impl ActionT for Checkout {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        let result = sseq.checkout(&self.name);
+        history_reply(sseq, result)
+    }
+}
+
+/// Deletes a branch other than the current one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteBranch {
+    pub name: String,
+}
+impl ActionT for DeleteBranch {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        let result = sseq.delete_branch(&self.name);
+        history_reply(sseq, result)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistory {}
+impl ActionT for QueryHistory {
+    fn act_sseq(&self, sseq: &mut SseqWrapper) -> Option<Message> {
+        history_reply(sseq, Ok(()))
+    }
+}
+
 /// This blocks the sseq object from recomputing classes and edges. This is useful when performing
 /// a large number of operations in a row, e.g. when loading files or undoing.
 ///
@@ -289,6 +437,10 @@ impl ActionT for Complete {}
 pub struct Construct {
     pub module_name: String,
     pub algebra_name: String,
+    /// A save directory to load precomputed data from and save new data to. Bidegrees already
+    /// saved are sent immediately. This is only supported by the native server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub save_dir: Option<PathBuf>,
 }
 impl ActionT for Construct {}
 
@@ -296,12 +448,19 @@ impl ActionT for Construct {}
 pub struct ConstructJson {
     pub data: String,
     pub algebra_name: String,
+    /// See [`Construct::save_dir`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub save_dir: Option<PathBuf>,
 }
 impl ActionT for ConstructJson {}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resolve {
     pub max_degree: i32,
+    /// Whether to also compute the $d_2$ differentials with the secondary resolution. These are
+    /// sent to the spectral sequence as [`AddComputedDifferential`]s.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secondary: bool,
 }
 impl ActionT for Resolve {}
 
@@ -345,6 +504,15 @@ pub struct SetPageList {
 }
 impl ActionT for SetPageList {}
 
+/// The branches of the history tree, sent in reply to [`Undo`], [`Redo`], [`Branch`],
+/// [`Checkout`], [`DeleteBranch`] and [`QueryHistory`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryState {
+    pub branch: String,
+    pub branches: Vec<BranchState>,
+}
+impl ActionT for HistoryState {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryTable {
     pub b: Bidegree,
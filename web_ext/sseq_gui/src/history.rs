@@ -0,0 +1,241 @@
+//! Reading, writing and replaying history files.
+//!
+//! A history file is what the "Save" button of the GUI produces, and is documented in
+//! `FORMAT.md`. It is a newline-separated list of JSON encoded [`Message`]s. The first few
+//! messages construct and resolve the module, and the remaining messages are the user actions, each
+//! of which is called a step.
+//!
+//! A [`History`] can be replayed without a frontend with [`History::replay`], which feeds the
+//! messages to a [`HeadlessManager`]. The resulting spectral sequence can then be drawn with
+//! [`SseqWrapper::write_to_graph`](crate::sseq::SseqWrapper::write_to_graph), e.g.
+//!
+//! ```no_run
+//! # use sseq_gui::{actions::SseqChoice, history::History};
+//! # use sseq::charting::SvgBackend;
+//! let history: History = std::fs::read_to_string("S_2.save")?.parse()?;
+//! let replay = history.replay(history.steps())?;
+//! let sseq = replay.sseq(SseqChoice::Main).unwrap();
+//! sseq.write_to_graph(SvgBackend::new(std::io::stdout()), 2, true, |_| true)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::{
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+use anyhow::Context;
+
+use crate::actions::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::managers::HeadlessManager;
+
+/// A parsed history file.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub messages: Vec<Message>,
+}
+
+impl History {
+    pub fn new(messages: Vec<Message>) -> Self {
+        Self { messages }
+    }
+
+    /// Reads a history file. Blank lines and lines starting with `//` are ignored.
+    pub fn read(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut messages = Vec::new();
+        let mut line = String::new();
+        let mut line_number = 0;
+        while reader.read_line(&mut line)? > 0 {
+            line_number += 1;
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with("//") {
+                messages.push(
+                    serde_json::from_str(trimmed)
+                        .with_context(|| format!("Invalid message on line {line_number}"))?,
+                );
+            }
+            line.clear();
+        }
+        Ok(Self { messages })
+    }
+
+    /// Writes the history in the format read by [`History::read`] and the GUI.
+    pub fn write(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        for message in &self.messages {
+            serde_json::to_writer(&mut writer, message)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// The number of messages that set up the spectral sequence, i.e. the leading
+    /// [`Construct`], [`ConstructJson`] and [`Resolve`] messages.
+    pub fn setup_len(&self) -> usize {
+        self.messages
+            .iter()
+            .take_while(|m| {
+                matches!(
+                    m.action,
+                    Action::Construct(_) | Action::ConstructJson(_) | Action::Resolve(_)
+                )
+            })
+            .count()
+    }
+
+    /// The number of steps, i.e. user actions after the setup messages.
+    pub fn steps(&self) -> usize {
+        self.messages.len() - self.setup_len()
+    }
+
+    /// The actions performed in each step.
+    pub fn actions(&self) -> impl Iterator<Item = &Action> {
+        self.messages[self.setup_len()..].iter().map(|m| &m.action)
+    }
+
+    /// Appends a user action acting on `sseq`. The recipients are chosen the same way the GUI
+    /// chooses them.
+    pub fn push(&mut self, sseq: SseqChoice, action: impl Into<Action>) {
+        let action = action.into();
+        let recipients = match action {
+            Action::AddProductType(_) | Action::AddProductDifferential(_) => {
+                vec![Recipient::Sseq, Recipient::Resolver]
+            }
+            Action::Construct(_)
+            | Action::ConstructJson(_)
+            | Action::Resolve(_)
+            | Action::NameClasses(_) => vec![Recipient::Resolver],
+            _ => vec![Recipient::Sseq],
+        };
+        self.messages.push(Message {
+            recipients,
+            sseq,
+            action,
+        });
+    }
+
+    /// Replays the setup and the first `steps` steps of the history.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn replay(&self, steps: usize) -> anyhow::Result<HeadlessManager> {
+        let mut replay = HeadlessManager::new();
+        for msg in &self.messages[..self.setup_len() + steps] {
+            replay
+                .process(msg.clone())
+                .with_context(|| format!("Failed to replay {msg}"))?;
+        }
+        Ok(replay)
+    }
+}
+
+impl FromStr for History {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Self::read(s.as_bytes())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use sseq::{charting::SvgBackend, coordinates::Bidegree};
+
+    use super::*;
+
+    /// Draws the pages of the main spectral sequence and lists its branches.
+    fn state(manager: &HeadlessManager) -> String {
+        let sseq = manager.sseq(SseqChoice::Main).unwrap();
+        let mut out = Vec::new();
+        for r in 2..5 {
+            sseq.write_to_graph(SvgBackend::new(&mut out), r, true, |_| true)
+                .unwrap();
+        }
+        let mut out = String::from_utf8(out).unwrap();
+        out.push_str(&format!("{:?}", sseq.history_state().branches));
+        out
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let mut history = History::default();
+        history.push(
+            SseqChoice::Main,
+            Construct {
+                module_name: "S_2".to_owned(),
+                algebra_name: "milnor".to_owned(),
+                save_dir: None,
+            },
+        );
+        history.push(
+            SseqChoice::Main,
+            Resolve {
+                max_degree: 20,
+                secondary: false,
+            },
+        );
+        history.push(
+            SseqChoice::Main,
+            AddProductType {
+                b: Bidegree::n_s(0, 1),
+                class: vec![1],
+                name: "h0".to_owned(),
+                permanent: true,
+            },
+        );
+        history.push(
+            SseqChoice::Main,
+            AddDifferential {
+                b: Bidegree::n_s(15, 1),
+                r: 2,
+                source: vec![1],
+                target: vec![1],
+            },
+        );
+        history.push(SseqChoice::Main, Undo {});
+        history.push(
+            SseqChoice::Main,
+            Branch {
+                name: "permanent".to_owned(),
+            },
+        );
+        history.push(
+            SseqChoice::Main,
+            AddPermanentClass {
+                b: Bidegree::n_s(15, 1),
+                class: vec![1],
+            },
+        );
+        history.push(
+            SseqChoice::Main,
+            Checkout {
+                name: crate::history_tree::MAIN_BRANCH.to_owned(),
+            },
+        );
+        history.push(SseqChoice::Main, Redo {});
+        history.push(
+            SseqChoice::Main,
+            SetClassName {
+                b: Bidegree::n_s(15, 1),
+                idx: 0,
+                name: "h_4".to_owned(),
+            },
+        );
+
+        // Record the session as the GUI would, one message at a time
+        let mut session = HeadlessManager::new();
+        for msg in &history.messages {
+            session.process(msg.clone()).unwrap();
+        }
+        assert_eq!(history.steps(), 8);
+
+        let mut saved = Vec::new();
+        history.write(&mut saved).unwrap();
+        let loaded = History::read(&saved[..]).unwrap();
+        assert_eq!(loaded.steps(), history.steps());
+
+        let replay = loaded.replay(loaded.steps()).unwrap();
+        let expected = state(&session);
+        assert_eq!(state(&replay), expected);
+        // Replaying only part of the history gives a different state
+        assert_ne!(state(&loaded.replay(3).unwrap()), expected);
+    }
+}
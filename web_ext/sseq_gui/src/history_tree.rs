@@ -0,0 +1,393 @@
+//! A tree of user actions, used to implement undo, redo and branching.
+//!
+//! Every node other than the root is labelled by the action that takes the spectral sequence of
+//! its parent to its own. The current state of the spectral sequence is given by a node, and is
+//! obtained by applying the actions on the path from the root to this node. Applying an action
+//! moves to a child of the current node, reusing an existing child if it has the same action. In
+//! particular, clearing the spectral sequence and re-applying a prefix of the actions moves back
+//! along the existing path, which is how the frontend implements undo.
+//!
+//! A branch is a name pointing to a node. There is always a current branch, which follows the
+//! current node around. The tree itself does not know about spectral sequences; see
+//! [`SseqWrapper::goto`](crate::sseq::SseqWrapper::goto) for how it is used.
+
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use sseq::coordinates::Bidegree;
+
+use crate::actions::Action;
+
+/// The name of the branch that exists from the start.
+pub const MAIN_BRANCH: &str = "main";
+
+struct Node {
+    parent: Option<usize>,
+    /// The action taking the parent to this node, and its serialization for comparison. This is
+    /// `None` for the root.
+    action: Option<(Action, String)>,
+    children: Vec<usize>,
+    /// The child that was most recently visited, which is where redo goes.
+    last_child: Option<usize>,
+    depth: usize,
+}
+
+struct Branch {
+    node: usize,
+    /// The inconsistent bidegrees when we were last on this branch.
+    inconsistent: Vec<Bidegree>,
+}
+
+/// The state of a branch as reported to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchState {
+    pub name: String,
+    /// The number of actions on the branch.
+    pub depth: usize,
+    pub current: bool,
+    /// The bidegrees involved in an inconsistent differential, as of the last time the branch was
+    /// current.
+    pub inconsistent: Vec<Bidegree>,
+}
+
+pub struct HistoryTree {
+    nodes: Vec<Node>,
+    current: usize,
+    branch: String,
+    branches: BTreeMap<String, Branch>,
+}
+
+impl Default for HistoryTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryTree {
+    pub fn new() -> Self {
+        let mut branches = BTreeMap::new();
+        branches.insert(
+            MAIN_BRANCH.to_owned(),
+            Branch {
+                node: 0,
+                inconsistent: Vec::new(),
+            },
+        );
+        Self {
+            nodes: vec![Node {
+                parent: None,
+                action: None,
+                children: Vec::new(),
+                last_child: None,
+                depth: 0,
+            }],
+            current: 0,
+            branch: MAIN_BRANCH.to_owned(),
+            branches,
+        }
+    }
+
+    /// Whether the action is recorded in the history tree.
+    pub fn records(action: &Action) -> bool {
+        matches!(
+            action,
+            Action::AddDifferential(_)
+                | Action::AddPermanentClass(_)
+                | Action::AddProductType(_)
+                | Action::AddProductDifferential(_)
+                | Action::SetClassName(_)
+                | Action::SetClassNames(_)
+        )
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Moves to `node`, taking the current branch along.
+    pub fn set_current(&mut self, node: usize) {
+        if let Some(parent) = self.nodes[node].parent {
+            self.nodes[parent].last_child = Some(node);
+        }
+        self.current = node;
+        self.branches.get_mut(&self.branch).unwrap().node = node;
+    }
+
+    /// Records that `action` has been applied to the current node, and moves to the resulting
+    /// node.
+    pub fn push(&mut self, action: &Action) {
+        let key = serde_json::to_string(action).unwrap();
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .copied()
+            .find(|&c| matches!(&self.nodes[c].action, Some((_, k)) if *k == key));
+
+        let node = existing.unwrap_or_else(|| {
+            let node = self.nodes.len();
+            self.nodes.push(Node {
+                parent: Some(self.current),
+                action: Some((action.clone(), key)),
+                children: Vec::new(),
+                last_child: None,
+                depth: self.nodes[self.current].depth + 1,
+            });
+            self.nodes[self.current].children.push(node);
+            node
+        });
+        self.set_current(node);
+    }
+
+    /// The node undo moves to, if any.
+    pub fn undo_target(&self) -> Option<usize> {
+        self.nodes[self.current].parent
+    }
+
+    /// The node redo moves to, if any.
+    pub fn redo_target(&self) -> Option<usize> {
+        self.nodes[self.current].last_child
+    }
+
+    /// Creates a branch at the current node and switches to it.
+    pub fn create_branch(&mut self, name: &str, inconsistent: Vec<Bidegree>) -> anyhow::Result<()> {
+        if self.branches.contains_key(name) {
+            return Err(anyhow!("Branch {name} already exists"));
+        }
+        self.leave_branch(inconsistent);
+        self.branches.insert(
+            name.to_owned(),
+            Branch {
+                node: self.current,
+                inconsistent: Vec::new(),
+            },
+        );
+        self.branch = name.to_owned();
+        Ok(())
+    }
+
+    /// Switches to the branch `name`, returning the node it points to. The caller is responsible
+    /// for moving to the node.
+    pub fn checkout(&mut self, name: &str, inconsistent: Vec<Bidegree>) -> anyhow::Result<usize> {
+        let node = self
+            .branches
+            .get(name)
+            .ok_or_else(|| anyhow!("No branch named {name}"))?
+            .node;
+        self.leave_branch(inconsistent);
+        self.branch = name.to_owned();
+        Ok(node)
+    }
+
+    /// Deletes the branch `name`, which must not be the current branch. The actions on it are
+    /// kept in the tree.
+    pub fn delete_branch(&mut self, name: &str) -> anyhow::Result<()> {
+        if name == self.branch {
+            return Err(anyhow!("Cannot delete the current branch"));
+        }
+        self.branches
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("No branch named {name}"))
+    }
+
+    fn leave_branch(&mut self, inconsistent: Vec<Bidegree>) {
+        self.branches.get_mut(&self.branch).unwrap().inconsistent = inconsistent;
+    }
+
+    /// The actions to apply to get from the current node to `target`. The first entry is whether
+    /// we have to clear the spectral sequence first, which is the case unless `target` is a
+    /// descendant of the current node.
+    pub fn path_to(&self, target: usize) -> (bool, Vec<Action>) {
+        let mut actions = Vec::new();
+        let mut node = target;
+        while node != self.current {
+            let Some(parent) = self.nodes[node].parent else {
+                // We reached the root without passing through the current node
+                actions.reverse();
+                return (true, actions);
+            };
+            actions.push(self.nodes[node].action.as_ref().unwrap().0.clone());
+            node = parent;
+        }
+        actions.reverse();
+        (false, actions)
+    }
+
+    /// The state of the branches. The inconsistencies of the current branch are `inconsistent`.
+    pub fn branches(&self, inconsistent: Vec<Bidegree>) -> Vec<BranchState> {
+        let mut inconsistent = Some(inconsistent);
+        self.branches
+            .iter()
+            .map(|(name, branch)| {
+                let current = *name == self.branch;
+                BranchState {
+                    name: name.clone(),
+                    depth: self.nodes[branch.node].depth,
+                    current,
+                    inconsistent: if current {
+                        inconsistent.take().unwrap()
+                    } else {
+                        branch.inconsistent.clone()
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::SetClassName;
+
+    fn name(idx: usize) -> Action {
+        Action::from(SetClassName {
+            b: Bidegree::n_s(0, 0),
+            idx,
+            name: format!("x_{idx}"),
+        })
+    }
+
+    fn names(actions: &[Action]) -> Vec<String> {
+        actions
+            .iter()
+            .map(|a| match a {
+                Action::SetClassName(a) => a.name.clone(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// Moves to `target` the way [`SseqWrapper::goto`](crate::sseq::SseqWrapper::goto) does,
+    /// returning the result of [`HistoryTree::path_to`].
+    fn goto(tree: &mut HistoryTree, target: usize) -> (bool, Vec<String>) {
+        let (clear, actions) = tree.path_to(target);
+        tree.set_current(target);
+        (clear, names(&actions))
+    }
+
+    fn depths(tree: &HistoryTree) -> Vec<(String, usize, bool)> {
+        tree.branches(Vec::new())
+            .into_iter()
+            .map(|b| (b.name, b.depth, b.current))
+            .collect()
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut tree = HistoryTree::new();
+        assert_eq!(tree.undo_target(), None);
+        assert_eq!(tree.redo_target(), None);
+
+        tree.push(&name(0));
+        tree.push(&name(1));
+        let top = tree.current();
+
+        let target = tree.undo_target().unwrap();
+        assert_eq!(goto(&mut tree, target), (true, vec!["x_0".to_owned()]));
+        let target = tree.undo_target().unwrap();
+        assert_eq!(goto(&mut tree, target), (true, vec![]));
+        assert_eq!(tree.current(), 0);
+        assert_eq!(tree.undo_target(), None);
+
+        let target = tree.redo_target().unwrap();
+        assert_eq!(goto(&mut tree, target), (false, vec!["x_0".to_owned()]));
+        let target = tree.redo_target().unwrap();
+        assert_eq!(goto(&mut tree, target), (false, vec!["x_1".to_owned()]));
+        assert_eq!(tree.current(), top);
+        assert_eq!(tree.redo_target(), None);
+        assert_eq!(depths(&tree), [(MAIN_BRANCH.to_owned(), 2, true)]);
+    }
+
+    #[test]
+    fn reapply_reuses_nodes() {
+        let mut tree = HistoryTree::new();
+        tree.push(&name(0));
+        let node = tree.current();
+        tree.set_current(0);
+        tree.push(&name(0));
+        assert_eq!(tree.current(), node);
+    }
+
+    #[test]
+    fn branch_after_undo() {
+        let mut tree = HistoryTree::new();
+        tree.push(&name(0));
+        let first = tree.current();
+        tree.push(&name(1));
+        let old = tree.current();
+
+        let target = tree.undo_target().unwrap();
+        goto(&mut tree, target);
+        tree.push(&name(2));
+        let new = tree.current();
+        assert_ne!(new, old);
+        assert_eq!(tree.undo_target(), Some(first));
+
+        // Redo follows the most recently visited child
+        goto(&mut tree, first);
+        assert_eq!(tree.redo_target(), Some(new));
+
+        // The old node is still reachable, through the common ancestor
+        assert_eq!(goto(&mut tree, old), (false, vec!["x_1".to_owned()]));
+        assert_eq!(tree.redo_target(), None);
+        goto(&mut tree, first);
+        assert_eq!(tree.redo_target(), Some(old));
+    }
+
+    #[test]
+    fn switch_branches() {
+        let mut tree = HistoryTree::new();
+        tree.push(&name(0));
+        tree.create_branch("side", vec![Bidegree::n_s(1, 1)])
+            .unwrap();
+        assert!(tree.create_branch("side", Vec::new()).is_err());
+        assert_eq!(tree.branch(), "side");
+
+        tree.push(&name(1));
+        tree.push(&name(2));
+        let side = tree.current();
+
+        let main = tree.checkout(MAIN_BRANCH, Vec::new()).unwrap();
+        assert_eq!(goto(&mut tree, main), (true, vec!["x_0".to_owned()]));
+        assert_eq!(tree.branch(), MAIN_BRANCH);
+        tree.push(&name(3));
+
+        let branches = tree.branches(Vec::new());
+        assert_eq!(branches[0].name, MAIN_BRANCH);
+        assert_eq!(branches[0].depth, 2);
+        assert!(branches[0].current);
+        assert_eq!(branches[1].name, "side");
+        assert_eq!(branches[1].depth, 3);
+        assert!(!branches[1].current);
+        assert_eq!(branches[1].inconsistent, []);
+
+        // Moving the main branch did not move the side branch
+        assert_eq!(
+            tree.checkout("side", vec![Bidegree::n_s(2, 1)]).unwrap(),
+            side
+        );
+        assert_eq!(
+            goto(&mut tree, side),
+            (
+                true,
+                vec!["x_0".to_owned(), "x_1".to_owned(), "x_2".to_owned()]
+            )
+        );
+        assert_eq!(
+            tree.branches(Vec::new())[0].inconsistent,
+            [Bidegree::n_s(2, 1)]
+        );
+
+        assert!(tree.checkout("missing", Vec::new()).is_err());
+        assert!(tree.delete_branch("side").is_err());
+        tree.checkout(MAIN_BRANCH, Vec::new()).unwrap();
+        tree.delete_branch("side").unwrap();
+        assert_eq!(depths(&tree), [(MAIN_BRANCH.to_owned(), 2, true)]);
+    }
+}
@@ -3,6 +3,8 @@
 pub mod sseq;
 
 pub mod actions;
+pub mod history;
+pub mod history_tree;
 pub mod managers;
 pub mod resolution_wrapper;
 #[cfg(target_arch = "wasm32")]
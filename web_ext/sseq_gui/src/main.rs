@@ -1,4 +1,9 @@
-use std::{fs, path::PathBuf, thread};
+use std::{
+    fs,
+    io::{BufRead, Write},
+    path::PathBuf,
+    thread,
+};
 
 use sseq_gui::{Sender, actions::*, managers::*};
 use textwrap::Options;
@@ -258,15 +263,56 @@ impl Server {
     }
 }
 
+/// Drives the managers through stdin and stdout instead of a WebSocket.
+///
+/// Each line of stdin is a JSON encoded [`Message`], and for each message we write the messages the
+/// managers send in response to stdout, one per line. Every recipient of a message sends a
+/// [`Complete`] when it is done, so a client knows a request has been fully processed once it has
+/// received as many [`Complete`]s as the request has recipients.
+fn run_stdio() -> anyhow::Result<()> {
+    let mut manager = HeadlessManager::new();
+    let mut stdout = std::io::stdout().lock();
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let responses = match serde_json::from_str::<Message>(&line) {
+            Ok(msg) => manager.respond(msg),
+            Err(e) => vec![Message {
+                recipients: Vec::new(),
+                sseq: SseqChoice::Main,
+                action: Action::from(Error {
+                    message: format!("Failed to parse message:\n{line}\nError: {e}"),
+                }),
+            }],
+        };
+        for msg in responses {
+            serde_json::to_writer(&mut stdout, &msg)?;
+            writeln!(stdout)?;
+        }
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let mut port = "8080";
     if args.len() > 1 {
         match args[1].as_ref() {
             "--help" => {
-                println!("Usage: ext-websocket [PORT]");
+                println!("Usage: sseq_gui [PORT | --stdio]");
                 std::process::exit(0)
             }
+            "--stdio" => {
+                if let Err(e) = run_stdio() {
+                    eprintln!("{e:?}");
+                    std::process::exit(1);
+                }
+                return;
+            }
             _ => port = &args[1],
         }
     };
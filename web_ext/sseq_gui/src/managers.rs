@@ -6,9 +6,13 @@ use ext::{
     utils::load_module_json,
 };
 use serde_json::json;
-use sseq::coordinates::Bidegree;
 
-use crate::{Sender, actions::*, resolution_wrapper::Resolution, sseq::SseqWrapper};
+use crate::{
+    Sender,
+    actions::*,
+    resolution_wrapper::{Resolution, resolve_bound},
+    sseq::SseqWrapper,
+};
 
 /// A struct that manipulates a Resolution.
 ///
@@ -94,6 +98,7 @@ impl ResolutionManager {
         let resolution = Resolution::new_from_json(
             json_data,
             &action.algebra_name,
+            action.save_dir,
             SseqChoice::Main,
             self.sender.clone(),
         )?;
@@ -106,6 +111,7 @@ impl ResolutionManager {
         let resolution = Resolution::new_from_json(
             json,
             &action.algebra_name,
+            action.save_dir,
             SseqChoice::Main,
             self.sender.clone(),
         )?;
@@ -127,6 +133,7 @@ impl ResolutionManager {
                     "actions": [],
                 }),
                 resolution.algebra().prefix(),
+                None,
                 SseqChoice::Unit,
                 self.sender.clone(),
             )
@@ -150,21 +157,37 @@ impl ResolutionManager {
             resolution.set_unit_resolution(unit_resolution);
         }
 
+        // Send everything that is already in the save directory so that the user doesn't have to
+        // wait for a Resolve to see it.
+        let saved_stem = resolution.saved_stem();
         self.resolution = Some(resolution);
+        if let Some(max_degree) = saved_stem {
+            self.resolve(
+                Resolve {
+                    max_degree,
+                    secondary: false,
+                },
+                SseqChoice::Main,
+            )?;
+        }
         Ok(())
     }
 
-    fn resolve(&self, action: Resolve, sseq: SseqChoice) -> anyhow::Result<()> {
+    fn resolve(&mut self, action: Resolve, sseq: SseqChoice) -> anyhow::Result<()> {
+        let is_unit = self.is_unit;
         let resolution = self
             .resolution
-            .as_ref()
+            .as_mut()
             .ok_or_else(|| anyhow!("Calling Resolve before Construct"))?;
         let resolution = match sseq {
             SseqChoice::Main => resolution,
-            SseqChoice::Unit => resolution.unit_resolution(),
+            SseqChoice::Unit => resolution.unit_resolution_mut(),
         };
 
         let min_degree = resolution.min_degree();
+        // Don't hide bidegrees that are already computed, e.g. if they were loaded from a save
+        // directory.
+        let max_degree = action.max_degree.max(resolution.max_computed().n());
 
         let msg = Message {
             recipients: vec![],
@@ -172,14 +195,17 @@ impl ResolutionManager {
             action: Action::from(Resolving {
                 p: resolution.prime(),
                 min_degree,
-                max_degree: action.max_degree,
-                is_unit: self.is_unit,
+                max_degree,
+                is_unit,
             }),
         };
         self.sender.send(msg)?;
 
-        resolution
-            .compute_through_stem(Bidegree::n_s(action.max_degree, action.max_degree / 2 + 5));
+        resolution.compute_through_stem(resolve_bound(action.max_degree));
+
+        if action.secondary {
+            resolution.compute_d2()?;
+        }
 
         Ok(())
     }
@@ -224,6 +250,8 @@ impl SseqManager {
             action,
             Action::AddClass(_)
                 | Action::AddProduct(_)
+                | Action::AddComputedDifferential(_)
+                | Action::SetClassNames(_)
                 | Action::Complete(_)
                 | Action::QueryTableResult(_)
                 | Action::QueryBoundaryStringResult(_)
@@ -249,8 +277,13 @@ impl SseqManager {
             Action::Error(_) => self.relay(msg)?,
             _ => {
                 if let Some(sseq) = self.get_sseq(msg.sseq) {
-                    msg.action.act_sseq(sseq);
+                    sseq.record(&msg.action);
+                    let ret = msg.action.act_sseq(sseq);
                     sseq.refresh();
+                    if let Some(mut m) = ret {
+                        m.sseq = target_sseq;
+                        self.sender.send(m)?;
+                    }
                 }
             }
         };
@@ -275,6 +308,14 @@ impl SseqManager {
             .unwrap()
     }
 
+    /// The spectral sequence managed under `sseq`, if it has been created.
+    pub fn sseq(&self, sseq: SseqChoice) -> Option<&SseqWrapper> {
+        match sseq {
+            SseqChoice::Main => self.sseq.as_ref(),
+            SseqChoice::Unit => self.unit_sseq.as_ref(),
+        }
+    }
+
     fn get_sseq(&mut self, sseq: SseqChoice) -> Option<&mut SseqWrapper> {
         match sseq {
             SseqChoice::Main => self.sseq.as_mut(),
@@ -300,3 +341,76 @@ impl SseqManager {
         Ok(())
     }
 }
+
+/// Runs a [`ResolutionManager`] and a [`SseqManager`] without a frontend.
+///
+/// Unlike the GUI server, messages are processed synchronously. Each message is fully processed,
+/// including the messages it causes the resolver to send to the [`SseqManager`], before
+/// [`HeadlessManager::respond`] returns.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct HeadlessManager {
+    resolution: ResolutionManager,
+    sseq: SseqManager,
+    resolution_receiver: std::sync::mpsc::Receiver<Message>,
+    output_receiver: std::sync::mpsc::Receiver<Message>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for HeadlessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl HeadlessManager {
+    pub fn new() -> Self {
+        let (resolution_sender, resolution_receiver) = std::sync::mpsc::channel();
+        let (output_sender, output_receiver) = std::sync::mpsc::channel();
+        Self {
+            resolution: ResolutionManager::new(resolution_sender),
+            sseq: SseqManager::new(output_sender),
+            resolution_receiver,
+            output_receiver,
+        }
+    }
+
+    /// Dispatches a message to its recipients in order, and returns the messages that would be
+    /// sent to the frontend. As in the GUI, each recipient eventually responds with a
+    /// [`Complete`] message.
+    pub fn respond(&mut self, msg: Message) -> Vec<Message> {
+        for recipient in &msg.recipients {
+            match recipient {
+                Recipient::Sseq => self.sseq.process_message(msg.clone()),
+                Recipient::Resolver => self.resolution.process_message(msg.clone()),
+            }
+            for m in self.resolution_receiver.try_iter() {
+                self.sseq.process_message(m);
+            }
+        }
+        self.output_receiver.try_iter().collect()
+    }
+
+    /// Processes a message and discards the responses. Returns an error if either manager reports
+    /// one.
+    pub fn process(&mut self, msg: Message) -> anyhow::Result<()> {
+        let errors: Vec<String> = self
+            .respond(msg)
+            .into_iter()
+            .filter_map(|m| match m.action {
+                Action::Error(e) => Some(e.message),
+                _ => None,
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(errors.join("\n")))
+        }
+    }
+
+    /// The spectral sequence as of the last processed message.
+    pub fn sseq(&self, sseq: SseqChoice) -> Option<&SseqWrapper> {
+        self.sseq.sseq(sseq)
+    }
+}
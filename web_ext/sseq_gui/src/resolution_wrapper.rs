@@ -1,14 +1,16 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use algebra::{
-    Algebra,
+    Algebra, MilnorAlgebra,
     module::{FreeModule, Module, homomorphism::FreeModuleHomomorphism},
 };
 use anyhow::{Context, anyhow};
 use ext::{
     chain_complex::{AugmentedChainComplex, ChainComplex, FreeChainComplex},
+    ext_algebra::{ExtAlgebra, SecondaryExtAlgebra, names::ClassNamer},
     resolution::Resolution as ResolutionInner,
     resolution_homomorphism::ResolutionHomomorphism as ResolutionHomomorphism_,
+    save::SaveKind,
 };
 use fp::{matrix::Matrix, prime::ValidPrime};
 use once::OnceBiVec;
@@ -17,7 +19,7 @@ use serde::Deserialize;
 use serde_json::Value;
 use sseq::coordinates::Bidegree;
 
-use crate::actions::{Action, Message};
+use crate::actions::{Action, AddComputedDifferential, Message, SetClassName};
 pub type ResolutionHomomorphism<CC> =
     ResolutionHomomorphism_<ResolutionInner<CC>, ResolutionInner<CC>>;
 
@@ -63,17 +65,35 @@ pub struct Resolution<CC: ChainComplex> {
 
     /// A list of all self maps
     self_maps: Vec<SelfMap<CC>>,
+
+    /// The secondary resolution used to compute $d_2$ differentials, if they were requested.
+    secondary: Option<SecondaryExtAlgebra<ResolutionInner<ext::CCC>>>,
+
+    /// The bidegrees whose $d_2$ differentials have been sent.
+    d2_sent: HashSet<Bidegree>,
+}
+
+/// The bidegree [`Resolve`](crate::actions::Resolve) resolves through when asked to resolve
+/// through stem `max_degree`.
+pub fn resolve_bound(max_degree: i32) -> Bidegree {
+    Bidegree::n_s(max_degree, max_degree / 2 + 5)
 }
 
 impl Resolution<ext::CCC> {
+    /// Constructs the resolution of the module specified by `json`. If `save_dir` is given,
+    /// precomputed data is loaded from it and newly computed data is saved to it.
     pub fn new_from_json(
         json: Value,
         algebra_name: &str,
+        save_dir: Option<PathBuf>,
         sseq: crate::actions::SseqChoice,
         sender: crate::Sender,
     ) -> anyhow::Result<Self> {
+        if cfg!(target_arch = "wasm32") && save_dir.is_some() {
+            return Err(anyhow!("Save directories are not supported in the browser"));
+        }
         let inner = Arc::new(
-            ext::utils::construct((json.clone(), algebra_name), None)
+            ext::utils::construct((json.clone(), algebra_name), save_dir)
                 .context("Failed to construct resolution from module json")?,
         );
         let algebra = inner.algebra();
@@ -90,6 +110,8 @@ impl Resolution<ext::CCC> {
             filtration_one_products: algebra.default_filtration_one_products(),
             self_maps: Vec::new(),
             chain_maps_to_unit_resolution: OnceBiVec::new(0),
+            secondary: None,
+            d2_sent: HashSet::default(),
         };
 
         // Add products
@@ -157,9 +179,123 @@ impl Resolution<ext::CCC> {
 
         Ok(result)
     }
+
+    /// The standard names of the classes computed so far, given by [`ClassNamer::standard`].
+    /// Classes without a standard name are omitted.
+    pub fn class_names(&self) -> anyhow::Result<Vec<SetClassName>> {
+        let max = self.max_computed();
+        let unit = self.unit_resolution();
+        if !std::ptr::eq(unit, self) {
+            unit.compute_through_stem(max);
+        }
+
+        let ext = ExtAlgebra::new(Arc::clone(&self.inner), Arc::clone(&unit.inner));
+        let names = ClassNamer::standard(self.prime()).name_through_stem(&ext, max)?;
+        Ok(names
+            .iter()
+            .filter_map(|(g, name)| {
+                Some(SetClassName {
+                    b: g.degree(),
+                    idx: g.idx(),
+                    name: name.name()?.to_owned(),
+                })
+            })
+            .collect())
+    }
+}
+
+impl Resolution<ext::CCC> {
+    /// Computes the $d_2$ differentials of the computed part of the resolution using
+    /// [`SecondaryExtAlgebra`], and sends those that haven't been sent yet as
+    /// [`AddComputedDifferential`] messages. This only works at the prime 2 with the Milnor basis.
+    pub fn compute_d2(&mut self) -> anyhow::Result<()> {
+        if self.prime() != 2 {
+            return Err(anyhow!("Computing d2 is only supported at the prime 2"));
+        }
+        let _: &MilnorAlgebra = (&*self.algebra())
+            .try_into()
+            .context("Computing d2 requires the Milnor basis")?;
+
+        let inner = Arc::clone(&self.inner);
+        let secondary = self.secondary.get_or_insert_with(|| {
+            SecondaryExtAlgebra::new(Arc::new(ExtAlgebra::without_unit(inner)))
+        });
+        secondary.extend_all();
+
+        let e2 = secondary.ext_algebra();
+        let d2_shift = Bidegree::n_s(-1, 2);
+        for b in e2.resolution().iter_nonzero_stem() {
+            if b.s() < 1 || self.d2_sent.contains(&b) {
+                continue;
+            }
+            let target = b + d2_shift;
+            if !e2.resolution().has_computed_bidegree(target) {
+                continue;
+            }
+            self.d2_sent.insert(b);
+            if e2.dimension(target) == 0 {
+                continue;
+            }
+            // We also send the zero differentials, since knowing that they vanish is
+            // information as well.
+            for g in e2.basis(b) {
+                let x = e2.generator(g);
+                let dx = secondary.d2(&x).unwrap();
+                self.sender
+                    .send(Message {
+                        recipients: vec![],
+                        sseq: self.sseq,
+                        action: Action::from(AddComputedDifferential {
+                            r: 2,
+                            b,
+                            source: x.vec().iter().collect(),
+                            target: dx.vec().iter().collect(),
+                        }),
+                    })
+                    .unwrap();
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<CC: ChainComplex> Resolution<CC> {
+    /// The largest stem and the largest filtration among the computed bidegrees.
+    pub fn max_computed(&self) -> Bidegree {
+        self.inner.iter_stem().fold(Bidegree::zero(), |max, b| {
+            Bidegree::n_s(max.n().max(b.n()), max.s().max(b.s()))
+        })
+    }
+
+    /// The largest stem `n` such that everything [`Resolve`](crate::actions::Resolve) computes
+    /// when resolving through stem `n` is saved in the save directory, i.e. the resolution is
+    /// saved through [`resolve_bound(n)`](resolve_bound). This returns `None` if there is no save
+    /// directory or nothing is saved.
+    pub fn saved_stem(&self) -> Option<i32> {
+        let dir = self.inner.save_dir().read()?;
+        let saved = |n, s| {
+            self.inner
+                .save_file(SaveKind::Differential, Bidegree::n_s(n, s))
+                .exists(dir.clone())
+        };
+        let min_degree = self.min_degree();
+        let mut max_s = -1;
+        let mut n = min_degree;
+        loop {
+            let s = resolve_bound(n).s();
+            // The filtration bound grows with the stem, so the previous stems have to be saved in
+            // the new filtrations as well.
+            let saved = (max_s + 1..=s).all(|s| (min_degree..n).all(|n| saved(n, s)))
+                && (0..=s).all(|s| saved(n, s));
+            if !saved {
+                break;
+            }
+            max_s = s;
+            n += 1;
+        }
+        (n > min_degree).then_some(n - 1)
+    }
+
     pub fn compute_through_stem(&self, b: Bidegree) {
         self.inner
             .compute_through_stem_with_callback(b, |b| self.step_after(b));
@@ -484,3 +620,83 @@ impl<CC: ChainComplex> Resolution<CC> {
         self.inner.differential(s)
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::collections::HashMap;
+
+    use ext::utils::load_module_json;
+
+    use super::*;
+    use crate::actions::SseqChoice;
+
+    #[test]
+    fn d2_sphere() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut resolution = Resolution::new_from_json(
+            load_module_json("S_2").unwrap(),
+            "milnor",
+            None,
+            SseqChoice::Main,
+            sender,
+        )
+        .unwrap();
+        resolution.compute_through_stem(Bidegree::n_s(19, 8));
+        resolution.compute_d2().unwrap();
+
+        let d2: HashMap<_, _> = receiver
+            .try_iter()
+            .filter_map(|m| match m.action {
+                Action::AddComputedDifferential(d) => Some(((d.b, d.source), d.target)),
+                _ => None,
+            })
+            .collect();
+
+        // d2(h4) = h0 h3^2
+        assert_eq!(d2[&(Bidegree::n_s(15, 1), vec![1])], [1]);
+        // d2(e0) = h1^2 d0
+        assert_eq!(d2[&(Bidegree::n_s(17, 4), vec![1])], [1]);
+        // d2(h1) = 0
+        assert_eq!(d2[&(Bidegree::n_s(1, 1), vec![1])], [0]);
+        // d2(h0 h4) = h0^2 h3^2 = 0
+        assert_eq!(d2[&(Bidegree::n_s(15, 2), vec![1])], [0]);
+
+        // Computing again does not resend the differentials
+        resolution.compute_d2().unwrap();
+        assert!(
+            receiver
+                .try_iter()
+                .all(|m| !matches!(m.action, Action::AddComputedDifferential(_)))
+        );
+    }
+
+    #[test]
+    fn saved_stem() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let (sender, _receiver) = std::sync::mpsc::channel();
+        let construct = || {
+            Resolution::new_from_json(
+                load_module_json("S_2").unwrap(),
+                "milnor",
+                Some(tempdir.path().to_owned()),
+                SseqChoice::Main,
+                sender.clone(),
+            )
+            .unwrap()
+        };
+
+        let resolution = construct();
+        assert_eq!(resolution.saved_stem(), None);
+
+        // Only the bottom filtrations are saved, which is not enough for any Resolve
+        resolution.compute_through_stem(Bidegree::n_s(20, 3));
+        assert_eq!(resolution.saved_stem(), None);
+
+        resolution.compute_through_stem(resolve_bound(8));
+        assert_eq!(construct().saved_stem(), Some(8));
+
+        // Stem 10 needs filtration 10, which is not saved in stem 0
+        resolution.compute_through_stem(Bidegree::n_s(10, 9));
+        assert_eq!(construct().saved_stem(), Some(9));
+    }
+}
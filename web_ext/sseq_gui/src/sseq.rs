@@ -4,7 +4,7 @@ use bivec::BiVec;
 use fp::{
     matrix::{Matrix, Subquotient},
     prime::ValidPrime,
-    vector::FpSlice,
+    vector::{FpSlice, FpVector},
 };
 use once::MultiIndexed;
 use serde::{Deserialize, Serialize};
@@ -13,7 +13,7 @@ use sseq::{
     coordinates::{Bidegree, BidegreeElement},
 };
 
-use crate::{Sender, actions::*};
+use crate::{Sender, actions::*, history_tree::HistoryTree};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum ClassState {
@@ -67,6 +67,12 @@ pub struct SseqWrapper<P: SseqProfile<2> = Adams> {
     products: BTreeMap<String, Product>,
     /// bidegree -> idx -> name
     class_names: MultiIndexed<2, Vec<String>>,
+    /// Differentials computed by the resolver rather than entered by the user. These survive
+    /// [`Self::clear`].
+    computed_differentials: Vec<(i32, BidegreeElement, FpVector)>,
+    /// The user actions applied so far, together with the ones that were undone or live on other
+    /// branches.
+    history: HistoryTree,
 }
 
 impl<P: SseqProfile<2>> SseqWrapper<P> {
@@ -81,6 +87,8 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
             products: BTreeMap::default(),
             class_names: MultiIndexed::new(),
             stale: MultiIndexed::new(),
+            computed_differentials: Vec::new(),
+            history: HistoryTree::new(),
         }
     }
 
@@ -96,6 +104,53 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
         }
 
         self.inner.clear();
+
+        let computed_differentials = std::mem::take(&mut self.computed_differentials);
+        for (r, source, target) in &computed_differentials {
+            self.inner.add_differential(*r, source, target.as_slice());
+            self.add_differential_propagate(*r, source, 0);
+        }
+        self.computed_differentials = computed_differentials;
+    }
+
+    /// Clears out all the user actions, including class names, and moves to the root of the
+    /// history tree. The history tree itself is kept, so re-applying the same actions reuses the
+    /// existing nodes.
+    pub fn reset(&mut self) {
+        self.clear();
+        self.reset_class_names();
+        self.history.set_current(0);
+    }
+
+    fn reset_class_names(&mut self) {
+        let degrees: Vec<Bidegree> = self.inner.iter_degrees().collect();
+        for b in degrees {
+            let names = Self::default_class_names(b, self.inner.dimension(b));
+            if let Some(current) = self.class_names.get_mut(b)
+                && *current != names
+            {
+                *current = names;
+                self.send_class_data(b);
+            }
+        }
+    }
+
+    fn default_class_names(b: Bidegree, dim: usize) -> Vec<String> {
+        if dim == 1 {
+            vec![format!("x_{{{x},{y}}}", x = b.x(), y = b.y())]
+        } else {
+            (0..dim)
+                .map(|i| format!("x_{{{x}, {y}}}^{{({i})}}", x = b.x(), y = b.y()))
+                .collect()
+        }
+    }
+
+    /// The bidegrees involved in an inconsistent differential.
+    pub fn inconsistent_degrees(&self) -> Vec<Bidegree> {
+        self.inner
+            .iter_degrees()
+            .filter(|&b| self.inner.inconsistent(b))
+            .collect()
     }
 
     pub fn refresh(&mut self) {
@@ -171,6 +226,51 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
         }
     }
 
+    /// The names of the products known to the spectral sequence, in alphabetical order.
+    pub fn product_names(&self) -> impl Iterator<Item = &str> {
+        self.products.keys().map(String::as_str)
+    }
+
+    /// Draws the $E_r$ page using a [`sseq::charting::Backend`]. Only the products whose names
+    /// satisfy `filter` are drawn as structlines.
+    ///
+    /// This requires the spectral sequence to be up to date, i.e. [`Self::refresh`] must have been
+    /// called since the last modification, which is always the case when it is driven by
+    /// [`crate::managers::SseqManager`].
+    pub fn write_to_graph<T: sseq::charting::Backend>(
+        &self,
+        g: T,
+        r: i32,
+        differentials: bool,
+        filter: impl Fn(&str) -> bool,
+    ) -> Result<(), T::Error> {
+        let products = self.filtered_products(filter);
+        self.inner
+            .write_to_graph(g, r, differentials, products.iter(), |_| Ok(()))
+    }
+
+    /// Draws every page from $E_2$ to $E_\infty$ using [`sseq::Sseq::write_pages`]. Only the
+    /// products whose names satisfy `filter` are drawn as structlines.
+    ///
+    /// As with [`Self::write_to_graph`], the spectral sequence must be up to date.
+    pub fn write_pages<T: sseq::charting::Backend>(
+        &self,
+        backend: impl FnMut(i32) -> Result<T, T::Error>,
+        filter: impl Fn(&str) -> bool,
+    ) -> Result<(), T::Error> {
+        let products = self.filtered_products(filter);
+        self.inner
+            .write_pages(backend, products.iter(), |_, _| Ok(()))
+    }
+
+    fn filtered_products(&self, filter: impl Fn(&str) -> bool) -> Vec<(String, sseq::Product<2>)> {
+        self.products
+            .iter()
+            .filter(|(name, _)| filter(name))
+            .map(|(name, prod)| (name.clone(), prod.inner.clone()))
+            .collect()
+    }
+
     /// Computes products whose source is at `b`.
     fn send_products(&self, b: Bidegree) {
         if !self.inner.defined(b) {
@@ -297,21 +397,91 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
     }
 }
 
+// The history tree. This is only implemented for the Adams grading since that is what `Action`
+// acts on.
+impl SseqWrapper {
+    /// Records a user action in the history tree, if it is one that is recorded. This should be
+    /// called before the action is applied.
+    pub fn record(&mut self, action: &Action) {
+        if HistoryTree::records(action) {
+            self.history.push(action);
+        }
+    }
+
+    /// Moves to the node `target` of the history tree. If `target` is a descendant of the current
+    /// node, this only applies the actions in between. Otherwise we have to clear the spectral
+    /// sequence and replay everything from the root.
+    fn goto(&mut self, target: usize) {
+        let (clear, actions) = self.history.path_to(target);
+        if clear {
+            self.clear();
+            self.reset_class_names();
+        }
+        for action in &actions {
+            action.act_sseq(self);
+        }
+        self.history.set_current(target);
+        // Update the differentials so that the inconsistencies we report are up to date
+        self.refresh();
+    }
+
+    /// Undoes the last action of the current branch. Returns whether there was anything to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo_target() {
+            Some(target) => {
+                self.goto(target);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redoes the most recently undone action. Returns whether there was anything to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo_target() {
+            Some(target) => {
+                self.goto(target);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Creates a branch at the current state and switches to it.
+    pub fn branch(&mut self, name: &str) -> anyhow::Result<()> {
+        let inconsistent = self.inconsistent_degrees();
+        self.history.create_branch(name, inconsistent)
+    }
+
+    /// Switches to the branch `name`.
+    pub fn checkout(&mut self, name: &str) -> anyhow::Result<()> {
+        let inconsistent = self.inconsistent_degrees();
+        let target = self.history.checkout(name, inconsistent)?;
+        self.goto(target);
+        Ok(())
+    }
+
+    pub fn delete_branch(&mut self, name: &str) -> anyhow::Result<()> {
+        self.history.delete_branch(name)
+    }
+
+    /// The state of the history tree, as reported to the frontend.
+    pub fn history_state(&self) -> HistoryState {
+        HistoryState {
+            branch: self.history.branch().to_owned(),
+            branches: self.history.branches(self.inconsistent_degrees()),
+        }
+    }
+}
+
 // Functions called by SseqManager
 impl<P: SseqProfile<2>> SseqWrapper<P> {
     /// This function should only be called when everything to the left and bottom of (x, y)
     /// has been defined.
     pub fn set_dimension(&mut self, b: Bidegree, dim: usize) {
         self.inner.set_dimension(b, dim);
-        let mut names = Vec::with_capacity(dim);
-        if dim == 1 {
-            names.push(format!("x_{{{x},{y}}}", x = b.x(), y = b.y()));
-        } else {
-            names.extend(
-                (0..dim).map(|i| format!("x_{{{x}, {y}}}^{{({i})}}", x = b.x(), y = b.y())),
-            );
-        }
-        self.class_names.insert(b, names);
+        self.class_names
+            .insert(b, Self::default_class_names(b, dim));
         self.stale.insert(b, CLASS_FLAG);
     }
 
@@ -376,6 +546,13 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
         }
     }
 
+    /// Add a differential computed by the resolver, and propagate it along products.
+    pub fn add_computed_differential(&mut self, r: i32, source: BidegreeElement, target: FpVector) {
+        self.inner.add_differential(r, &source, target.as_slice());
+        self.add_differential_propagate(r, &source, 0);
+        self.computed_differentials.push((r, source, target));
+    }
+
     /// Add a product to the list of products, but don't add any computed product
     pub fn add_product_type(&mut self, name: &str, mult_b: Bidegree, left: bool, permanent: bool) {
         if let Some(product) = self.products.get_mut(name) {
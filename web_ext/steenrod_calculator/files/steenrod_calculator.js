@@ -8,9 +8,17 @@ worker.addEventListener('message', ev => {
         case 'error':
             elt.innerHTML = `<span style='color: red'>${ev.data.error}</span>`;
             break;
+        case 'margolis':
+            katex.render(ev.data.result, margolis_elt, { displayMode: true });
+            break;
+        case 'margolis-error':
+            margolis_elt.innerHTML = `<span style='color: red'>${ev.data.error}</span>`;
+            break;
     }
 });
 
+const margolis_elt = document.getElementById('margolis-result');
+
 function katexMathInDelims(string) {
     const html_list = string.split(
         /(?:\\\[)|(?:\\\()|(?:\\\))|(?:\\\])|(?:\$)/,
@@ -37,3 +45,16 @@ window.compute = () => {
         input: document.getElementById('calculator-input').value,
     });
 };
+
+window.computeMargolis = () => {
+    margolis_elt.innerHTML = '';
+
+    worker.postMessage({
+        cmd: 'margolis',
+        prime: Number.parseInt(
+            document.querySelector('input[name="prime"]:checked').value,
+        ),
+        module: document.getElementById('margolis-module').value,
+        input: document.getElementById('margolis-operator').value,
+    });
+};
@@ -21,6 +21,18 @@ self.onmessage = async ev => {
         self.calculators[m.prime] = SteenrodCalculator.new(m.prime);
     }
 
+    if (m.cmd === 'margolis') {
+        try {
+            self.postMessage({
+                cmd: 'margolis',
+                result: self.calculators[m.prime].margolis(m.module, m.input),
+            });
+        } catch (e) {
+            self.postMessage({ cmd: 'margolis-error', error: e });
+        }
+        return;
+    }
+
     try {
         let result;
         if (m.basis === 'adem') {
@@ -1,4 +1,13 @@
-use algebra::{steenrod_evaluator::SteenrodEvaluator, Algebra};
+use std::sync::Arc;
+
+use algebra::{
+    module::{
+        margolis::{margolis_homology, MargolisOperator},
+        FDModule,
+    },
+    steenrod_evaluator::SteenrodEvaluator,
+    Algebra, MilnorAlgebra, SteenrodAlgebra,
+};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -23,4 +32,48 @@ impl SteenrodCalculator {
             .map(|(d, v)| self.0.milnor.element_to_string(d, v.as_slice()))
             .map_err(|e| JsValue::from(e.to_string()))
     }
+
+    /// Computes the Margolis homology of a finite dimensional module, given in the json format of
+    /// `ext`, with respect to an operator such as `Q1` or `P^0_2`. The result is a LaTeX string.
+    pub fn margolis(&self, module: &str, operator: &str) -> Result<String, JsValue> {
+        let err = |e: &dyn std::fmt::Display| JsValue::from(e.to_string());
+
+        let p = self.0.milnor.prime();
+        let json: serde_json::Value = serde_json::from_str(module).map_err(|e| err(&e))?;
+        if json["p"]
+            .as_u64()
+            .is_none_or(|module_p| p != module_p as u32)
+        {
+            return Err(JsValue::from(format!(
+                "The module must be over the prime {p}"
+            )));
+        }
+        let operator: MargolisOperator = operator.parse().map_err(|e| err(&e))?;
+
+        let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(p, false)));
+        let module = FDModule::from_json(algebra, &json).map_err(|e| err(&e))?;
+        let homology = margolis_homology(&module, operator).map_err(|e| err(&e))?;
+
+        let mut summands = Vec::new();
+        for (degree, &dim) in homology.iter_enum() {
+            if dim == 0 {
+                continue;
+            }
+            let mut summand = String::new();
+            if degree != 0 {
+                summand += &format!("\\Sigma^{{{degree}}}");
+            }
+            summand += &format!("\\mathbb{{F}}_{{{p}}}");
+            if dim > 1 {
+                summand += &format!("^{{\\oplus {dim}}}");
+            }
+            summands.push(summand);
+        }
+        let homology = if summands.is_empty() {
+            String::from("0")
+        } else {
+            summands.join(" \\oplus ")
+        };
+        Ok(format!("H(M; {operator}) \\cong {homology}"))
+    }
 }